@@ -1,7 +1,21 @@
-use lsp_types::ServerCapabilities;
+use crate::lsp_ext::{MIGRATE_SYNTAX_COMMAND, RUN_TESTS_COMMAND};
+use lsp_types::{
+    CodeActionProviderCapability, CodeLensOptions, ExecuteCommandOptions, HoverProviderCapability, OneOf,
+    ServerCapabilities,
+};
 
 pub(crate) fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![MIGRATE_SYNTAX_COMMAND.into(), RUN_TESTS_COMMAND.into()],
+            work_done_progress_options: Default::default(),
+        }),
         ..Default::default()
     }
 }
@@ -1,3 +1,4 @@
+use ide::{AssistConfig, Target};
 use lsp_types::Url;
 use std::collections::HashSet;
 use std::path::PathBuf;
@@ -12,6 +13,11 @@ pub struct Config {
     pub diagnostics_ignored: HashSet<String>,
     pub formatting_command: Option<Vec<String>>,
     pub gleam_binary: PathBuf,
+    /// The target to analyze for, instead of the one in `gleam.toml`.
+    pub target: Option<Target>,
+    /// Extra directories of sources outside of any package, relative to the root.
+    pub include_dirs: Vec<PathBuf>,
+    pub assists: AssistConfig,
 }
 
 impl Config {
@@ -23,6 +29,9 @@ impl Config {
             diagnostics_ignored: HashSet::new(),
             formatting_command: None,
             gleam_binary: "gleam".into(),
+            target: None,
+            include_dirs: Vec::new(),
+            assists: AssistConfig::default(),
         }
     }
 
@@ -84,6 +93,53 @@ impl Config {
             }
         }
 
+        if let Some(v) = value.pointer_mut("/analysis/target") {
+            match serde_json::from_value::<Option<String>>(v.take()) {
+                Ok(None) => {
+                    self.target = None;
+                    updated_diagnostics = true;
+                }
+                Ok(Some(name)) => match Target::from_name(&name) {
+                    Some(target) => {
+                        self.target = Some(target);
+                        updated_diagnostics = true;
+                    }
+                    None => {
+                        errors.push(format!(
+                            "Invalid value of `analysis.target`: unknown target `{name}`"
+                        ));
+                    }
+                },
+                Err(e) => {
+                    errors.push(format!("Invalid value of `analysis.target`: {e}"));
+                }
+            }
+        }
+
+        if let Some(v) = value.pointer_mut("/analysis/includeDirs") {
+            match serde_json::from_value::<Vec<PathBuf>>(v.take()) {
+                Ok(v) => {
+                    self.include_dirs = v.into_iter().map(|dir| self.root_path.join(dir)).collect();
+                }
+                Err(e) => {
+                    errors.push(format!("Invalid value of `analysis.includeDirs`: {e}"));
+                }
+            }
+        }
+
+        if let Some(v) = value.pointer_mut("/assists/splitImportThreshold") {
+            match serde_json::from_value::<usize>(v.take()) {
+                Ok(n) => {
+                    self.assists.split_import_threshold = n;
+                }
+                Err(e) => {
+                    errors.push(format!(
+                        "Invalid value of `assists.splitImportThreshold`: {e}"
+                    ));
+                }
+            }
+        }
+
         (errors, updated_diagnostics)
     }
 }
@@ -1,10 +1,15 @@
+use crate::lsp_ext::RUN_TESTS_COMMAND;
 use crate::{LineMap, LspError, Result, Vfs};
-use ide::{Diagnostic, FileId, FilePos, FileRange, Severity};
+use ide::{
+    Assist, AssistKind, CodeLens, CodeLensKind, Diagnostic, FileId, FilePos, FileRange, HoverResult, Severity,
+    SourceChange, SymbolKind, WorkspaceSymbol,
+};
 use lsp_server::ErrorCode;
 use lsp_types::{
-    self as lsp, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
-    Position, PrepareRenameResponse, Range, TextDocumentIdentifier, TextDocumentPositionParams,
-    Url,
+    self as lsp, CodeAction, CodeActionKind, Command, DiagnosticRelatedInformation, DiagnosticSeverity,
+    DiagnosticTag, Hover, HoverContents, Location, MarkupContent, MarkupKind, NumberOrString,
+    OneOf, Position, PrepareRenameResponse, Range, TextDocumentIdentifier,
+    TextDocumentPositionParams, Url, WorkspaceEdit,
 };
 use std::sync::Arc;
 use text_size::{TextRange, TextSize};
@@ -82,6 +87,9 @@ pub(crate) fn to_diagnostics(
             },
             tags: {
                 let mut tags = Vec::new();
+                if diag.is_deprecated() {
+                    tags.push(DiagnosticTag::DEPRECATED);
+                }
                 Some(tags)
             },
             data: None,
@@ -116,6 +124,25 @@ pub(crate) fn to_diagnostics(
     ret
 }
 
+pub(crate) fn to_workspace_edit(vfs: &Vfs, change: SourceChange) -> WorkspaceEdit {
+    let changes = change
+        .file_edits
+        .into_iter()
+        .map(|(file, edits)| {
+            let uri = vfs.uri_for_file(file);
+            let line_map = vfs.line_map_for_file(file);
+            let edits = edits
+                .into_iter()
+                .map(|edit| {
+                    lsp::TextEdit::new(to_range(&line_map, edit.delete), edit.insert.into())
+                })
+                .collect();
+            (uri, edits)
+        })
+        .collect();
+    WorkspaceEdit::new(changes)
+}
+
 pub(crate) fn to_rename_error(message: String) -> LspError {
     LspError {
         code: ErrorCode::InvalidRequest,
@@ -134,3 +161,64 @@ pub(crate) fn to_prepare_rename_response(
         placeholder: text,
     }
 }
+
+pub(crate) fn to_workspace_symbol(vfs: &Vfs, sym: WorkspaceSymbol) -> lsp::WorkspaceSymbol {
+    let frange = FileRange::new(sym.nav.file_id, sym.nav.full_range);
+    lsp::WorkspaceSymbol {
+        name: sym.name,
+        kind: to_symbol_kind(sym.kind),
+        tags: None,
+        container_name: sym.container_name,
+        location: OneOf::Left(to_location(vfs, frange)),
+        data: None,
+    }
+}
+
+pub(crate) fn to_symbol_kind(kind: SymbolKind) -> lsp::SymbolKind {
+    match kind {
+        SymbolKind::Module => lsp::SymbolKind::MODULE,
+        SymbolKind::Function => lsp::SymbolKind::FUNCTION,
+        SymbolKind::Constant => lsp::SymbolKind::CONSTANT,
+        SymbolKind::Type => lsp::SymbolKind::STRUCT,
+    }
+}
+
+pub(crate) fn to_code_action(vfs: &Vfs, assist: Assist) -> CodeAction {
+    CodeAction {
+        title: assist.label,
+        kind: Some(match assist.kind {
+            AssistKind::QuickFix => CodeActionKind::QUICKFIX,
+            AssistKind::RefactorRewrite => CodeActionKind::REFACTOR_REWRITE,
+        }),
+        edit: Some(to_workspace_edit(vfs, assist.change)),
+        ..CodeAction::default()
+    }
+}
+
+pub(crate) fn to_code_lens(uri: &Url, line_map: &LineMap, lens: CodeLens) -> lsp::CodeLens {
+    let command = match lens.kind {
+        CodeLensKind::RunModuleTests { module, tests } => Command {
+            title: match tests {
+                1 => "Run 1 test".into(),
+                n => format!("Run {n} tests"),
+            },
+            command: RUN_TESTS_COMMAND.into(),
+            arguments: Some(vec![uri.as_str().into(), module.into()]),
+        },
+    };
+    lsp::CodeLens {
+        range: to_range(line_map, lens.range),
+        command: Some(command),
+        data: None,
+    }
+}
+
+pub(crate) fn to_hover(line_map: &LineMap, hover: HoverResult) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: hover.markup,
+        }),
+        range: Some(to_range(line_map, hover.range)),
+    }
+}
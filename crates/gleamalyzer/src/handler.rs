@@ -1,6 +1,10 @@
 use crate::{convert, StateSnapshot};
 use anyhow::Result;
-use lsp_types::{Diagnostic, Url};
+use ide::FileRange;
+use lsp_types::{
+    CodeActionOrCommand, CodeActionParams, CodeActionResponse, CodeLens, CodeLensParams, Diagnostic, Hover, HoverParams,
+    TextDocumentIdentifier, Url, WorkspaceEdit, WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
 
 const MAX_DIAGNOSTICS_CNT: usize = 128;
 
@@ -15,3 +19,74 @@ pub(crate) fn diagnostics(snap: StateSnapshot, uri: &Url) -> Result<Vec<Diagnost
     diags.truncate(MAX_DIAGNOSTICS_CNT);
     Ok(convert::to_diagnostics(uri, file, &line_map, &diags))
 }
+
+pub(crate) fn hover(snap: StateSnapshot, params: HoverParams) -> Result<Option<Hover>> {
+    let (fpos, line_map) =
+        convert::from_file_pos(&snap.vfs(), &params.text_document_position_params)?;
+    let ret = snap.analysis.hover(fpos)?;
+    Ok(ret.map(|ret| convert::to_hover(&line_map, ret)))
+}
+
+pub(crate) fn code_action(
+    snap: StateSnapshot,
+    params: CodeActionParams,
+) -> Result<Option<CodeActionResponse>> {
+    let (file, _) = convert::from_file(&snap.vfs(), &params.text_document)?;
+    let (_, range) = convert::from_range(&snap.vfs(), file, params.range)?;
+    let assists = snap
+        .analysis
+        .assists(FileRange::new(file, range), snap.config.assists)?;
+    let vfs = snap.vfs();
+    let actions = assists
+        .into_iter()
+        .map(|assist| CodeActionOrCommand::CodeAction(convert::to_code_action(&vfs, assist)))
+        .collect::<Vec<_>>();
+    Ok((!actions.is_empty()).then_some(actions))
+}
+
+pub(crate) fn code_lens(snap: StateSnapshot, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+    let (file, line_map) = convert::from_file(&snap.vfs(), &params.text_document)?;
+    let lenses = snap.analysis.code_lenses(file)?;
+    let uri = &params.text_document.uri;
+    Ok(Some(
+        lenses
+            .into_iter()
+            .map(|lens| convert::to_code_lens(uri, &line_map, lens))
+            .collect(),
+    ))
+}
+
+pub(crate) fn workspace_symbol(
+    snap: StateSnapshot,
+    params: WorkspaceSymbolParams,
+) -> Result<Option<WorkspaceSymbolResponse>> {
+    let symbols = snap.analysis.workspace_symbols(&params.query)?;
+    let vfs = snap.vfs();
+    let symbols = symbols
+        .into_iter()
+        .map(|sym| convert::to_workspace_symbol(&vfs, sym))
+        .collect();
+    Ok(Some(WorkspaceSymbolResponse::Nested(symbols)))
+}
+
+pub(crate) fn migrate_syntax(snap: StateSnapshot) -> Result<(WorkspaceEdit, String)> {
+    let migration = snap.analysis.migrate_syntax()?;
+    let mut summary = format!(
+        "Rewrote {} target group(s) in {} file(s).",
+        migration.target_groups,
+        migration.change.file_edits.len(),
+    );
+    if !migration.manual.is_empty() {
+        summary += &format!(
+            " {} use(s) of `try` or the old `assert` need manual migration.",
+            migration.manual.len(),
+        );
+    }
+    let vfs = snap.vfs();
+    Ok((convert::to_workspace_edit(&vfs, migration.change), summary))
+}
+
+pub(crate) fn module_docs(snap: StateSnapshot, params: TextDocumentIdentifier) -> Result<String> {
+    let (file, _) = convert::from_file(&snap.vfs(), &params)?;
+    Ok(snap.analysis.module_docs(file)?)
+}
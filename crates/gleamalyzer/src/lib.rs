@@ -2,7 +2,11 @@ mod capabilities;
 mod config;
 mod convert;
 mod handler;
+mod lsp_ext;
+mod packages;
+mod project;
 mod server;
+mod test_runner;
 mod vfs;
 
 use anyhow::Result;
@@ -0,0 +1,27 @@
+use lsp_types::request::Request;
+use lsp_types::TextDocumentIdentifier;
+
+/// Rewrite removed or deprecated syntax in all workspace files.
+pub(crate) const MIGRATE_SYNTAX_COMMAND: &str = "gleamalyzer.migrateSyntax";
+
+/// Run the tests of a module with `gleam test`, given the URI of its file and its module name.
+pub(crate) const RUN_TESTS_COMMAND: &str = "gleamalyzer.runTests";
+
+/// Non-standard requests served by gleamalyzer.
+pub(crate) enum Profile {}
+
+impl Request for Profile {
+    type Params = ();
+    /// The rendered timing breakdown of the last profiled request, if profiling is enabled.
+    type Result = Option<String>;
+    const METHOD: &'static str = "gleamalyzer/profile";
+}
+
+pub(crate) enum ModuleDocs {}
+
+impl Request for ModuleDocs {
+    type Params = TextDocumentIdentifier;
+    /// The documentation of the module and its public items, in markdown.
+    type Result = String;
+    const METHOD: &'static str = "gleamalyzer/moduleDocs";
+}
@@ -0,0 +1,135 @@
+//! Discovery of the dependency packages `gleam` unpacks under `build/packages`.
+use crate::{project, MAX_FILE_LEN};
+use anyhow::{Context, Result};
+use ide::PackageInfo;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Where `gleam deps download` unpacks dependencies, relative to the project root.
+pub const PACKAGES_DIR: &str = "build/packages";
+
+#[derive(Debug)]
+pub struct Package {
+    pub name: String,
+    /// The directory it is unpacked to.
+    pub root: PathBuf,
+    /// `None` if its `gleam.toml` is missing or invalid.
+    pub config: Option<PackageInfo>,
+    /// The `.gleam` files under `src` with their content.
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Load the sources of all dependencies of the project at `root`. The standard library is a
+/// dependency like any other. Dependencies which are not downloaded yet are no error, there
+/// are just no packages.
+pub fn load_packages(root: &Path) -> Result<Vec<Package>> {
+    let dir = root.join(PACKAGES_DIR);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let mut packages = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let config = project::load_config(&entry.path())
+            .map_err(|err| tracing::warn!("Failed to load package {name}: {err:#}"))
+            .ok();
+        let mut files = Vec::new();
+        collect_sources(&entry.path().join("src"), &mut files)?;
+        packages.push(Package {
+            name,
+            root: entry.path(),
+            config,
+            files,
+        });
+    }
+    packages.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    Ok(packages)
+}
+
+/// Read the `.gleam` files below `dir`, if it exists.
+pub fn collect_sources(dir: &Path, files: &mut Vec<(PathBuf, String)>) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let typ = entry.file_type()?;
+        if typ.is_dir() {
+            collect_sources(&path, files)?;
+        } else if typ.is_file() && path.extension().map_or(false, |ext| ext == "gleam") {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            if text.len() > MAX_FILE_LEN {
+                tracing::warn!("Skip too large file {}", path.display());
+                continue;
+            }
+            files.push((path, text));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_packages, PACKAGES_DIR};
+    use std::fs;
+
+    #[test]
+    fn load() {
+        let root =
+            std::env::temp_dir().join(format!("gleamalyzer-packages-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        assert!(load_packages(&root).unwrap().is_empty());
+
+        let stdlib = root.join(PACKAGES_DIR).join("gleam_stdlib");
+        fs::create_dir_all(stdlib.join("src/gleam")).unwrap();
+        fs::write(stdlib.join("src/gleam/list.gleam"), "pub fn map() { 1 }").unwrap();
+        fs::write(stdlib.join("src/gleam_stdlib.mjs"), "").unwrap();
+        fs::write(stdlib.join("gleam.toml"), "name = \"gleam_stdlib\"").unwrap();
+        fs::create_dir_all(root.join(PACKAGES_DIR).join("empty")).unwrap();
+
+        let packages = load_packages(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        let got = packages
+            .iter()
+            .map(|pkg| {
+                let files = pkg
+                    .files
+                    .iter()
+                    .map(|(path, _)| path.strip_prefix(&root).unwrap().display().to_string())
+                    .collect::<Vec<_>>();
+                (pkg.name.as_str(), files)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            packages[1]
+                .config
+                .as_ref()
+                .map(|config| config.name.as_str()),
+            Some("gleam_stdlib")
+        );
+        assert_eq!(
+            got,
+            [
+                ("empty", vec![]),
+                (
+                    "gleam_stdlib",
+                    vec!["build/packages/gleam_stdlib/src/gleam/list.gleam".to_owned()]
+                ),
+            ]
+        );
+    }
+}
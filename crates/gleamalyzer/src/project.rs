@@ -0,0 +1,277 @@
+//! The packages of the workspace, described by their `gleam.toml`.
+use crate::packages::{self, Package};
+use anyhow::{Context, Result};
+use ide::PackageInfo;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const GLEAM_FILE: &str = "gleam.toml";
+
+/// The lock file of dependency versions, rewritten when dependencies are downloaded.
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// Directories which never contain packages of the workspace.
+const SKIPPED_DIRS: &[&str] = &["build", "node_modules"];
+
+/// The packages of a workspace and the dependencies of all of them.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub packages: Vec<PackageInfo>,
+    pub dependencies: Vec<Package>,
+    /// Editable files read from the disk, since they may be imported before they are opened.
+    pub sources: Vec<(PathBuf, String)>,
+    /// Directories of sources outside of any package, each a source root of its own.
+    pub include_dirs: Vec<PathBuf>,
+}
+
+/// Find the packages at or below `root`, as in a monorepo, and load their dependencies. A
+/// dependency of several packages is loaded once. Dependencies given by path are packages of
+/// the workspace too, wherever they are.
+pub fn load_workspace(root: &Path) -> Result<Workspace> {
+    let mut workspace = Workspace::default();
+    discover_packages(root, &mut workspace.packages)?;
+    let mut loaded_paths = HashSet::new();
+    // Path dependencies may add more packages with path dependencies.
+    let mut i = 0;
+    while let Some(package) = workspace.packages.get(i) {
+        i += 1;
+        for dep in packages::load_packages(&package.root)? {
+            if !workspace.dependencies.iter().any(|d| d.name == dep.name) {
+                workspace.dependencies.push(dep);
+            }
+        }
+        for dir in package.path_dependencies.clone() {
+            if !loaded_paths.insert(dir.clone()) {
+                continue;
+            }
+            if !workspace.packages.iter().any(|p| p.root == dir) {
+                workspace.packages.push(load_config(&dir)?);
+            }
+            packages::collect_sources(&dir.join("src"), &mut workspace.sources)?;
+        }
+    }
+    Ok(workspace)
+}
+
+/// Load the sources of the extra directories of the `analysis.includeDirs` setting, for layouts
+/// `gleam.toml` cannot describe.
+pub fn load_include_dirs(dirs: &[PathBuf]) -> Result<Workspace> {
+    let mut workspace = Workspace::default();
+    for dir in dirs {
+        packages::collect_sources(dir, &mut workspace.sources)?;
+        workspace.include_dirs.push(dir.clone());
+    }
+    Ok(workspace)
+}
+
+fn discover_packages(dir: &Path, packages: &mut Vec<PackageInfo>) -> Result<()> {
+    if dir.join(GLEAM_FILE).is_file() {
+        packages.push(load_config(dir)?);
+    }
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else { continue };
+        if !entry.file_type()?.is_dir() || name.starts_with('.') || SKIPPED_DIRS.contains(&name) {
+            continue;
+        }
+        discover_packages(&entry.path(), packages)?;
+    }
+    Ok(())
+}
+
+/// The closest directory containing a `gleam.toml` enclosing `path`.
+pub fn find_package_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join(GLEAM_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Load the `gleam.toml` of the package at `root`.
+pub fn load_config(root: &Path) -> Result<PackageInfo> {
+    let path = root.join(GLEAM_FILE);
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_config(root.to_path_buf(), &text).with_context(|| format!("In {}", path.display()))
+}
+
+pub fn parse_config(root: PathBuf, text: &str) -> Result<PackageInfo> {
+    let table = text.parse::<toml::Table>().context("Invalid TOML")?;
+    let name = table
+        .get("name")
+        .and_then(|v| v.as_str())
+        .context("Missing string `name`")?
+        .to_owned();
+    let target = match table.get("target") {
+        None => None,
+        Some(v) => Some(v.as_str().context("`target` must be a string")?.to_owned()),
+    };
+    let gleam_version = match table.get("gleam") {
+        None => None,
+        Some(v) => Some(v.as_str().context("`gleam` must be a string")?.to_owned()),
+    };
+    let dep_tables = ["dependencies", "dev-dependencies"]
+        .iter()
+        .filter_map(|key| table.get(*key)?.as_table())
+        .collect::<Vec<_>>();
+    let mut dependencies = dep_tables
+        .iter()
+        .flat_map(|deps| deps.keys().cloned())
+        .collect::<Vec<_>>();
+    dependencies.sort();
+    dependencies.dedup();
+    let mut path_dependencies = Vec::new();
+    for (name, dep) in dep_tables.iter().flat_map(|deps| deps.iter()) {
+        let Some(path) = dep.as_table().and_then(|dep| dep.get("path")) else {
+            continue;
+        };
+        let path = path
+            .as_str()
+            .with_context(|| format!("`path` of dependency `{name}` must be a string"))?;
+        path_dependencies.push(normalize(&root.join(path)));
+    }
+    Ok(PackageInfo {
+        name,
+        root,
+        target,
+        gleam_version,
+        dependencies,
+        path_dependencies,
+    })
+}
+
+/// Resolve `.` and `..` without accessing the disk, since paths from the client are not
+/// canonicalized either.
+fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                ret.pop();
+            }
+            comp => ret.push(comp),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_package_root, load_include_dirs, load_workspace, parse_config, GLEAM_FILE};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse() {
+        let config = parse_config(
+            "/app".into(),
+            r#"
+name = "app"
+version = "1.0.0"
+target = "javascript"
+gleam = ">= 1.4.0"
+
+[dependencies]
+gleam_stdlib = "~> 0.30"
+gleam_json = "~> 0.5"
+
+[dev-dependencies]
+gleeunit = "~> 0.10"
+local = { path = "../libs/./local" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.name, "app");
+        assert_eq!(config.root, PathBuf::from("/app"));
+        assert_eq!(config.target.as_deref(), Some("javascript"));
+        assert_eq!(config.gleam_version.as_deref(), Some(">= 1.4.0"));
+        assert_eq!(
+            config.dependencies,
+            ["gleam_json", "gleam_stdlib", "gleeunit", "local"]
+        );
+        assert_eq!(config.path_dependencies, [PathBuf::from("/libs/local")]);
+
+        let config = parse_config("/lib".into(), "name = \"lib\"").unwrap();
+        assert_eq!(config.target, None);
+        assert_eq!(config.gleam_version, None);
+        assert!(config.dependencies.is_empty());
+
+        assert!(parse_config("/x".into(), "version = \"1.0.0\"").is_err());
+        assert!(parse_config("/x".into(), "name = \"x\"\ntarget = 1").is_err());
+        assert!(parse_config("/x".into(), "name = \"x\"\n[dependencies]\ny = { path = 1 }").is_err());
+    }
+
+    #[test]
+    fn monorepo() {
+        let root = std::env::temp_dir().join(format!("gleamalyzer-project-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (dir, name) in [("apps/web", "web"), ("libs/core", "core")] {
+            fs::create_dir_all(root.join(dir).join("src")).unwrap();
+            let config = format!("name = \"{name}\"\n[dependencies]\ngleam_stdlib = \"1.0\"");
+            fs::write(root.join(dir).join(GLEAM_FILE), config).unwrap();
+            let stdlib = root.join(dir).join("build/packages/gleam_stdlib/src");
+            fs::create_dir_all(&stdlib).unwrap();
+            fs::write(stdlib.join("list.gleam"), "").unwrap();
+        }
+        // Packages of dependencies are not part of the workspace.
+        fs::write(
+            root.join("apps/web/build/packages/gleam_stdlib")
+                .join(GLEAM_FILE),
+            "name = \"gleam_stdlib\"",
+        )
+        .unwrap();
+
+        let workspace = load_workspace(&root).unwrap();
+        let found = find_package_root(&root.join("libs/core/src/core/x.gleam"));
+        fs::remove_dir_all(&root).unwrap();
+
+        let mut names = workspace
+            .packages
+            .iter()
+            .map(|pkg| pkg.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["core", "web"]);
+        assert_eq!(workspace.dependencies.len(), 1);
+        assert_eq!(found, Some(root.join("libs/core")));
+    }
+
+    #[test]
+    fn path_dependency() {
+        let root = std::env::temp_dir().join(format!("gleamalyzer-path-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(
+            root.join("app").join(GLEAM_FILE),
+            "name = \"app\"\n[dependencies]\nlocal = { path = \"../local\" }",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("local/src")).unwrap();
+        fs::write(root.join("local").join(GLEAM_FILE), "name = \"local\"").unwrap();
+        fs::write(root.join("local/src/local.gleam"), "pub fn f() { 1 }").unwrap();
+        fs::create_dir_all(root.join("extra/nested")).unwrap();
+        fs::write(root.join("extra/nested/x.gleam"), "").unwrap();
+
+        let workspace = load_workspace(&root.join("app")).unwrap();
+        let include = load_include_dirs(&[root.join("extra")]).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let names = workspace
+            .packages
+            .iter()
+            .map(|pkg| pkg.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["app", "local"]);
+        assert_eq!(
+            workspace.sources,
+            [(root.join("local/src/local.gleam"), "pub fn f() { 1 }".to_owned())]
+        );
+        assert_eq!(include.include_dirs, [root.join("extra")]);
+        assert_eq!(include.sources.len(), 1);
+        assert_eq!(include.sources[0].0, root.join("extra/nested/x.gleam"));
+    }
+}
@@ -1,26 +1,30 @@
 use crate::config::{Config, CONFIG_KEY};
-use crate::{convert, handler, LspError, UrlExt, Vfs, MAX_FILE_LEN};
+use crate::project::{self, Workspace};
+use crate::test_runner::{self, TestReport};
+use crate::{convert, handler, lsp_ext, LspError, UrlExt, Vfs, MAX_FILE_LEN};
 use anyhow::{anyhow, bail, Context, Result};
 use crossbeam_channel::{Receiver, Sender};
-use ide::{Analysis, AnalysisHost, Cancelled, ModuleInfo, VfsPath};
+use ide::{Analysis, AnalysisHost, Cancelled, Change, ModuleInfo};
 use lsp_server::{ErrorCode, Message, Notification, ReqQueue, Request, RequestId, Response};
 use lsp_types::notification::Notification as _;
 use lsp_types::{
-    notification as notif, request as req, ConfigurationItem, ConfigurationParams, Diagnostic,
-    InitializeParams, MessageType, NumberOrString, PublishDiagnosticsParams, ShowMessageParams,
-    Url,
+    notification as notif, request as req, ApplyWorkspaceEditParams, ConfigurationItem,
+    ConfigurationParams, Diagnostic, DidChangeWatchedFilesRegistrationOptions, FileSystemWatcher,
+    GlobPattern, InitializeParams, MessageType, NumberOrString,
+    ProgressParams, ProgressParamsValue, PublishDiagnosticsParams, Registration,
+    RegistrationParams, ShowMessageParams, Url,
+    WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkspaceEdit,
 };
 use std::backtrace::Backtrace;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::ErrorKind;
 use std::panic::UnwindSafe;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Once, RwLock};
 use std::{fs, panic, thread};
 
-pub const GLEAM_FILE: &str = "gleam.toml";
-
 type ReqHandler = Box<dyn FnOnce(&mut Server, Response) + 'static>;
 
 type Task = Box<dyn FnOnce() -> Event + Send + 'static>;
@@ -34,6 +38,18 @@ enum Event {
     },
     ClientExited,
     LoadFlake(Result<LoadModuleResult>),
+    LoadWorkspace(Result<Workspace>),
+    ReloadWorkspace {
+        root: PathBuf,
+        result: Result<Workspace>,
+    },
+    MigrateSyntax(Result<(WorkspaceEdit, String)>),
+    RunTests {
+        module: String,
+        /// The progress to end, if the client supports it.
+        token: Option<NumberOrString>,
+        result: Result<TestReport>,
+    },
 }
 
 struct LoadModuleResult {
@@ -47,6 +63,8 @@ pub struct Server {
     host: AnalysisHost,
     vfs: Arc<RwLock<Vfs>>,
     opened_files: HashMap<Url, FileData>,
+    /// The directories whose packages are loaded or being loaded.
+    workspace_roots: HashSet<PathBuf>,
     config: Arc<Config>,
     is_shutdown: bool,
     /// Monotonic version counter for diagnostics calculation ordering.
@@ -85,6 +103,7 @@ impl Server {
             host: AnalysisHost::default(),
             vfs: Arc::new(RwLock::new(Vfs::new())),
             opened_files: HashMap::default(),
+            workspace_roots: HashSet::default(),
             config: Arc::new(Config::new(root_path)),
             is_shutdown: false,
             version_counter: 0,
@@ -149,6 +168,16 @@ impl Server {
             });
         }
 
+        self.load_workspace(self.config.root_path.clone());
+        let can_watch = init_params
+            .capabilities
+            .workspace
+            .and_then(|ws| ws.did_change_watched_files?.dynamic_registration)
+            .unwrap_or(false);
+        if can_watch {
+            self.register_watchers();
+        }
+
         loop {
             crossbeam_channel::select! {
                 recv(lsp_rx) -> msg => {
@@ -217,6 +246,109 @@ impl Server {
                     self.apply_vfs_change();
                 }
             },
+            Event::LoadWorkspace(ret) => match ret {
+                Err(err) => {
+                    self.show_message(
+                        MessageType::ERROR,
+                        format!("Failed to load workspace: {err:#}"),
+                    );
+                }
+                Ok(workspace) => {
+                    tracing::info!(
+                        "Loaded {} packages with {} dependencies",
+                        workspace.packages.len(),
+                        workspace.dependencies.len(),
+                    );
+                    self.vfs.write().unwrap().add_workspace(workspace);
+                    self.apply_vfs_change();
+                }
+            },
+            Event::ReloadWorkspace { root, result } => match result {
+                Err(err) => {
+                    self.show_message(
+                        MessageType::ERROR,
+                        format!("Failed to reload {}: {err:#}", root.display()),
+                    );
+                }
+                Ok(workspace) => {
+                    tracing::info!("Reloaded {}", root.display());
+                    self.vfs.write().unwrap().reload_workspace(&root, workspace);
+                    self.apply_vfs_change();
+                    // Targets and dependencies affect files which did not change.
+                    let version = self.next_version();
+                    for uri in self.opened_files.keys() {
+                        self.update_diagnostics(uri.clone(), version);
+                    }
+                }
+            },
+            Event::MigrateSyntax(ret) => match ret {
+                Err(err) => {
+                    self.show_message(
+                        MessageType::ERROR,
+                        format!("Failed to migrate syntax: {err:#}"),
+                    );
+                }
+                Ok((edit, summary)) if edit.changes.as_ref().map_or(true, |c| c.is_empty()) => {
+                    self.show_message(MessageType::INFO, summary);
+                }
+                Ok((edit, summary)) => {
+                    let params = ApplyWorkspaceEditParams {
+                        label: Some("Migrate syntax".into()),
+                        edit,
+                    };
+                    self.send_request::<req::ApplyWorkspaceEdit>(params, move |st, resp| {
+                        match resp {
+                            Ok(resp) if resp.applied => st.show_message(MessageType::INFO, summary),
+                            Ok(resp) => st.show_message(
+                                MessageType::ERROR,
+                                format!(
+                                    "Failed to apply syntax migration: {}",
+                                    resp.failure_reason.unwrap_or_default(),
+                                ),
+                            ),
+                            Err(err) => st.show_message(
+                                MessageType::ERROR,
+                                format!("Failed to apply syntax migration: {err:#}"),
+                            ),
+                        }
+                    });
+                }
+            },
+            Event::RunTests {
+                module,
+                token,
+                result,
+            } => {
+                let (typ, message) = match result {
+                    Ok(report) => (
+                        if report.failed == 0 {
+                            MessageType::INFO
+                        } else {
+                            MessageType::WARNING
+                        },
+                        format!(
+                            "{module}: {} passed, {} failed",
+                            report.passed, report.failed
+                        ),
+                    ),
+                    Err(err) => (
+                        MessageType::ERROR,
+                        format!("Failed to run tests of {module}: {err:#}"),
+                    ),
+                };
+                match token {
+                    // Errors are shown anyway, since the end of a progress is easy to miss.
+                    Some(token) => {
+                        self.send_progress(token, WorkDoneProgress::End(WorkDoneProgressEnd {
+                            message: Some(message.clone()),
+                        }));
+                        if typ == MessageType::ERROR {
+                            self.show_message(typ, message);
+                        }
+                    }
+                    None => self.show_message(typ, message),
+                }
+            }
         }
         Ok(())
     }
@@ -237,6 +369,38 @@ impl Server {
                 st.is_shutdown = true;
                 Ok(())
             })
+            // Served synchronously without a span, or it would report itself.
+            .on_sync_mut::<lsp_ext::Profile>(|_st, ()| {
+                Ok(ide::profile::last_profile().map(|frame| frame.to_string()))
+            })
+            .on_sync_mut::<req::ExecuteCommand>(|st, params| {
+                match params.command.as_str() {
+                    // The edit is computed in background and applied via `workspace/applyEdit`.
+                    lsp_ext::MIGRATE_SYNTAX_COMMAND => st.migrate_syntax(),
+                    // The results are reported via progress notifications.
+                    lsp_ext::RUN_TESTS_COMMAND => {
+                        let (uri, module) = serde_json::from_value(params.arguments.into())
+                            .map_err(|err| LspError {
+                                code: ErrorCode::InvalidParams,
+                                message: format!("Invalid arguments: {err}"),
+                            })?;
+                        st.run_tests(uri, module)?;
+                    }
+                    _ => {
+                        return Err(LspError {
+                            code: ErrorCode::InvalidParams,
+                            message: format!("Unknown command: {}", params.command),
+                        }
+                        .into())
+                    }
+                }
+                Ok(None)
+            })
+            .on::<req::HoverRequest>(handler::hover)
+            .on::<req::CodeActionRequest>(handler::code_action)
+            .on::<req::CodeLensRequest>(handler::code_lens)
+            .on::<req::WorkspaceSymbolRequest>(handler::workspace_symbol)
+            .on::<lsp_ext::ModuleDocs>(handler::module_docs)
             .finish();
     }
 
@@ -273,8 +437,11 @@ impl Server {
             .on_sync_mut::<notif::DidChangeTextDocument>(|st, params| {
                 let mut vfs = st.vfs.write().unwrap();
                 let uri = &params.text_document.uri;
-                // Ignore files not maintained in Vfs.
+                // Ignore files not maintained in Vfs, and read-only dependencies.
                 let Ok(file) = vfs.file_for_uri(uri) else { return };
+                if vfs.is_dependency_file(file) {
+                    return;
+                }
                 for change in params.content_changes {
                     let ret = (|| {
                         let del_range = match change.range {
@@ -303,10 +470,27 @@ impl Server {
             .on_sync_mut::<notif::DidChangeConfiguration>(|st, _params| {
                 st.load_config(|_| {});
             })
-            // Workaround:
-            // > In former implementations clients pushed file events without the server actively asking for it.
-            // Ref: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#workspace_didChangeWatchedFiles
-            .on_sync_mut::<notif::DidChangeWatchedFiles>(|_st, _params| {})
+            // Only `gleam.toml` and `manifest.toml` matter, as asked for in `register_watchers`.
+            // Events of other files, which some clients push without being asked, are ignored.
+            .on_sync_mut::<notif::DidChangeWatchedFiles>(|st, params| {
+                let mut roots = params
+                    .changes
+                    .iter()
+                    .filter_map(|change| {
+                        let path = change.uri.to_file_path().ok()?;
+                        let name = path.file_name()?;
+                        if name != project::GLEAM_FILE && name != project::MANIFEST_FILE {
+                            return None;
+                        }
+                        Some(path.parent()?.to_path_buf())
+                    })
+                    .collect::<Vec<_>>();
+                roots.sort();
+                roots.dedup();
+                for root in roots {
+                    st.reload_workspace(root);
+                }
+            })
             .finish();
     }
 
@@ -375,6 +559,17 @@ impl Server {
         let mut config = Config::clone(&self.config);
         let (errors, updated_diagnostics) = config.update(value);
         tracing::debug!("Updated config, errors: {errors:?}, config: {config:?}");
+        if config.target != self.config.target {
+            let mut change = Change::default();
+            change.set_active_target(config.target);
+            self.host.apply_change(change);
+        }
+        // Directories removed from the setting stay loaded until restart.
+        if config.include_dirs != self.config.include_dirs {
+            let dirs = config.include_dirs.clone();
+            let task = move || Event::LoadWorkspace(project::load_include_dirs(&dirs));
+            self.task_tx.send(Box::new(task)).unwrap();
+        }
         self.config = Arc::new(config);
 
         if !errors.is_empty() {
@@ -398,6 +593,7 @@ impl Server {
     fn update_diagnostics(&self, uri: Url, version: u64) {
         let snap = self.snapshot();
         let task = move || {
+            let _p = ide::profile::span("publish_diagnostics");
             // Return empty diagnostics for ignored files.
             let diagnostics = (!snap.config.diagnostics_excluded_files.contains(&uri))
                 .then(|| {
@@ -417,6 +613,107 @@ impl Server {
         self.task_tx.send(Box::new(task)).unwrap();
     }
 
+    /// Load the packages at or below `root` and the sources of their dependencies in
+    /// background. Each directory is only read once, since dependencies do not change unless
+    /// they are downloaded again.
+    fn load_workspace(&mut self, root: PathBuf) {
+        if !self.workspace_roots.insert(root.clone()) {
+            return;
+        }
+        let task = move || Event::LoadWorkspace(project::load_workspace(&root));
+        self.task_tx.send(Box::new(task)).unwrap();
+    }
+
+    /// Load the packages at or below `root` again, after their configuration or dependencies
+    /// changed.
+    fn reload_workspace(&mut self, root: PathBuf) {
+        self.workspace_roots.insert(root.clone());
+        let task = move || {
+            let result = project::load_workspace(&root);
+            Event::ReloadWorkspace { root, result }
+        };
+        self.task_tx.send(Box::new(task)).unwrap();
+    }
+
+    /// Ask the client to report changes of package configurations, which are rarely opened.
+    fn register_watchers(&mut self) {
+        let watchers = [project::GLEAM_FILE, project::MANIFEST_FILE]
+            .iter()
+            .map(|name| FileSystemWatcher {
+                glob_pattern: GlobPattern::String(format!("**/{name}")),
+                kind: None,
+            })
+            .collect();
+        let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+        let params = RegistrationParams {
+            registrations: vec![Registration {
+                id: notif::DidChangeWatchedFiles::METHOD.into(),
+                method: notif::DidChangeWatchedFiles::METHOD.into(),
+                register_options: Some(serde_json::to_value(options).unwrap()),
+            }],
+        };
+        self.send_request::<req::RegisterCapability>(params, |_st, resp| {
+            if let Err(err) = resp {
+                tracing::error!("Failed to watch package configurations: {err:#}");
+            }
+        });
+    }
+
+    fn migrate_syntax(&self) {
+        let snap = self.snapshot();
+        let task = move || {
+            Event::MigrateSyntax(with_catch_unwind("migrate_syntax", || {
+                handler::migrate_syntax(snap)
+            }))
+        };
+        self.task_tx.send(Box::new(task)).unwrap();
+    }
+
+    /// Run the tests of `module` in background, reporting the progress if the client supports
+    /// it.
+    fn run_tests(&mut self, uri: Url, module: String) -> Result<()> {
+        let root = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| project::find_package_root(&path))
+            .with_context(|| format!("No package found for {uri}"))?;
+        let token = NumberOrString::String(format!("gleamalyzer/runTests/{}", self.next_version()));
+        let params = WorkDoneProgressCreateParams {
+            token: token.clone(),
+        };
+        let gleam_binary = self.config.gleam_binary.clone();
+        self.send_request::<req::WorkDoneProgressCreate>(params, move |st, resp| {
+            let token = resp.ok().map(|()| token);
+            if let Some(token) = &token {
+                st.send_progress(
+                    token.clone(),
+                    WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                        title: "Running tests".into(),
+                        message: Some(module.clone()),
+                        ..WorkDoneProgressBegin::default()
+                    }),
+                );
+            }
+            let task = move || {
+                let result = test_runner::run_module_tests(&gleam_binary, &root, &module);
+                Event::RunTests {
+                    module,
+                    token,
+                    result,
+                }
+            };
+            st.task_tx.send(Box::new(task)).unwrap();
+        });
+        Ok(())
+    }
+
+    fn send_progress(&self, token: NumberOrString, progress: WorkDoneProgress) {
+        self.send_notification::<notif::Progress>(ProgressParams {
+            token,
+            value: ProgressParamsValue::WorkDone(progress),
+        });
+    }
+
     fn next_version(&mut self) -> u64 {
         self.version_counter += 1;
         self.version_counter
@@ -432,6 +729,18 @@ impl Server {
 
     fn set_vfs_file_content(&mut self, uri: &Url, text: String) {
         let vpath = uri.to_vfs_path();
+        // Files of packages outside of the workspace folder.
+        if let Some(path) = vpath.as_path() {
+            if !self
+                .workspace_roots
+                .iter()
+                .any(|root| path.starts_with(root))
+            {
+                if let Some(root) = project::find_package_root(path) {
+                    self.load_workspace(root);
+                }
+            }
+        }
         self.vfs.write().unwrap().set_path_content(vpath, text);
         self.apply_vfs_change();
     }
@@ -502,6 +811,7 @@ impl<'s> RequestDispatcher<'s> {
             let snap = self.0.snapshot();
             self.0.req_queue.incoming.register(req.id.clone(), ());
             let task = move || {
+                let _p = ide::profile::request_span(R::METHOD);
                 let ret = with_catch_unwind(R::METHOD, || {
                     let params = serde_json::from_value::<R::Params>(req.params)?;
                     let resp = f(snap, params)?;
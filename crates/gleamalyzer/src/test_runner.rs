@@ -0,0 +1,95 @@
+//! Running the tests of a module with `gleam test`.
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+/// Run `gleam test` with the `gleam` executable at `gleam_binary` in the package at `root`. The
+/// module name is passed to the test runner after `--`, which it uses to only run the tests of
+/// that module.
+pub fn run_module_tests(gleam_binary: &Path, root: &Path, module: &str) -> Result<TestReport> {
+    let output = Command::new(gleam_binary)
+        .args(["test", "--", module])
+        .current_dir(root)
+        .output()
+        .context("Failed to run `gleam test`")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if let Some(report) = parse_summary(&stdout) {
+        return Ok(report);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr.lines().rev().find(|line| !line.trim().is_empty());
+    bail!(
+        "`gleam test` exited with {} without a summary: {}",
+        output.status,
+        reason.unwrap_or_default().trim(),
+    )
+}
+
+/// Parse the final summary line of the test runner, like `12 tests, 1 failures` or
+/// `12 tests, no failures`.
+fn parse_summary(output: &str) -> Option<TestReport> {
+    output.lines().rev().find_map(|line| {
+        let line = strip_ansi(line);
+        let words = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        let count_of = |names: &[&str]| {
+            let i = words.iter().position(|word| names.contains(word))?;
+            match *words.get(i.checked_sub(1)?)? {
+                "no" => Some(0),
+                count => count.parse::<usize>().ok(),
+            }
+        };
+        let tests = count_of(&["test", "tests"])?;
+        let failed = count_of(&["failure", "failures"])?;
+        Some(TestReport {
+            passed: tests.saturating_sub(failed),
+            failed,
+        })
+    })
+}
+
+/// Remove the terminal color codes of `gleam test` output.
+fn strip_ansi(line: &str) -> String {
+    let mut ret = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            ret.push(c);
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_summary, TestReport};
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            parse_summary("Compiled in 0.1s\n....F\nFinished in 0.02 seconds\n5 tests, 1 failures\n"),
+            Some(TestReport {
+                passed: 4,
+                failed: 1
+            }),
+        );
+        assert_eq!(
+            parse_summary("..\n\x1b[32m2 tests, no failures\x1b[39m\n"),
+            Some(TestReport {
+                passed: 2,
+                failed: 0
+            }),
+        );
+        assert_eq!(parse_summary("error: Unknown module\n"), None);
+    }
+}
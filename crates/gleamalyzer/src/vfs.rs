@@ -1,8 +1,13 @@
+use crate::project::Workspace;
 use crate::UrlExt;
 use anyhow::{ensure, Context, Result};
-use ide::{Change, FileId, FileSet, ModuleGraph, ModuleInfo, SourceRoot, SourceRootId, VfsPath};
+use ide::{
+    Change, FileId, FileSet, LineColUtf16, LineIndex, ModuleGraph, ModuleInfo, PackageInfo,
+    SourceRoot, SourceRootId, VfsPath,
+};
 use lsp_types::Url;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{fmt, mem};
 use text_size::{TextRange, TextSize};
@@ -14,10 +19,25 @@ pub struct Vfs {
     // FIXME: Currently this list is append-only.
     files: Vec<(Arc<str>, Arc<LineMap>)>,
     local_file_set: FileSet,
+    /// The packages of the workspace. Local files are split into one source root for each.
+    workspace: Vec<PackageInfo>,
+    /// Directories of local files outside of packages, each one source root too.
+    include_dirs: Vec<PathBuf>,
+    dependencies: Vec<DependencyRoot>,
+    /// The index in `dependencies` of each dependency file.
+    dependency_of_file: HashMap<FileId, usize>,
     root_changed: bool,
     change: Change,
 }
 
+/// The read-only files of a dependency package.
+struct DependencyRoot {
+    name: String,
+    root: PathBuf,
+    config: Option<PackageInfo>,
+    file_set: FileSet,
+}
+
 impl fmt::Debug for Vfs {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Vfs")
@@ -33,6 +53,10 @@ impl Vfs {
         Self {
             files: Vec::new(),
             local_file_set: FileSet::default(),
+            workspace: Vec::new(),
+            include_dirs: Vec::new(),
+            dependencies: Vec::new(),
+            dependency_of_file: HashMap::new(),
             root_changed: false,
             change: Change::default(),
         }
@@ -45,6 +69,10 @@ impl Vfs {
     }
 
     pub fn set_path_content(&mut self, path: VfsPath, text: String) -> FileId {
+        // Dependencies are read-only, and already loaded from the disk.
+        if let Some(file) = self.dependency_file_for_path(&path) {
+            return file;
+        }
         let (text, line_map) = LineMap::normalize(text);
         let text = <Arc<str>>::from(text);
         let line_map = Arc::new(line_map);
@@ -73,25 +101,28 @@ impl Vfs {
         del_range: Option<TextRange>,
         ins_text: &str,
     ) -> Result<()> {
-        let new_text = match del_range {
-            None => ins_text.to_owned(),
+        let (new_text, line_map) = match del_range {
+            None => LineMap::normalize(ins_text.to_owned()),
             Some(del_range) => {
-                let text = &*self.files[file.0 as usize].0;
+                let (text, line_map) = &self.files[file.0 as usize];
                 ensure!(
-                    del_range.end() <= TextSize::of(text),
+                    del_range.end() <= TextSize::of(&**text)
+                        && text.is_char_boundary(del_range.start().into())
+                        && text.is_char_boundary(del_range.end().into()),
                     "Invalid delete range {del_range:?}",
                 );
+                let ins_text = ins_text.replace('\r', "");
                 let mut buf = String::with_capacity(
                     text.len() - usize::from(del_range.len()) + ins_text.len(),
                 );
                 buf += &text[..usize::from(del_range.start())];
-                buf += ins_text;
+                buf += &ins_text;
                 buf += &text[usize::from(del_range.end())..];
-                buf
+                let mut line_map = LineMap::clone(line_map);
+                line_map.edit(del_range, &ins_text);
+                (buf, line_map)
             }
         };
-        // This is not quite efficient, but we already do many O(n) traversals.
-        let (new_text, line_map) = LineMap::normalize(new_text);
         let new_text = <Arc<str>>::from(new_text);
         log::trace!("File {:?} content changed: {:?}", file, new_text);
         self.files[file.0 as usize] = (new_text.clone(), Arc::new(line_map));
@@ -99,9 +130,108 @@ impl Vfs {
         Ok(())
     }
 
+    /// Add the packages of a workspace and their dependencies. Dependencies already loaded,
+    /// eg. for another package of the workspace, are kept, as are local files already opened.
+    pub fn add_workspace(&mut self, workspace: Workspace) {
+        for package in workspace.packages {
+            if !self.workspace.iter().any(|p| p.root == package.root) {
+                self.workspace.push(package);
+            }
+        }
+        for dir in workspace.include_dirs {
+            if !self.include_dirs.contains(&dir) {
+                self.include_dirs.push(dir);
+            }
+        }
+        for (path, text) in workspace.sources {
+            let path = VfsPath::from(path);
+            if self.local_file_set.file_for_path(&path).is_none() {
+                self.set_path_content(path, text);
+            }
+        }
+        for dep in workspace.dependencies {
+            if self.dependencies.iter().any(|d| d.name == dep.name) {
+                continue;
+            }
+            let idx = self.dependencies.len();
+            let mut file_set = FileSet::default();
+            for (path, text) in dep.files {
+                let path = VfsPath::from(path);
+                let (text, line_map) = LineMap::normalize(text);
+                let text = <Arc<str>>::from(text);
+                let data = (text.clone(), Arc::new(line_map));
+                // Files opened before the dependency was loaded keep their `FileId`.
+                let file = match self.local_file_set.file_for_path(&path) {
+                    Some(file) => {
+                        self.local_file_set.remove_file(file);
+                        self.files[file.0 as usize] = data;
+                        file
+                    }
+                    None => {
+                        let file =
+                            FileId(u32::try_from(self.files.len()).expect("Length overflow"));
+                        self.files.push(data);
+                        file
+                    }
+                };
+                self.change.change_file(file, text);
+                file_set.insert(file, path);
+                self.dependency_of_file.insert(file, idx);
+            }
+            self.dependencies.push(DependencyRoot {
+                name: dep.name,
+                root: dep.root,
+                config: dep.config,
+                file_set,
+            });
+        }
+        self.root_changed = true;
+    }
+
+    /// Replace the packages at or below `root` and their dependencies by a fresh load, eg. after
+    /// `gleam.toml` changed. Dependencies which are no longer downloaded are removed.
+    pub fn reload_workspace(&mut self, root: &Path, workspace: Workspace) {
+        for package in &workspace.packages {
+            if let Some(old) = self.workspace.iter_mut().find(|p| p.root == package.root) {
+                *old = package.clone();
+            }
+        }
+        // Reloaded dependencies may have another version now, so their files are read again.
+        self.dependencies.retain(|dep| {
+            !dep.root.starts_with(root)
+                && !workspace.dependencies.iter().any(|d| d.name == dep.name)
+        });
+        self.dependency_of_file = self
+            .dependencies
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, dep)| dep.file_set.iter().map(move |(file, _)| (file, idx)))
+            .collect();
+        self.add_workspace(workspace);
+    }
+
+    pub fn is_dependency_file(&self, file: FileId) -> bool {
+        self.dependency_of_file.contains_key(&file)
+    }
+
+    fn dependency_file_for_path(&self, path: &VfsPath) -> Option<FileId> {
+        self.dependencies
+            .iter()
+            .find_map(|dep| dep.file_set.file_for_path(path))
+    }
+
+    /// The innermost workspace package containing `path`.
+    fn workspace_package_for_path(&self, path: &VfsPath) -> Option<usize> {
+        let path = path.as_path()?;
+        (0..self.workspace.len())
+            .filter(|&idx| path.starts_with(&self.workspace[idx].root))
+            .max_by_key(|&idx| self.workspace[idx].root.components().count())
+    }
+
     pub fn file_for_path(&self, path: &VfsPath) -> Result<FileId> {
         self.local_file_set
             .file_for_path(path)
+            .or_else(|| self.dependency_file_for_path(path))
             .with_context(|| format!("File not loaded: {path:?}"))
     }
 
@@ -110,18 +240,61 @@ impl Vfs {
     }
 
     pub fn uri_for_file(&self, file: FileId) -> Url {
-        let vpath = self.local_file_set.path_for_file(file);
+        let vpath = match self.dependency_of_file.get(&file) {
+            Some(&idx) => self.dependencies[idx].file_set.path_for_file(file),
+            None => self.local_file_set.path_for_file(file),
+        };
         Url::from_vfs_path(vpath)
     }
 
     pub fn take_change(&mut self) -> Change {
         let mut change = mem::take(&mut self.change);
         if mem::take(&mut self.root_changed) {
-            change.set_roots(vec![SourceRoot::new_local(
-                self.local_file_set.clone(),
-                // TODO: Entry.
-                None,
-            )]);
+            let mut outside = FileSet::default();
+            let mut package_file_sets = vec![FileSet::default(); self.workspace.len()];
+            let mut include_file_sets = vec![FileSet::default(); self.include_dirs.len()];
+            for (file, path) in self.local_file_set.iter() {
+                let include_dir = || {
+                    let path = path.as_path()?;
+                    self.include_dirs.iter().position(|dir| path.starts_with(dir))
+                };
+                match (self.workspace_package_for_path(path), include_dir()) {
+                    (Some(idx), _) => package_file_sets[idx].insert(file, path.clone()),
+                    (None, Some(idx)) => include_file_sets[idx].insert(file, path.clone()),
+                    (None, None) => outside.insert(file, path.clone()),
+                }
+            }
+            // Files outside of any package stay the first root, which the module graph refers
+            // to.
+            // TODO: Entry.
+            let outside = SourceRoot::new_local(outside, None);
+            let packages = self
+                .workspace
+                .iter()
+                .zip(package_file_sets)
+                .map(|(pkg, file_set)| {
+                    SourceRoot::new_local(file_set, None).with_package(pkg.clone())
+                });
+            let includes = self
+                .include_dirs
+                .iter()
+                .zip(include_file_sets)
+                .map(|(dir, file_set)| SourceRoot::new_local(file_set, None).with_source_dir(dir.clone()));
+            let dependencies = self.dependencies.iter().map(|dep| {
+                let root = SourceRoot::new_library(dep.file_set.clone(), None);
+                match &dep.config {
+                    Some(config) => root.with_package(config.clone()),
+                    None => root,
+                }
+            });
+            change.set_roots(
+                [outside]
+                    .into_iter()
+                    .chain(packages)
+                    .chain(includes)
+                    .chain(dependencies)
+                    .collect(),
+            );
         }
         change
     }
@@ -135,21 +308,9 @@ impl Vfs {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct LineMap {
-    /// Invariant:
-    /// - Have at least two elements.
-    /// - The first must be 0.
-    /// - The last must be the length of original text.
-    line_starts: Vec<u32>,
-    char_diffs: HashMap<u32, Vec<(u32, CodeUnitsDiff)>>,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum CodeUnitsDiff {
-    One = 1,
-    Two = 2,
-}
+/// The `LineIndex` of a file, with columns in UTF-16 code units as the LSP uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMap(LineIndex);
 
 impl LineMap {
     fn normalize(mut text: String) -> (String, Self) {
@@ -157,103 +318,51 @@ impl LineMap {
         u32::try_from(text.len()).expect("Text too long");
 
         text.retain(|c| c != '\r');
-        let bytes = text.as_bytes();
-
-        let mut line_starts = Some(0)
-            .into_iter()
-            .chain(
-                bytes
-                    .iter()
-                    .zip(0u32..)
-                    .filter(|(b, _)| **b == b'\n')
-                    .map(|(_, i)| i + 1),
-            )
-            .collect::<Vec<_>>();
-        line_starts.push(text.len() as u32);
-
-        let mut char_diffs = HashMap::new();
-        for ((&start, &end), i) in line_starts.iter().zip(&line_starts[1..]).zip(0u32..) {
-            let mut diffs = Vec::new();
-            for (&b, pos) in bytes[start as usize..end as usize].iter().zip(0u32..) {
-                let diff = match b {
-                    0b0000_0000..=0b0111_1111 |                      // utf8_len == 1, utf16_len == 1
-                    0b1000_0000..=0b1011_1111 => continue,           // Continuation bytes.
-                    0b1100_0000..=0b1101_1111 => CodeUnitsDiff::One, // utf8_len == 2, utf16_len == 1
-                    0b1110_0000..=0b1110_1111 => CodeUnitsDiff::Two, // utf8_len == 3, utf16_len == 1
-                    0b1111_0000.. => CodeUnitsDiff::Two,             // utf8_len == 4, utf16_len == 2
-                };
-                diffs.push((pos, diff));
-            }
-            if !diffs.is_empty() {
-                char_diffs.insert(i, diffs);
-            }
-        }
-
-        let this = Self {
-            line_starts,
-            char_diffs,
-        };
+        let this = Self(LineIndex::new(&text));
         (text, this)
     }
 
+    /// Update the map for replacing `del_range` with the already normalized `ins_text`.
+    fn edit(&mut self, del_range: TextRange, ins_text: &str) {
+        self.0.edit(del_range, ins_text);
+    }
+
     pub fn last_line(&self) -> u32 {
-        self.line_starts.len() as u32 - 2
+        self.0.line_count() - 1
     }
 
-    pub fn pos_for_line_col(&self, line: u32, mut col: u32) -> TextSize {
-        let pos = self.line_starts.get(line as usize).copied().unwrap_or(0);
-        if let Some(diffs) = self.char_diffs.get(&line) {
-            for &(char_pos, diff) in diffs {
-                if char_pos < col {
-                    col += diff as u32;
-                }
-            }
-        }
-        (pos + col).into()
+    pub fn pos_for_line_col(&self, line: u32, col: u32) -> TextSize {
+        let pos = self.0.to_utf8(LineColUtf16 { line, col });
+        self.0.offset(pos).unwrap_or(self.0.len())
     }
 
     pub fn line_col_for_pos(&self, pos: TextSize) -> (u32, u32) {
-        let pos = u32::from(pos);
-        let line = self
-            .line_starts
-            .partition_point(|&i| i <= pos)
-            .saturating_sub(1);
-        let mut col = pos - self.line_starts[line];
-        if let Some(diffs) = self.char_diffs.get(&(line as u32)) {
-            col -= diffs
-                .iter()
-                .take_while(|(char_pos, _)| *char_pos < col)
-                .map(|(_, diff)| *diff as u32)
-                .sum::<u32>();
-        }
-        (line as u32, col)
+        let LineColUtf16 { line, col } = self.0.to_utf16(self.0.line_col(pos));
+        (line, col)
     }
 
     pub fn end_col_for_line(&self, line: u32) -> u32 {
-        let mut len = self.line_starts[line as usize + 1] - self.line_starts[line as usize];
-        if let Some(diffs) = self.char_diffs.get(&line) {
-            len -= diffs.iter().map(|&(_, diff)| diff as u32).sum::<u32>();
-        }
-        // Lines except the last one has a trailing `\n`.
-        // Note that `line_starts` has one element more than actual total lines.
-        if line + 1 + 1 != self.line_starts.len() as u32 {
-            len -= 1;
-        }
-        len
+        self.0
+            .line_range(line)
+            .map_or(0, |range| self.line_col_for_pos(range.end()).1)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{CodeUnitsDiff, LineMap};
-    use std::collections::HashMap;
+    use super::{LineMap, Vfs};
+    use crate::packages::Package;
+    use crate::project::Workspace;
+    use ide::PackageInfo;
+    use std::path::PathBuf;
+    use text_size::TextRange;
 
     #[test]
     fn line_map_ascii() {
         let s = "hello\nworld\nend";
         let (norm, map) = LineMap::normalize(s.into());
         assert_eq!(norm, s);
-        assert_eq!(&map.line_starts, &[0, 6, 12, 15]);
+        assert_eq!(map.last_line(), 2);
 
         let mapping = [
             (0, 0, 0),
@@ -279,18 +388,7 @@ mod tests {
         let s = "_A_ß_ℝ_💣_";
         let (norm, map) = LineMap::normalize(s.into());
         assert_eq!(norm, s);
-        assert_eq!(&map.line_starts, &[0, 15]);
-        assert_eq!(
-            &map.char_diffs,
-            &HashMap::from([(
-                0u32,
-                vec![
-                    (3u32, CodeUnitsDiff::One),
-                    (6, CodeUnitsDiff::Two),
-                    (10, CodeUnitsDiff::Two),
-                ],
-            )])
-        );
+        assert_eq!(map.last_line(), 0);
 
         let mapping = [
             (0, 0, 0),
@@ -330,4 +428,70 @@ mod tests {
         assert_eq!(map.end_col_for_line(2), 0);
         assert_eq!(map.end_col_for_line(3), 3);
     }
+
+    #[test]
+    fn edit() {
+        let (text, mut map) = LineMap::normalize("a\r\nß\nc".into());
+        assert_eq!(text, "a\nß\nc");
+        map.edit(TextRange::new(2.into(), 4.into()), "💣\nd");
+        let (_, expect) = LineMap::normalize("a\n💣\nd\nc".into());
+        assert_eq!(map, expect);
+        assert_eq!(map.line_col_for_pos(6.into()), (1, 2));
+    }
+
+    #[test]
+    fn reload_workspace() {
+        let package = |target: &str| PackageInfo {
+            name: "app".into(),
+            root: "/app".into(),
+            target: Some(target.into()),
+            gleam_version: None,
+            dependencies: Vec::new(),
+            path_dependencies: Vec::new(),
+        };
+        let dep = |name: &str| Package {
+            name: name.into(),
+            root: PathBuf::from("/app/build/packages").join(name),
+            config: None,
+            files: vec![(
+                PathBuf::from(format!("/app/build/packages/{name}/src/{name}.gleam")),
+                String::new(),
+            )],
+        };
+        let mut vfs = Vfs::new();
+        vfs.add_workspace(Workspace {
+            packages: vec![package("erlang")],
+            dependencies: vec![dep("gleam_json"), dep("gleam_stdlib")],
+            ..Workspace::default()
+        });
+        vfs.take_change();
+        vfs.reload_workspace(
+            "/app".as_ref(),
+            Workspace {
+                packages: vec![package("javascript")],
+                dependencies: vec![dep("gleam_stdlib")],
+                ..Workspace::default()
+            },
+        );
+
+        let roots = vfs.take_change().roots.unwrap();
+        let got = roots
+            .iter()
+            .map(|root| {
+                let package = root.package().map(|pkg| pkg.target.clone());
+                (root.is_library(), package, root.files().len())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            [
+                (false, None, 0),
+                (false, Some(Some("javascript".to_owned())), 0),
+                (true, None, 1),
+            ]
+        );
+        let stdlib = "/app/build/packages/gleam_stdlib/src/gleam_stdlib.gleam";
+        let file = vfs.file_for_path(&PathBuf::from(stdlib).into()).unwrap();
+        assert!(vfs.is_dependency_file(file));
+    }
 }
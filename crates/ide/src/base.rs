@@ -1,9 +1,10 @@
 use salsa::Durability;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use syntax::{TextRange, TextSize};
+use syntax::{LanguageVersion, TextRange, TextSize};
+pub use syntax::{LineCol, LineColUtf16, LineIndex, TextEdit};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FileId(pub u32);
@@ -137,16 +138,114 @@ impl fmt::Debug for FileSet {
     }
 }
 
+/// The platform code is compiled for. Items can be limited to one with `@target(erlang)` or
+/// the deprecated `if erlang { ... }` groups.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    #[default]
+    Erlang,
+    JavaScript,
+}
+
+impl Target {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "erlang" => Some(Self::Erlang),
+            "javascript" => Some(Self::JavaScript),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Erlang => "erlang",
+            Self::JavaScript => "javascript",
+        }
+    }
+
+    #[must_use]
+    pub fn other(self) -> Self {
+        match self {
+            Self::Erlang => Self::JavaScript,
+            Self::JavaScript => Self::Erlang,
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The package a source root is, as described by its `gleam.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInfo {
+    pub name: String,
+    /// The directory containing `gleam.toml`.
+    pub root: PathBuf,
+    /// The default compilation target, `erlang` if `None`.
+    pub target: Option<String>,
+    /// The required Gleam version, as in `>= 1.4.0`. The latest syntax is accepted if `None`.
+    pub gleam_version: Option<String>,
+    /// The names of dependencies, including dev dependencies, sorted.
+    pub dependencies: Vec<String>,
+    /// The directories of dependencies given by path, as in `local = { path = "../local" }`,
+    /// which are edited along with this package.
+    pub path_dependencies: Vec<PathBuf>,
+}
+
 /// A workspace unit, typically a Gleam package.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SourceRoot {
     file_set: FileSet,
     entry: Option<FileId>,
+    is_library: bool,
+    package: Option<PackageInfo>,
+    /// The directory module names are relative to, for sources outside of any package.
+    source_dir: Option<PathBuf>,
 }
 
 impl SourceRoot {
     pub fn new_local(file_set: FileSet, entry: Option<FileId>) -> Self {
-        Self { file_set, entry }
+        Self {
+            file_set,
+            entry,
+            is_library: false,
+            package: None,
+            source_dir: None,
+        }
+    }
+
+    /// A read-only root, eg. a dependency package.
+    pub fn new_library(file_set: FileSet, entry: Option<FileId>) -> Self {
+        Self {
+            file_set,
+            entry,
+            is_library: true,
+            package: None,
+            source_dir: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_package(mut self, package: PackageInfo) -> Self {
+        self.package = Some(package);
+        self
+    }
+
+    #[must_use]
+    pub fn with_source_dir(mut self, dir: PathBuf) -> Self {
+        self.source_dir = Some(dir);
+        self
+    }
+
+    pub fn is_library(&self) -> bool {
+        self.is_library
+    }
+
+    pub fn package(&self) -> Option<&PackageInfo> {
+        self.package.as_ref()
     }
 
     pub fn file_for_path(&self, path: &VfsPath) -> Option<FileId> {
@@ -164,6 +263,53 @@ impl SourceRoot {
     pub fn entry(&self) -> Option<FileId> {
         self.entry
     }
+
+    /// The Gleam module name of a file, which is its path relative to the `src` or `test`
+    /// directory of the package without the `.gleam` extension, eg. `gleam/list`. Without a
+    /// known package, the source directory of the root or the closest enclosing `src` or `test`
+    /// directory is used.
+    pub fn module_name_for_file(&self, file: FileId) -> Option<String> {
+        let path = self.path_for_file(file).as_path()?;
+        if path.extension()? != "gleam" {
+            return None;
+        }
+        let path = path.with_extension("");
+        let src_dir = self.source_dir(&path)?;
+        let components = path
+            .strip_prefix(&src_dir)
+            .ok()?
+            .iter()
+            .map(|c| c.to_str())
+            .collect::<Option<Vec<_>>>()?;
+        Some(components.join("/"))
+    }
+
+    /// Whether a file is a module of the `test` directory, rather than of `src`.
+    pub fn is_test_file(&self, file: FileId) -> bool {
+        self.path_for_file(file)
+            .as_path()
+            .and_then(|path| self.source_dir(path))
+            .map_or(false, |dir| dir.ends_with("test"))
+    }
+
+    fn source_dir(&self, path: &Path) -> Option<PathBuf> {
+        if let Some(dir) = self.source_dir.as_ref().filter(|dir| path.starts_with(dir)) {
+            return Some(dir.clone());
+        }
+        match &self.package {
+            Some(package) => ["src", "test"]
+                .iter()
+                .map(|dir| package.root.join(dir))
+                .find(|dir| path.starts_with(dir)),
+            None => Some(
+                path.ancestors()
+                    .skip(1)
+                    .find(|dir| dir.ends_with("src") || dir.ends_with("test"))
+                    .or_else(|| path.parent())?
+                    .to_path_buf(),
+            ),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -229,6 +375,22 @@ impl FileRange {
     }
 }
 
+/// Text edits over multiple files.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SourceChange {
+    pub file_edits: HashMap<FileId, Vec<TextEdit>>,
+}
+
+impl SourceChange {
+    pub fn is_empty(&self) -> bool {
+        self.file_edits.is_empty()
+    }
+
+    pub fn insert(&mut self, file_id: FileId, edit: TextEdit) {
+        self.file_edits.entry(file_id).or_default().push(edit);
+    }
+}
+
 #[salsa::query_group(SourceDatabaseStorage)]
 pub trait SourceDatabase {
     #[salsa::input]
@@ -237,6 +399,10 @@ pub trait SourceDatabase {
     #[salsa::input]
     fn source_root(&self, sid: SourceRootId) -> Arc<SourceRoot>;
 
+    /// All currently known source roots.
+    #[salsa::input]
+    fn source_root_ids(&self) -> Arc<Vec<SourceRootId>>;
+
     fn source_root_module_info(&self, sid: SourceRootId) -> Option<Arc<ModuleInfo>>;
 
     #[salsa::input]
@@ -244,22 +410,79 @@ pub trait SourceDatabase {
 
     #[salsa::input]
     fn module_graph(&self) -> Arc<ModuleGraph>;
+
+    /// The target chosen by the user, which overrides the one of each package.
+    #[salsa::input]
+    fn active_target(&self) -> Option<Target>;
+
+    /// The target `file` is analyzed for.
+    fn file_target(&self, file_id: FileId) -> Target;
+
+    /// The oldest Gleam version `file` has to compile with, which decides the syntax accepted.
+    fn file_language_version(&self, file_id: FileId) -> LanguageVersion;
 }
 
 fn source_root_module_info(db: &dyn SourceDatabase, sid: SourceRootId) -> Option<Arc<ModuleInfo>> {
     db.module_graph().nodes.get(&sid).cloned().map(Arc::new)
 }
 
+fn file_target(db: &dyn SourceDatabase, file_id: FileId) -> Target {
+    if let Some(target) = db.active_target() {
+        return target;
+    }
+    let root = db.source_root(db.file_source_root(file_id));
+    root.package()
+        .and_then(|package| package.target.as_deref())
+        .and_then(Target::from_name)
+        .unwrap_or_default()
+}
+
+fn file_language_version(db: &dyn SourceDatabase, file_id: FileId) -> LanguageVersion {
+    let root = db.source_root(db.file_source_root(file_id));
+    root.package()
+        .and_then(|package| package.gleam_version.as_deref())
+        .and_then(min_language_version)
+        .unwrap_or_default()
+}
+
+/// The oldest version allowed by a requirement like `>= 1.4.0 and < 2.0.0`, or `None` if it
+/// has no lower bound.
+fn min_language_version(requirement: &str) -> Option<LanguageVersion> {
+    let lower_bound = |clause: &str| {
+        let clause = clause.trim();
+        let version = [">=", "~>", "==", ">"]
+            .iter()
+            .find_map(|op| clause.strip_prefix(op))
+            .or_else(|| {
+                clause
+                    .starts_with(|c: char| c.is_ascii_digit())
+                    .then_some(clause)
+            })?;
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+        Some(LanguageVersion::new(major, minor))
+    };
+    requirement
+        .split(" or ")
+        .map(|alternative| alternative.split(" and ").filter_map(lower_bound).max())
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()
+}
+
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct Change {
     pub module_graph: Option<ModuleGraph>,
     pub roots: Option<Vec<SourceRoot>>,
+    /// `Some(None)` resets to the targets of packages.
+    pub active_target: Option<Option<Target>>,
     pub file_changes: Vec<(FileId, Arc<str>)>,
 }
 
 impl Change {
     pub fn is_empty(&self) -> bool {
-        self.roots.is_none() && self.file_changes.is_empty()
+        self.roots.is_none() && self.active_target.is_none() && self.file_changes.is_empty()
     }
 
     pub fn set_module_graph(&mut self, graph: ModuleGraph) {
@@ -270,6 +493,10 @@ impl Change {
         self.roots = Some(roots);
     }
 
+    pub fn set_active_target(&mut self, target: Option<Target>) {
+        self.active_target = Some(target);
+    }
+
     pub fn change_file(&mut self, file_id: FileId, content: Arc<str>) {
         self.file_changes.push((file_id, content));
     }
@@ -278,17 +505,32 @@ impl Change {
         if let Some(module_graph) = self.module_graph {
             db.set_module_graph_with_durability(Arc::new(module_graph), Durability::MEDIUM);
         }
+        if let Some(target) = self.active_target {
+            db.set_active_target_with_durability(target, Durability::MEDIUM);
+        }
+        let mut library_files = HashSet::new();
         if let Some(roots) = self.roots {
-            u32::try_from(roots.len()).expect("Length overflow");
+            let len = u32::try_from(roots.len()).expect("Length overflow");
             for (sid, root) in (0u32..).map(SourceRootId).zip(roots) {
                 for (fid, _) in root.files() {
                     db.set_file_source_root_with_durability(fid, sid, Durability::HIGH);
+                    if root.is_library() {
+                        library_files.insert(fid);
+                    }
                 }
                 db.set_source_root_with_durability(sid, Arc::new(root), Durability::HIGH);
             }
+            let ids = (0..len).map(SourceRootId).collect();
+            db.set_source_root_ids_with_durability(Arc::new(ids), Durability::HIGH);
         }
         for (file_id, content) in self.file_changes {
-            db.set_file_content_with_durability(file_id, content, Durability::LOW);
+            // Libraries are read-only, so their files only change together with the roots.
+            let durability = if library_files.contains(&file_id) {
+                Durability::HIGH
+            } else {
+                Durability::LOW
+            };
+            db.set_file_content_with_durability(file_id, content, durability);
         }
     }
 }
@@ -303,6 +545,7 @@ impl fmt::Debug for Change {
         let cleared = self.file_changes.len() - modified;
         f.debug_struct("Change")
             .field("roots", &self.roots.as_ref().map(|roots| roots.len()))
+            .field("active_target", &self.active_target)
             .field("modified", &modified)
             .field("cleared", &cleared)
             .finish_non_exhaustive()
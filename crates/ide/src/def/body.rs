@@ -0,0 +1,667 @@
+//! Function bodies lowered into arenas of expressions and patterns.
+//!
+//! Later analyses refer to expressions by `ExprId` instead of syntax nodes, and only go back to
+//! the syntax through the `BodySourceMap` when they need a position. Data computed per
+//! expression or pattern, like scopes or name resolutions, is kept in `ArenaMap`s indexed by
+//! these ids rather than in the arenas themselves.
+use std::collections::HashMap;
+use std::ops;
+
+use la_arena::{Arena, ArenaMap, Idx};
+use ordered_float::OrderedFloat;
+use smol_str::SmolStr;
+use syntax::ast::{self, AstNode};
+use syntax::lexer::split_radix;
+use syntax::{AstPtr, SyntaxKind, SyntaxNodePtr};
+
+use super::{BinaryOp, Name, UnaryOp};
+
+pub type ExprId = Idx<Expr>;
+pub type PatId = Idx<Pattern>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Body {
+    pub exprs: Arena<Expr>,
+    pub patterns: Arena<Pattern>,
+    pub params: Vec<BodyParam>,
+    /// `Expr::Missing` for external functions.
+    pub body_expr: ExprId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyParam {
+    pub label: Option<Name>,
+    pub pattern: PatId,
+}
+
+impl ops::Index<ExprId> for Body {
+    type Output = Expr;
+    fn index(&self, index: ExprId) -> &Self::Output {
+        &self.exprs[index]
+    }
+}
+
+impl ops::Index<PatId> for Body {
+    type Output = Pattern;
+    fn index(&self, index: PatId) -> &Self::Output {
+        &self.patterns[index]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// Missing because of a syntax error.
+    Missing,
+    Literal(Literal),
+    /// A local variable, or an item of this module or imported unqualified.
+    Variable(Name),
+    /// `module.name`, unless `module` turns out to be a local variable, in which case this is a
+    /// field access.
+    ModuleAccess {
+        module: Name,
+        name: Name,
+    },
+    Tuple(Vec<ExprId>),
+    List(Vec<ExprId>),
+    Call {
+        callee: ExprId,
+        /// The record being updated, as in `Person(..old, name: "x")`.
+        spread: Option<ExprId>,
+        args: Vec<Arg>,
+    },
+    Case {
+        subjects: Vec<ExprId>,
+        clauses: Vec<Clause>,
+    },
+    BitArray(Vec<BitArraySegment<ExprId>>),
+    Binary {
+        op: Option<BinaryOp>,
+        lhs: ExprId,
+        rhs: ExprId,
+    },
+    Unary {
+        op: Option<UnaryOp>,
+        arg: ExprId,
+    },
+    /// The hole of a function capture, as in `add(1, _)`.
+    CaptureHole,
+    FieldAccess {
+        container: ExprId,
+        label: Name,
+    },
+    TupleIndex {
+        container: ExprId,
+        index: u32,
+    },
+    Todo(Option<ExprId>),
+    Panic(Option<ExprId>),
+    /// The argument is `None` at the end of a pipeline.
+    Echo(Option<ExprId>),
+    Block(Vec<Statement>),
+    /// An anonymous function, with parameters like those of the function itself.
+    Lambda {
+        params: Vec<BodyParam>,
+        body: ExprId,
+    },
+}
+
+impl Expr {
+    /// Call `f` on each direct sub-expression, in source order. Expressions in patterns, like
+    /// bit array sizes, are not included.
+    pub fn walk_child_exprs(&self, mut f: impl FnMut(ExprId)) {
+        match self {
+            Self::Missing
+            | Self::Literal(_)
+            | Self::Variable(_)
+            | Self::ModuleAccess { .. }
+            | Self::CaptureHole => {}
+            Self::Tuple(elems) | Self::List(elems) => elems.iter().copied().for_each(f),
+            Self::Call {
+                callee,
+                spread,
+                args,
+            } => {
+                f(*callee);
+                spread.iter().copied().for_each(&mut f);
+                args.iter().for_each(|arg| f(arg.value));
+            }
+            Self::Case { subjects, clauses } => {
+                subjects.iter().copied().for_each(&mut f);
+                for clause in clauses {
+                    clause.guard.iter().copied().for_each(&mut f);
+                    f(clause.body);
+                }
+            }
+            Self::BitArray(segments) => {
+                for seg in segments {
+                    f(seg.value);
+                    seg.options
+                        .iter()
+                        .filter_map(|opt| opt.value)
+                        .for_each(&mut f);
+                }
+            }
+            Self::Binary { lhs, rhs, .. } => {
+                f(*lhs);
+                f(*rhs);
+            }
+            Self::Unary { arg, .. } => f(*arg),
+            Self::FieldAccess { container, .. } | Self::TupleIndex { container, .. } => {
+                f(*container)
+            }
+            Self::Todo(arg) | Self::Panic(arg) | Self::Echo(arg) => arg.iter().copied().for_each(f),
+            Self::Block(stmts) => {
+                for stmt in stmts {
+                    match stmt {
+                        Statement::Let { value, .. } => f(*value),
+                        Statement::Use { callee, .. } => f(*callee),
+                        Statement::Expr(expr) => f(*expr),
+                    }
+                }
+            }
+            Self::Lambda { body, .. } => f(*body),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Let {
+        pattern: PatId,
+        value: ExprId,
+        is_assert: bool,
+    },
+    /// The rest of the block is the last argument of `callee`, binding `patterns`.
+    Use {
+        patterns: Vec<PatId>,
+        callee: ExprId,
+    },
+    Expr(ExprId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arg {
+    pub label: Option<Name>,
+    pub value: ExprId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    /// The alternatives separated by `|`, each with one pattern per subject.
+    pub alternatives: Vec<Vec<PatId>>,
+    pub guard: Option<ExprId>,
+    pub body: ExprId,
+}
+
+/// A segment of a bit array expression or pattern. Sizes are expressions in both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitArraySegment<T> {
+    pub value: T,
+    pub options: Vec<BitArrayOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitArrayOption {
+    /// `None` for unknown options.
+    pub kind: Option<ast::BitArrayOptionKind>,
+    pub value: Option<ExprId>,
+}
+
+/// The value of a literal, `None` if it is invalid or does not fit, which the parser reports.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Literal {
+    Int(Option<i64>),
+    Float(Option<OrderedFloat<f64>>),
+    String(Option<SmolStr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Missing,
+    Variable(Name),
+    Discard,
+    Literal(Literal),
+    Tuple(Vec<PatId>),
+    List {
+        elements: Vec<PatId>,
+        /// A bare `..` is a `Pattern::Discard` without syntax.
+        rest: Option<PatId>,
+    },
+    Constructor {
+        module: Option<Name>,
+        name: Name,
+        args: Vec<PatternArg>,
+        has_spread: bool,
+    },
+    /// A string prefix pattern, as in `"pre" as p <> rest`.
+    Concat {
+        prefix: Option<SmolStr>,
+        prefix_name: Option<Name>,
+        rest: PatId,
+    },
+    BitArray(Vec<BitArraySegment<PatId>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternArg {
+    pub label: Option<Name>,
+    pub pattern: PatId,
+}
+
+/// The syntax of each expression and pattern of a `Body`, in both directions. Parameters
+/// point to their `Param` node.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BodySourceMap {
+    expr_map: HashMap<AstPtr<ast::Expr>, ExprId>,
+    expr_map_rev: ArenaMap<ExprId, AstPtr<ast::Expr>>,
+    pattern_map: HashMap<SyntaxNodePtr, PatId>,
+    pattern_map_rev: ArenaMap<PatId, SyntaxNodePtr>,
+}
+
+impl BodySourceMap {
+    pub fn expr_for_node(&self, node: AstPtr<ast::Expr>) -> Option<ExprId> {
+        self.expr_map.get(&node).copied()
+    }
+
+    pub fn node_for_expr(&self, expr: ExprId) -> Option<AstPtr<ast::Expr>> {
+        self.expr_map_rev.get(expr).copied()
+    }
+
+    pub fn pattern_for_node(&self, node: SyntaxNodePtr) -> Option<PatId> {
+        self.pattern_map.get(&node).copied()
+    }
+
+    pub fn node_for_pattern(&self, pat: PatId) -> Option<SyntaxNodePtr> {
+        self.pattern_map_rev.get(pat).copied()
+    }
+}
+
+/// Lower a function or an external function. Other items do not have a body.
+pub(crate) fn lower(item: &ast::Statement) -> (Body, BodySourceMap) {
+    let mut ctx = LowerCtx::default();
+    let (param_list, body) = match item {
+        ast::Statement::Function(func) => (func.param_list(), func.body()),
+        ast::Statement::ExternalFunction(func) => (func.param_list(), None),
+        _ => (None, None),
+    };
+    let has_names = matches!(item, ast::Statement::Function(_));
+    let params = param_list
+        .iter()
+        .flat_map(|list| list.params())
+        .map(|param| ctx.lower_param(&param, has_names))
+        .collect();
+    let body_expr = ctx.lower_expr_opt(body.map(ast::Expr::Block));
+    let body = Body {
+        exprs: ctx.exprs,
+        patterns: ctx.patterns,
+        params,
+        body_expr,
+    };
+    (body, ctx.source_map)
+}
+
+#[derive(Default)]
+struct LowerCtx {
+    exprs: Arena<Expr>,
+    patterns: Arena<Pattern>,
+    source_map: BodySourceMap,
+}
+
+impl LowerCtx {
+    fn alloc_expr(&mut self, expr: Expr, ptr: AstPtr<ast::Expr>) -> ExprId {
+        let id = self.exprs.alloc(expr);
+        self.source_map.expr_map.insert(ptr, id);
+        self.source_map.expr_map_rev.insert(id, ptr);
+        id
+    }
+
+    fn alloc_pat(&mut self, pat: Pattern, ptr: SyntaxNodePtr) -> PatId {
+        let id = self.patterns.alloc(pat);
+        self.source_map.pattern_map.insert(ptr, id);
+        self.source_map.pattern_map_rev.insert(id, ptr);
+        id
+    }
+
+    /// Parameters of the deprecated `external fn` only have a type and an optional label, which
+    /// is then their only name.
+    fn lower_param(&mut self, param: &ast::Param, has_names: bool) -> BodyParam {
+        let (label, name) = if has_names {
+            (param.label(), param.name())
+        } else {
+            (param.name(), None)
+        };
+        let pat = match name.and_then(|name| name.token()) {
+            Some(tok) if tok.kind() == SyntaxKind::DISCARD_IDENT => Pattern::Discard,
+            Some(tok) => Pattern::Variable(tok.text().into()),
+            None if has_names => Pattern::Missing,
+            None => Pattern::Discard,
+        };
+        BodyParam {
+            label: label.and_then(lower_name),
+            pattern: self.alloc_pat(pat, SyntaxNodePtr::new(param.syntax())),
+        }
+    }
+
+    fn lower_expr_opt(&mut self, expr: Option<ast::Expr>) -> ExprId {
+        match expr {
+            Some(expr) => self.lower_expr(expr),
+            None => self.exprs.alloc(Expr::Missing),
+        }
+    }
+
+    fn lower_expr(&mut self, expr: ast::Expr) -> ExprId {
+        let ptr = AstPtr::new(&expr);
+        let expr = match expr {
+            ast::Expr::Literal(lit) => lower_literal(&lit).map_or(Expr::Missing, Expr::Literal),
+            ast::Expr::NameRef(name) => match name.token() {
+                Some(tok) => Expr::Variable(tok.text().into()),
+                None => Expr::Missing,
+            },
+            ast::Expr::QualifiedRef(qualified) => {
+                let module = qualified.module().and_then(|it| it.token());
+                let name = qualified.name().and_then(|it| it.token());
+                match (module, name) {
+                    (Some(module), Some(name)) => Expr::ModuleAccess {
+                        module: module.text().into(),
+                        name: name.text().into(),
+                    },
+                    _ => Expr::Missing,
+                }
+            }
+            ast::Expr::Tuple(tuple) => {
+                Expr::Tuple(tuple.elements().map(|e| self.lower_expr(e)).collect())
+            }
+            ast::Expr::List(list) => {
+                Expr::List(list.elements().map(|e| self.lower_expr(e)).collect())
+            }
+            ast::Expr::CallExpr(call) => {
+                let callee = self.lower_expr_opt(call.callee());
+                let arg_list = call.arg_list();
+                let spread = arg_list
+                    .as_ref()
+                    .and_then(|args| args.record_spread())
+                    .map(|spread| self.lower_expr_opt(spread.expr()));
+                let args = arg_list
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .map(|arg| Arg {
+                        label: arg.label().and_then(lower_name),
+                        value: self.lower_expr_opt(arg.value()),
+                    })
+                    .collect();
+                Expr::Call {
+                    callee,
+                    spread,
+                    args,
+                }
+            }
+            ast::Expr::CaseExpr(case) => {
+                let subjects = case.subjects().map(|e| self.lower_expr(e)).collect();
+                let clauses = case
+                    .clauses()
+                    .map(|clause| {
+                        let alternatives = match clause.patterns().next() {
+                            Some(ast::Pattern::AlternativePattern(alt)) => alt.alternatives(),
+                            _ => vec![clause.patterns().collect()],
+                        };
+                        Clause {
+                            alternatives: alternatives
+                                .into_iter()
+                                .map(|pats| pats.into_iter().map(|p| self.lower_pat(p)).collect())
+                                .collect(),
+                            guard: clause.guard().map(|e| self.lower_expr(e)),
+                            body: self.lower_expr_opt(clause.body()),
+                        }
+                    })
+                    .collect();
+                Expr::Case { subjects, clauses }
+            }
+            ast::Expr::BitArray(bits) => {
+                let segments = bits
+                    .segments()
+                    .map(|seg| BitArraySegment {
+                        value: self.lower_expr_opt(seg.value()),
+                        options: self.lower_bit_array_options(&seg),
+                    })
+                    .collect();
+                Expr::BitArray(segments)
+            }
+            ast::Expr::BinaryExpr(bin) => Expr::Binary {
+                op: bin.op_kind(),
+                lhs: self.lower_expr_opt(bin.lhs()),
+                rhs: self.lower_expr_opt(bin.rhs()),
+            },
+            ast::Expr::UnaryExpr(unary) => Expr::Unary {
+                op: unary.op_kind(),
+                arg: self.lower_expr_opt(unary.arg()),
+            },
+            ast::Expr::CaptureHole(_) => Expr::CaptureHole,
+            ast::Expr::FieldAccess(access) => {
+                let container = self.lower_expr_opt(access.container());
+                if let Some(tok) = access.label().and_then(|it| it.token()) {
+                    Expr::FieldAccess {
+                        container,
+                        label: tok.text().into(),
+                    }
+                } else if let Some(index) = access.index().and_then(|it| it.text().parse().ok()) {
+                    Expr::TupleIndex { container, index }
+                } else {
+                    Expr::Missing
+                }
+            }
+            ast::Expr::TodoExpr(todo) => Expr::Todo(todo.message().map(|e| self.lower_expr(e))),
+            ast::Expr::PanicExpr(panic) => Expr::Panic(panic.message().map(|e| self.lower_expr(e))),
+            ast::Expr::EchoExpr(echo) => Expr::Echo(echo.expression().map(|e| self.lower_expr(e))),
+            ast::Expr::Block(block) => {
+                let stmts = block
+                    .statements()
+                    .map(|stmt| self.lower_stmt(stmt))
+                    .collect();
+                Expr::Block(stmts)
+            }
+            ast::Expr::Lambda(lambda) => Expr::Lambda {
+                params: lambda
+                    .param_list()
+                    .iter()
+                    .flat_map(|list| list.params())
+                    .map(|param| self.lower_param(&param, true))
+                    .collect(),
+                body: self.lower_expr_opt(lambda.body().map(ast::Expr::Block)),
+            },
+        };
+        self.alloc_expr(expr, ptr)
+    }
+
+    fn lower_stmt(&mut self, stmt: ast::Stmt) -> Statement {
+        match stmt {
+            ast::Stmt::Let(stmt) => Statement::Let {
+                pattern: self.lower_pat_opt(stmt.pattern()),
+                value: self.lower_expr_opt(stmt.value()),
+                is_assert: stmt.is_assert(),
+            },
+            ast::Stmt::Use(stmt) => Statement::Use {
+                patterns: stmt
+                    .assignments()
+                    .map(|it| self.lower_pat_opt(it.pattern()))
+                    .collect(),
+                callee: self.lower_expr_opt(stmt.callee()),
+            },
+            ast::Stmt::Expr(expr) => Statement::Expr(self.lower_expr(expr)),
+        }
+    }
+
+    fn lower_bit_array_options(&mut self, seg: &ast::BitArraySegment) -> Vec<BitArrayOption> {
+        seg.options()
+            .map(|opt| BitArrayOption {
+                kind: opt.kind(),
+                value: opt.value().map(|e| self.lower_expr(e)),
+            })
+            .collect()
+    }
+
+    fn lower_pat_opt(&mut self, pat: Option<ast::Pattern>) -> PatId {
+        match pat {
+            Some(pat) => self.lower_pat(pat),
+            None => self.patterns.alloc(Pattern::Missing),
+        }
+    }
+
+    fn lower_pat(&mut self, pat: ast::Pattern) -> PatId {
+        let ptr = SyntaxNodePtr::new(pat.syntax());
+        let pat = match pat {
+            ast::Pattern::VariablePattern(var) => match var.name().and_then(lower_name) {
+                Some(name) => Pattern::Variable(name),
+                None => Pattern::Missing,
+            },
+            ast::Pattern::DiscardPattern(_) => Pattern::Discard,
+            ast::Pattern::LiteralPattern(lit) => lit
+                .literal()
+                .and_then(|lit| lower_literal(&lit))
+                .map_or(Pattern::Missing, Pattern::Literal),
+            ast::Pattern::TuplePattern(tuple) => {
+                Pattern::Tuple(tuple.elements().map(|p| self.lower_pat(p)).collect())
+            }
+            ast::Pattern::ListPattern(list) => {
+                let elements = list.elements().map(|p| self.lower_pat(p)).collect();
+                let rest = list.rest().map(|rest| match rest.pattern() {
+                    Some(pat) => self.lower_pat(pat),
+                    None => self.patterns.alloc(Pattern::Discard),
+                });
+                Pattern::List { elements, rest }
+            }
+            ast::Pattern::ConstructorPattern(ctor) => {
+                let module = ctor
+                    .qualified_ref()
+                    .and_then(|it| it.module())
+                    .and_then(|it| it.token());
+                match ctor.constructor().and_then(|it| it.token()) {
+                    Some(name) => Pattern::Constructor {
+                        module: module.map(|tok| tok.text().into()),
+                        name: name.text().into(),
+                        args: ctor
+                            .args()
+                            .map(|arg| PatternArg {
+                                label: arg.label().and_then(lower_name),
+                                pattern: self.lower_pat_opt(arg.pattern()),
+                            })
+                            .collect(),
+                        has_spread: ctor.has_spread(),
+                    },
+                    None => Pattern::Missing,
+                }
+            }
+            ast::Pattern::ConcatPattern(concat) => Pattern::Concat {
+                prefix: concat
+                    .prefix()
+                    .and_then(|lit| lit.string_value())
+                    .map(Into::into),
+                prefix_name: concat.prefix_name().and_then(lower_name),
+                rest: self.lower_pat_opt(concat.rest()),
+            },
+            // Alternatives only appear as the patterns of case clauses, where they are lowered.
+            ast::Pattern::AlternativePattern(_) => Pattern::Missing,
+            ast::Pattern::BitArray(bits) => {
+                let segments = bits
+                    .segments()
+                    .map(|seg| BitArraySegment {
+                        value: self.lower_pat_opt(seg.pattern()),
+                        options: self.lower_bit_array_options(&seg),
+                    })
+                    .collect();
+                Pattern::BitArray(segments)
+            }
+        };
+        self.alloc_pat(pat, ptr)
+    }
+}
+
+fn lower_name(name: ast::Name) -> Option<Name> {
+    Some(name.token()?.text().into())
+}
+
+fn lower_literal(lit: &ast::Literal) -> Option<Literal> {
+    Some(match lit.kind()? {
+        ast::LiteralKind::Int => {
+            let tok = lit.token()?;
+            let (radix, digits) = split_radix(tok.text());
+            Literal::Int(i64::from_str_radix(&digits.replace('_', ""), radix).ok())
+        }
+        ast::LiteralKind::Float => Literal::Float(lit.float_value().map(OrderedFloat)),
+        ast::LiteralKind::String => Literal::String(lit.string_value().map(Into::into)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expr, Literal, Pattern, Statement};
+    use crate::def::{FunctionId, ItemLoc, ModuleId, ModuleItem};
+    use crate::tests::TestDB;
+    use crate::DefDatabase;
+    use syntax::ast::{self, AstNode};
+    use syntax::{AstPtr, SyntaxNodePtr};
+
+    fn body_of(src: &str) -> (TestDB, FunctionId) {
+        let (db, file) = TestDB::single_file(src).unwrap();
+        let ModuleItem::Function(func) = db.item_tree(file).items[0] else {
+            panic!("not a function");
+        };
+        let func = db.intern_function(ItemLoc::new(ModuleId(file), func));
+        (db, func)
+    }
+
+    #[test]
+    fn lower() {
+        let (db, func) = body_of(
+            "fn f(a, with _b) {\n  let #(x, [y, ..]) = a\n  case x { 1 | 2 -> y.0 _ -> todo }\n}",
+        );
+        let body = db.body(func);
+        assert_eq!(body.params.len(), 2);
+        assert_eq!(body[body.params[0].pattern], Pattern::Variable("a".into()));
+        assert_eq!(body.params[1].label, Some("with".into()));
+        assert_eq!(body[body.params[1].pattern], Pattern::Discard);
+
+        let Expr::Block(stmts) = &body[body.body_expr] else {
+            panic!("not a block");
+        };
+        let [Statement::Let { pattern, .. }, Statement::Expr(case)] = &stmts[..] else {
+            panic!("unexpected statements: {stmts:?}");
+        };
+        let Pattern::Tuple(elems) = &body[*pattern] else {
+            panic!("not a tuple pattern");
+        };
+        assert!(matches!(
+            &body[elems[1]],
+            Pattern::List { rest: Some(_), .. }
+        ));
+        let Expr::Case { clauses, .. } = &body[*case] else {
+            panic!("not a case");
+        };
+        let alts = &clauses[0].alternatives;
+        assert_eq!(alts.len(), 2);
+        assert_eq!(body[alts[1][0]], Pattern::Literal(Literal::Int(Some(2))));
+        assert!(matches!(
+            body[clauses[0].body],
+            Expr::TupleIndex { index: 0, .. }
+        ));
+        assert_eq!(body[clauses[1].body], Expr::Todo(None));
+    }
+
+    #[test]
+    fn source_map() {
+        let src = "fn f(a) { a + 0x1F }";
+        let (db, func) = body_of(src);
+        let (body, source_map) = db.body_with_source_map(func);
+        let root = db.parse(func.lookup(&db).module.0).syntax_node();
+        let bin = root.descendants().find_map(ast::BinaryExpr::cast).unwrap();
+        let rhs = bin.rhs().unwrap();
+        let id = source_map.expr_for_node(AstPtr::new(&rhs)).unwrap();
+        assert_eq!(body[id], Expr::Literal(Literal::Int(Some(31))));
+        assert_eq!(source_map.node_for_expr(id), Some(AstPtr::new(&rhs)));
+
+        let param = root.descendants().find_map(ast::Param::cast).unwrap();
+        let pat = source_map
+            .pattern_for_node(SyntaxNodePtr::new(param.syntax()))
+            .unwrap();
+        assert_eq!(body.params[0].pattern, pat);
+    }
+}
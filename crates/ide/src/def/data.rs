@@ -0,0 +1,232 @@
+//! The constructors of custom types and their fields, for checks and completions on records.
+//!
+//! Only labels and positions are known. Field types are not resolved yet.
+use std::sync::Arc;
+
+use super::{ConstructorId, CustomTypeId, DefDatabase, Name};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TypeConstructors {
+    constructors: Vec<ConstructorData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstructorData {
+    pub name: Name,
+    pub fields: Arc<ConstructorFields>,
+}
+
+/// The fields of a constructor in declaration order, as in `Person(String, age: Int)`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConstructorFields {
+    /// The label of each field, `None` for positional ones.
+    labels: Vec<Option<Name>>,
+}
+
+impl TypeConstructors {
+    pub fn len(&self) -> usize {
+        self.constructors.len()
+    }
+
+    /// Types without constructors are external or opaque on the outside.
+    pub fn is_empty(&self) -> bool {
+        self.constructors.is_empty()
+    }
+
+    pub fn iter(
+        &self,
+        parent: CustomTypeId,
+    ) -> impl Iterator<Item = (ConstructorId, &ConstructorData)> + '_ {
+        (0u32..)
+            .zip(&self.constructors)
+            .map(move |(index, data)| (ConstructorId { parent, index }, data))
+    }
+
+    pub fn get(&self, index: u32) -> Option<&ConstructorData> {
+        self.constructors.get(index as usize)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<u32> {
+        let pos = self
+            .constructors
+            .iter()
+            .position(|data| data.name.as_str() == name)?;
+        Some(pos as u32)
+    }
+
+    /// Labels at the same position in every constructor, with that position. Only these can
+    /// be accessed as `value.label` without knowing the constructor.
+    pub fn shared_fields(&self) -> Vec<(u32, Name)> {
+        let Some((first, rest)) = self.constructors.split_first() else {
+            return Vec::new();
+        };
+        first
+            .fields
+            .labeled()
+            .filter(|&(index, label)| {
+                rest.iter()
+                    .all(|data| data.fields.label(index) == Some(label))
+            })
+            .map(|(index, label)| (index, label.clone()))
+            .collect()
+    }
+}
+
+impl ConstructorFields {
+    pub fn arity(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn label(&self, index: u32) -> Option<&Name> {
+        self.labels.get(index as usize)?.as_ref()
+    }
+
+    /// The position of the field labelled `label`.
+    pub fn index_of(&self, label: &str) -> Option<u32> {
+        self.labeled()
+            .find(|(_, name)| name.as_str() == label)
+            .map(|(index, _)| index)
+    }
+
+    pub fn labeled(&self) -> impl Iterator<Item = (u32, &Name)> + '_ {
+        (0u32..)
+            .zip(&self.labels)
+            .filter_map(|(index, label)| Some((index, label.as_ref()?)))
+    }
+
+    pub fn positional_count(&self) -> usize {
+        self.labels.iter().filter(|label| label.is_none()).count()
+    }
+}
+
+pub(crate) fn type_constructors(db: &dyn DefDatabase, ty: CustomTypeId) -> TypeConstructors {
+    let loc = ty.lookup(db);
+    let tree = db.item_tree(loc.module.0);
+    let constructors = tree[loc.id]
+        .constructors
+        .iter()
+        .map(|ctor| ConstructorData {
+            name: ctor.name.clone(),
+            fields: Arc::new(ConstructorFields {
+                labels: ctor.fields.clone(),
+            }),
+        })
+        .collect();
+    TypeConstructors { constructors }
+}
+
+pub(crate) fn constructor_fields(
+    db: &dyn DefDatabase,
+    ctor: ConstructorId,
+) -> Arc<ConstructorFields> {
+    db.type_constructors(ctor.parent)
+        .get(ctor.index)
+        .map(|data| data.fields.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::def::{ConstructorId, CustomTypeId, ItemLoc, ModuleId, ModuleItem};
+    use crate::tests::TestDB;
+    use crate::DefDatabase;
+    use expect_test::{expect, Expect};
+    use std::fmt::Write;
+
+    fn custom_types(db: &TestDB, file: crate::FileId) -> Vec<CustomTypeId> {
+        db.item_tree(file)
+            .items
+            .iter()
+            .filter_map(|&item| match item {
+                ModuleItem::CustomType(id) => {
+                    Some(db.intern_custom_type(ItemLoc::new(ModuleId(file), id)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[track_caller]
+    fn check(src: &str, expect: Expect) {
+        let (db, file) = TestDB::single_file(src).unwrap();
+        let mut out = String::new();
+        for ty in custom_types(&db, file) {
+            let ctors = db.type_constructors(ty);
+            let shared = ctors
+                .shared_fields()
+                .iter()
+                .map(|(index, label)| format!("{label}@{index}"))
+                .collect::<Vec<_>>();
+            let name = &db.item_tree(file)[ty.lookup(&db).id].name;
+            writeln!(out, "{name} shared=[{}]", shared.join(", ")).unwrap();
+            for (ctor, data) in ctors.iter(ty) {
+                let fields = db.constructor_fields(ctor);
+                let labels = (0..fields.arity() as u32)
+                    .map(|index| fields.label(index).map_or("_".into(), |l| l.to_string()))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    out,
+                    "  {}/{}({})",
+                    data.name,
+                    fields.arity(),
+                    labels.join(", ")
+                )
+                .unwrap();
+            }
+        }
+        expect.assert_eq(&out);
+    }
+
+    #[test]
+    fn constructors() {
+        check(
+            "
+type Shape { Circle(x: Int, y: Int, radius: Int) Rect(x: Int, y: Int, w: Int, h: Int) }
+type Pair(a) { Pair(a, a) }
+type Mixed { A(Int, label: Int) B(label: Int) }
+type Unit { Unit }
+external type Ref
+",
+            expect![[r#"
+                Shape shared=[x@0, y@1]
+                  Circle/3(x, y, radius)
+                  Rect/4(x, y, w, h)
+                Pair shared=[]
+                  Pair/2(_, _)
+                Mixed shared=[]
+                  A/2(_, label)
+                  B/1(label)
+                Unit shared=[]
+                  Unit/0()
+                Ref shared=[]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let (db, file) =
+            TestDB::single_file("type Person { Person(String, name: String, age: Int) }").unwrap();
+        let ty = custom_types(&db, file)[0];
+        let ctors = db.type_constructors(ty);
+        assert_eq!(ctors.len(), 1);
+        assert_eq!(ctors.index_of("Person"), Some(0));
+        assert_eq!(ctors.index_of("Nobody"), None);
+
+        let fields = db.constructor_fields(ConstructorId {
+            parent: ty,
+            index: 0,
+        });
+        assert_eq!(fields.arity(), 3);
+        assert_eq!(fields.positional_count(), 1);
+        assert_eq!(fields.index_of("age"), Some(2));
+        assert_eq!(fields.index_of("email"), None);
+
+        // Out of range constructors have no fields.
+        let missing = db.constructor_fields(ConstructorId {
+            parent: ty,
+            index: 1,
+        });
+        assert_eq!(missing.arity(), 0);
+    }
+}
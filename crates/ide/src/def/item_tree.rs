@@ -0,0 +1,453 @@
+//! The items of a module with their names and signatures, but without bodies or positions.
+//!
+//! Edits to whitespace, comments or function bodies give an equal `ItemTree`, so queries only
+//! depending on it, like name resolution, are not recomputed while typing. Positions are kept
+//! in the separate `ItemTreeSourceMap`.
+use std::collections::HashMap;
+use std::ops;
+
+use la_arena::{Arena, Idx};
+use syntax::ast::{self, AstNode, HasAttributes, HasVisibility};
+use syntax::AstPtr;
+
+use super::{ModuleName, Name};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ItemTree {
+    pub functions: Arena<Function>,
+    pub constants: Arena<Constant>,
+    pub custom_types: Arena<CustomType>,
+    pub type_aliases: Arena<TypeAlias>,
+    pub imports: Arena<Import>,
+    /// All items in source order.
+    pub items: Vec<ModuleItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleItem {
+    Function(Idx<Function>),
+    Constant(Idx<Constant>),
+    CustomType(Idx<CustomType>),
+    TypeAlias(Idx<TypeAlias>),
+    Import(Idx<Import>),
+}
+
+/// A function, including the deprecated `external fn`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: Name,
+    pub is_public: bool,
+    /// The target of an `@target(erlang)` attribute or of the enclosing target group, as in
+    /// `if erlang { ... }`.
+    pub target: Option<Name>,
+    pub params: Vec<Param>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub label: Option<Name>,
+    /// `None` for the deprecated `external fn`, whose parameters only have types.
+    pub name: Option<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constant {
+    pub name: Name,
+    pub is_public: bool,
+    pub target: Option<Name>,
+}
+
+/// A custom type, including the deprecated `external type` without constructors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomType {
+    pub name: Name,
+    pub is_public: bool,
+    pub is_opaque: bool,
+    pub target: Option<Name>,
+    pub type_params: Vec<Name>,
+    pub constructors: Vec<Constructor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    pub name: Name,
+    /// The labels of the fields, `None` for positional ones.
+    pub fields: Vec<Option<Name>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeAlias {
+    pub name: Name,
+    pub is_public: bool,
+    pub target: Option<Name>,
+    pub type_params: Vec<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The full module path, eg. `gleam/list`.
+    pub module: ModuleName,
+    pub alias: Option<Name>,
+    pub target: Option<Name>,
+    pub unqualified: Vec<UnqualifiedImport>,
+}
+
+impl Import {
+    /// The name the module is referred to by, which is its alias or the last segment of its
+    /// path.
+    pub fn local_name(&self) -> &str {
+        self.alias
+            .as_ref()
+            .map_or_else(|| self.module.last_segment(), Name::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnqualifiedImport {
+    pub name: Name,
+    pub alias: Option<Name>,
+}
+
+impl UnqualifiedImport {
+    pub fn local_name(&self) -> &Name {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
+}
+
+macro_rules! impl_index {
+    ($($field:ident: $ty:ty),*) => {
+        $(
+            impl ops::Index<Idx<$ty>> for ItemTree {
+                type Output = $ty;
+                fn index(&self, index: Idx<$ty>) -> &Self::Output {
+                    &self.$field[index]
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(
+    functions: Function,
+    constants: Constant,
+    custom_types: CustomType,
+    type_aliases: TypeAlias,
+    imports: Import
+);
+
+/// The syntax of each item in an `ItemTree`, in both directions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ItemTreeSourceMap {
+    item_map: HashMap<AstPtr<ast::Statement>, ModuleItem>,
+    item_map_rev: HashMap<ModuleItem, AstPtr<ast::Statement>>,
+}
+
+impl ItemTreeSourceMap {
+    pub fn item_for_node(&self, node: AstPtr<ast::Statement>) -> Option<ModuleItem> {
+        self.item_map.get(&node).copied()
+    }
+
+    pub fn node_for_item(&self, item: ModuleItem) -> Option<AstPtr<ast::Statement>> {
+        self.item_map_rev.get(&item).copied()
+    }
+}
+
+/// Lower the items of `root`. Items without a name, or imports without a module path, are
+/// incomplete syntax and skipped.
+pub(crate) fn lower(root: &ast::SourceFile) -> (ItemTree, ItemTreeSourceMap) {
+    let mut ctx = LowerCtx::default();
+    for group in root.statements() {
+        let target = group
+            .target()
+            .and_then(|target| target.name())
+            .map(|tok| tok.text().into());
+        for stmt in group.statements() {
+            let target = attribute_target(&stmt).or_else(|| target.clone());
+            if let Some(item) = ctx.lower_item(&stmt, target) {
+                let ptr = AstPtr::new(&stmt);
+                ctx.tree.items.push(item);
+                ctx.source_map.item_map.insert(ptr, item);
+                ctx.source_map.item_map_rev.insert(item, ptr);
+            }
+        }
+    }
+    (ctx.tree, ctx.source_map)
+}
+
+#[derive(Default)]
+struct LowerCtx {
+    tree: ItemTree,
+    source_map: ItemTreeSourceMap,
+}
+
+impl LowerCtx {
+    fn lower_item(&mut self, stmt: &ast::Statement, target: Option<Name>) -> Option<ModuleItem> {
+        let is_public = stmt.is_public();
+        let item = match stmt {
+            ast::Statement::Import(import) => {
+                let module = import.module()?;
+                let path = module
+                    .module_path()
+                    .map(|path| path.token())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|path| !path.is_empty())?;
+                let unqualified = module
+                    .unqualified()
+                    .filter_map(|it| {
+                        Some(UnqualifiedImport {
+                            name: lower_name(it.name()?)?,
+                            alias: it.as_name().and_then(lower_name),
+                        })
+                    })
+                    .collect();
+                ModuleItem::Import(self.tree.imports.alloc(Import {
+                    module: ModuleName::from_segments(path.iter().map(|tok| tok.text())),
+                    alias: module.as_name().and_then(lower_name),
+                    target,
+                    unqualified,
+                }))
+            }
+            ast::Statement::Function(func) => {
+                let params = func
+                    .param_list()
+                    .into_iter()
+                    .flat_map(|list| list.params())
+                    .map(|param| Param {
+                        label: param.label().and_then(lower_name),
+                        name: param.name().and_then(lower_name),
+                    })
+                    .collect();
+                ModuleItem::Function(self.tree.functions.alloc(Function {
+                    name: lower_name(func.name()?)?,
+                    is_public,
+                    target,
+                    params,
+                }))
+            }
+            ast::Statement::ExternalFunction(func) => {
+                let params = func
+                    .param_list()
+                    .into_iter()
+                    .flat_map(|list| list.params())
+                    .map(|param| Param {
+                        label: param.label().and_then(lower_name),
+                        name: None,
+                    })
+                    .collect();
+                ModuleItem::Function(self.tree.functions.alloc(Function {
+                    name: lower_name(func.name()?)?,
+                    is_public,
+                    target,
+                    params,
+                }))
+            }
+            ast::Statement::ModuleConstant(constant) => {
+                ModuleItem::Constant(self.tree.constants.alloc(Constant {
+                    name: lower_name(constant.name()?)?,
+                    is_public,
+                    target,
+                }))
+            }
+            ast::Statement::CustomType(ty) => {
+                let constructors = ty
+                    .constructors()
+                    .filter_map(|ctor| {
+                        Some(Constructor {
+                            name: lower_name(ctor.name()?)?,
+                            fields: ctor
+                                .fields()
+                                .map(|field| field.label().and_then(lower_name))
+                                .collect(),
+                        })
+                    })
+                    .collect();
+                ModuleItem::CustomType(self.tree.custom_types.alloc(CustomType {
+                    name: lower_name(ty.name()?)?,
+                    is_public,
+                    is_opaque: ty.is_opaque(),
+                    target,
+                    type_params: lower_type_params(ty.type_params()),
+                    constructors,
+                }))
+            }
+            ast::Statement::ExternalType(ty) => {
+                ModuleItem::CustomType(self.tree.custom_types.alloc(CustomType {
+                    name: lower_name(ty.name()?)?,
+                    is_public,
+                    is_opaque: false,
+                    target,
+                    type_params: lower_type_params(ty.type_params()),
+                    constructors: Vec::new(),
+                }))
+            }
+            ast::Statement::TypeAlias(alias) => {
+                ModuleItem::TypeAlias(self.tree.type_aliases.alloc(TypeAlias {
+                    name: lower_name(alias.name()?)?,
+                    is_public,
+                    target,
+                    type_params: lower_type_params(alias.type_params()),
+                }))
+            }
+        };
+        Some(item)
+    }
+}
+
+fn lower_name(name: ast::Name) -> Option<Name> {
+    Some(name.token()?.text().into())
+}
+
+/// The argument of `@target(...)`, as in `@target(erlang)`.
+fn attribute_target(stmt: &ast::Statement) -> Option<Name> {
+    stmt.attributes()
+        .filter(|attr| {
+            attr.name()
+                .and_then(lower_name)
+                .map_or(false, |name| name.as_str() == "target")
+        })
+        .find_map(|attr| {
+            let arg = attr.arguments().next()?;
+            Some(arg.syntax().text().to_string().trim().into())
+        })
+}
+
+fn lower_type_params(params: ast::AstChildren<ast::TypeParam>) -> Vec<Name> {
+    params
+        .filter_map(|param| lower_name(param.name()?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ItemTree, ModuleItem};
+    use crate::base::SourceDatabase;
+    use crate::tests::TestDB;
+    use crate::DefDatabase;
+    use expect_test::{expect, Expect};
+    use std::fmt::Write;
+    use syntax::ast;
+
+    fn dump(tree: &ItemTree) -> String {
+        let mut out = String::new();
+        for &item in &tree.items {
+            let (target, line) = match item {
+                ModuleItem::Function(idx) => {
+                    let f = &tree[idx];
+                    let params = f
+                        .params
+                        .iter()
+                        .map(|p| format!("{:?} {:?}", p.label, p.name))
+                        .collect::<Vec<_>>();
+                    (&f.target, format!("fn {}({})", f.name, params.join(", ")))
+                }
+                ModuleItem::Constant(idx) => {
+                    let c = &tree[idx];
+                    (&c.target, format!("const {} pub={}", c.name, c.is_public))
+                }
+                ModuleItem::CustomType(idx) => {
+                    let t = &tree[idx];
+                    let ctors = t
+                        .constructors
+                        .iter()
+                        .map(|c| format!("{}{:?}", c.name, c.fields))
+                        .collect::<Vec<_>>();
+                    let line = format!(
+                        "type {}{:?} opaque={} {{ {} }}",
+                        t.name,
+                        t.type_params,
+                        t.is_opaque,
+                        ctors.join(" "),
+                    );
+                    (&t.target, line)
+                }
+                ModuleItem::TypeAlias(idx) => {
+                    let t = &tree[idx];
+                    (&t.target, format!("alias {}{:?}", t.name, t.type_params))
+                }
+                ModuleItem::Import(idx) => {
+                    let i = &tree[idx];
+                    let uq = i
+                        .unqualified
+                        .iter()
+                        .map(|uq| format!("{} as {}", uq.name, uq.local_name()))
+                        .collect::<Vec<_>>();
+                    let line = format!(
+                        "import {} as {} {{{}}}",
+                        i.module,
+                        i.local_name(),
+                        uq.join(", ")
+                    );
+                    (&i.target, line)
+                }
+            };
+            match target {
+                Some(target) => writeln!(out, "[{target}] {line}").unwrap(),
+                None => writeln!(out, "{line}").unwrap(),
+            }
+        }
+        out
+    }
+
+    #[track_caller]
+    fn check(src: &str, expect: Expect) {
+        let (db, file) = TestDB::single_file(src).unwrap();
+        expect.assert_eq(&dump(&db.item_tree(file)));
+    }
+
+    #[test]
+    fn items() {
+        check(
+            r#"
+import gleam/list.{map, Some as S}
+import a as b
+pub const x = 1
+fn f(a, with b: Int) { a }
+if erlang {
+  external fn g(Int, label: String) -> Int = "m" "f"
+  pub external type Ref
+}
+pub opaque type T(a) { A(Int, x: a) B }
+type L = List(Int)
+@target(javascript)
+fn now() { 1 }
+"#,
+            expect![[r#"
+                import gleam/list as list {map as map, Some as S}
+                import a as b {}
+                const x pub=true
+                fn f(None Some("a"), Some("with") Some("b"))
+                [erlang] fn g(None None, Some("label") None)
+                [erlang] type Ref[] opaque=false {  }
+                type T["a"] opaque=true { A[None, Some("x")] B[] }
+                alias L[]
+                [javascript] fn now()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        check(
+            "const = 1\nimport\ntype A { B }",
+            expect![[r#"
+                type A[] opaque=false { B[] }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn stable_across_body_edits() {
+        let (mut db, file) = TestDB::single_file("fn f() { 1 }\nconst x = 1").unwrap();
+        let before = db.item_tree(file);
+        db.set_file_content(file, "fn f() {\n  2 + 3\n}\n\nconst x = 1".into());
+        assert_eq!(db.item_tree(file), before);
+
+        let (tree, source_map) = db.item_tree_with_source_map(file);
+        let root = db.parse(file).syntax_node();
+        let item = tree.items[1];
+        let node = source_map.node_for_item(item).unwrap().to_node(&root);
+        assert!(matches!(node, ast::Statement::ModuleConstant(_)));
+        let ptr = syntax::AstPtr::new(&node);
+        assert_eq!(source_map.item_for_node(ptr), Some(item));
+    }
+}
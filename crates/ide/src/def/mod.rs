@@ -1,16 +1,281 @@
+mod body;
+mod data;
+mod item_tree;
+mod module_scope;
+mod name;
+mod resolve;
+mod scope;
+
 use crate::base::SourceDatabase;
-use crate::FileId;
+use crate::{FileId, SourceRootId, Target};
+
+use la_arena::Idx;
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use syntax::{NodeCache, Parse};
 
-use syntax::Parse;
+pub use self::body::{Body, BodySourceMap, Expr, ExprId, PatId, Pattern, Statement};
+pub use self::data::{ConstructorFields, TypeConstructors};
+pub use self::item_tree::{
+    Constant, CustomType, Function, Import, ItemTree, ItemTreeSourceMap, ModuleItem, TypeAlias,
+};
+pub use self::module_scope::{ImportedName, ModuleScope, ScopeEntry, TypeDef, ValueDef};
+pub use self::name::{ModuleName, Name};
+pub use self::resolve::{ModuleMap, NameResolution, Path, PathResolution};
+pub use self::scope::ExprScopes;
 
 pub use syntax::ast::{BinaryOpKind as BinaryOp, UnaryOpKind as UnaryOp};
 
 #[salsa::query_group(DefDatabaseStorage)]
 pub trait DefDatabase: SourceDatabase {
     fn parse(&self, file_id: FileId) -> Parse;
+
+    fn item_tree_with_source_map(&self, file_id: FileId)
+        -> (Arc<ItemTree>, Arc<ItemTreeSourceMap>);
+
+    /// Only changes when items are added, removed or change their signatures, not on edits of
+    /// function bodies.
+    fn item_tree(&self, file_id: FileId) -> Arc<ItemTree>;
+
+    fn item_tree_source_map(&self, file_id: FileId) -> Arc<ItemTreeSourceMap>;
+
+    #[salsa::interned]
+    fn intern_function(&self, loc: ItemLoc<Function>) -> FunctionId;
+
+    #[salsa::interned]
+    fn intern_constant(&self, loc: ItemLoc<Constant>) -> ConstantId;
+
+    #[salsa::interned]
+    fn intern_custom_type(&self, loc: ItemLoc<CustomType>) -> CustomTypeId;
+
+    #[salsa::interned]
+    fn intern_type_alias(&self, loc: ItemLoc<TypeAlias>) -> TypeAliasId;
+
+    fn body_with_source_map(&self, function: FunctionId) -> (Arc<Body>, Arc<BodySourceMap>);
+
+    fn body(&self, function: FunctionId) -> Arc<Body>;
+
+    fn body_source_map(&self, function: FunctionId) -> Arc<BodySourceMap>;
+
+    fn expr_scopes(&self, function: FunctionId) -> Arc<ExprScopes>;
+
+    fn type_constructors(&self, ty: CustomTypeId) -> Arc<TypeConstructors>;
+
+    /// Empty for constructors which do not exist.
+    fn constructor_fields(&self, ctor: ConstructorId) -> Arc<ConstructorFields>;
+
+    /// Only depends on the item tree, so it is not recomputed on edits of function bodies.
+    fn module_scope(&self, module: ModuleId) -> Arc<ModuleScope>;
+
+    fn module_map(&self, sid: SourceRootId) -> Arc<ModuleMap>;
+
+    /// The module an import refers to, `None` if no known file has its name.
+    fn resolve_import(&self, module: ModuleId, import: Idx<Import>) -> Option<ModuleId>;
+
+    /// The definition `path` refers to at the top level of `module`, when compiling for
+    /// `target`.
+    fn resolve_path(&self, module: ModuleId, target: Target, path: Path) -> Option<PathResolution>;
+
+    /// What each name in the body of `function` refers to.
+    fn name_resolution(&self, function: FunctionId) -> Arc<NameResolution>;
+}
+
+/// A module, which is a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(pub FileId);
+
+/// An item of the `ItemTree` of `module`.
+pub struct ItemLoc<N> {
+    pub module: ModuleId,
+    pub id: Idx<N>,
+}
+
+impl<N> ItemLoc<N> {
+    pub fn new(module: ModuleId, id: Idx<N>) -> Self {
+        Self { module, id }
+    }
+}
+
+// Derives would require the bounds on `N`.
+impl<N> fmt::Debug for ItemLoc<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ItemLoc")
+            .field("module", &self.module)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<N> Clone for ItemLoc<N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for ItemLoc<N> {}
+
+impl<N> PartialEq for ItemLoc<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.module == other.module && self.id == other.id
+    }
+}
+
+impl<N> Eq for ItemLoc<N> {}
+
+impl<N> Hash for ItemLoc<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.module.hash(state);
+        self.id.hash(state);
+    }
+}
+
+/// Interned handles of items across all modules, which are cheap to hash and compare. Look up
+/// the `ItemLoc` to get to the item in its `ItemTree`.
+macro_rules! define_item_id {
+    ($($id:ident: $item:ty, $lookup:ident;)*) => {
+        $(
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub struct $id(salsa::InternId);
+
+            impl salsa::InternKey for $id {
+                fn from_intern_id(v: salsa::InternId) -> Self {
+                    Self(v)
+                }
+
+                fn as_intern_id(&self) -> salsa::InternId {
+                    self.0
+                }
+            }
+
+            impl $id {
+                pub fn lookup(self, db: &dyn DefDatabase) -> ItemLoc<$item> {
+                    db.$lookup(self)
+                }
+            }
+        )*
+    };
+}
+
+define_item_id! {
+    FunctionId: Function, lookup_intern_function;
+    ConstantId: Constant, lookup_intern_constant;
+    CustomTypeId: CustomType, lookup_intern_custom_type;
+    TypeAliasId: TypeAlias, lookup_intern_type_alias;
+}
+
+/// A constructor of a custom type, by its position among the constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConstructorId {
+    pub parent: CustomTypeId,
+    pub index: u32,
+}
+
+/// The amount of source text parsed with a worker's cache before it is replaced by an empty
+/// one. The cache never forgets nodes, so it would otherwise keep those of every version of
+/// every file alive.
+const NODE_CACHE_LIMIT: usize = 64 << 20;
+
+thread_local! {
+    /// Green nodes are immutable and thread-safe, so each worker keeps its own cache
+    /// and identical subtrees across files are shared. The count is the source text parsed
+    /// with the cache so far.
+    static NODE_CACHE: RefCell<(NodeCache, usize)> = RefCell::default();
 }
 
 fn parse(db: &dyn DefDatabase, file_id: FileId) -> Parse {
+    let _p = crate::profile::span("parse");
     let content = db.file_content(file_id);
-    syntax::parse_file(&content)
+    NODE_CACHE.with(|cache| {
+        let (cache, parsed) = &mut *cache.borrow_mut();
+        *parsed += content.len();
+        if *parsed > NODE_CACHE_LIMIT {
+            *cache = NodeCache::default();
+            *parsed = content.len();
+        }
+        syntax::parse_file_with_cache(&content, db.file_language_version(file_id), cache)
+    })
+}
+
+fn item_tree_with_source_map(
+    db: &dyn DefDatabase,
+    file_id: FileId,
+) -> (Arc<ItemTree>, Arc<ItemTreeSourceMap>) {
+    let _p = crate::profile::span("item_tree");
+    let (tree, source_map) = item_tree::lower(&db.parse(file_id).root());
+    (Arc::new(tree), Arc::new(source_map))
+}
+
+fn item_tree(db: &dyn DefDatabase, file_id: FileId) -> Arc<ItemTree> {
+    db.item_tree_with_source_map(file_id).0
+}
+
+fn item_tree_source_map(db: &dyn DefDatabase, file_id: FileId) -> Arc<ItemTreeSourceMap> {
+    db.item_tree_with_source_map(file_id).1
+}
+
+fn body_with_source_map(
+    db: &dyn DefDatabase,
+    function: FunctionId,
+) -> (Arc<Body>, Arc<BodySourceMap>) {
+    let _p = crate::profile::span("body");
+    let loc = function.lookup(db);
+    let file = loc.module.0;
+    let root = db.parse(file).syntax_node();
+    let item = db
+        .item_tree_source_map(file)
+        .node_for_item(ModuleItem::Function(loc.id))
+        .expect("function is in the item tree")
+        .to_node(&root);
+    let (body, source_map) = body::lower(&item);
+    (Arc::new(body), Arc::new(source_map))
+}
+
+fn body(db: &dyn DefDatabase, function: FunctionId) -> Arc<Body> {
+    db.body_with_source_map(function).0
+}
+
+fn body_source_map(db: &dyn DefDatabase, function: FunctionId) -> Arc<BodySourceMap> {
+    db.body_with_source_map(function).1
+}
+
+fn expr_scopes(db: &dyn DefDatabase, function: FunctionId) -> Arc<ExprScopes> {
+    Arc::new(scope::compute(&db.body(function)))
+}
+
+fn type_constructors(db: &dyn DefDatabase, ty: CustomTypeId) -> Arc<TypeConstructors> {
+    Arc::new(data::type_constructors(db, ty))
+}
+
+fn constructor_fields(db: &dyn DefDatabase, ctor: ConstructorId) -> Arc<ConstructorFields> {
+    data::constructor_fields(db, ctor)
+}
+
+fn module_scope(db: &dyn DefDatabase, module: ModuleId) -> Arc<ModuleScope> {
+    let _p = crate::profile::span("module_scope");
+    Arc::new(module_scope::collect(db, module))
+}
+
+fn name_resolution(db: &dyn DefDatabase, function: FunctionId) -> Arc<NameResolution> {
+    let _p = crate::profile::span("name_resolution");
+    Arc::new(resolve::name_resolution(db, function))
+}
+
+fn module_map(db: &dyn DefDatabase, sid: SourceRootId) -> Arc<ModuleMap> {
+    Arc::new(resolve::module_map(db, sid))
+}
+
+fn resolve_import(db: &dyn DefDatabase, module: ModuleId, import: Idx<Import>) -> Option<ModuleId> {
+    resolve::resolve_import(db, module, import)
+}
+
+fn resolve_path(
+    db: &dyn DefDatabase,
+    module: ModuleId,
+    target: Target,
+    path: Path,
+) -> Option<PathResolution> {
+    let _p = crate::profile::span("resolve_path");
+    resolve::resolve_path(db, module, target, &path)
 }
@@ -0,0 +1,292 @@
+//! The names defined or imported at the top level of a module.
+//!
+//! Values, types and modules are separate namespaces. Items with `@target(erlang)` or in a
+//! target group, as in `if erlang { ... }`, only exist for that target, so a name may have one
+//! definition for each target.
+use indexmap::IndexMap;
+use la_arena::Idx;
+
+use super::{
+    ConstantId, ConstructorId, CustomTypeId, DefDatabase, FunctionId, Import, ItemLoc, ModuleId,
+    ModuleItem, Name, TypeAliasId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueDef {
+    Function(FunctionId),
+    Constant(ConstantId),
+    Constructor(ConstructorId),
+    /// Imported unqualified, not yet resolved in the imported module.
+    Import(ImportedName),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeDef {
+    CustomType(CustomTypeId),
+    TypeAlias(TypeAliasId),
+    Import(ImportedName),
+}
+
+/// The `index`th unqualified name of `import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImportedName {
+    pub import: Idx<Import>,
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeEntry<D> {
+    pub def: D,
+    /// The only target the item exists for, if any.
+    pub target: Option<Name>,
+}
+
+impl<D> ScopeEntry<D> {
+    /// Whether the entry exists when compiling for `target`. An unknown target sees every
+    /// entry.
+    pub fn is_visible_for(&self, target: Option<&str>) -> bool {
+        match (&self.target, target) {
+            (Some(this), Some(target)) => this.as_str() == target,
+            _ => true,
+        }
+    }
+
+    /// Whether both entries exist for some target.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.is_visible_for(other.target.as_ref().map(Name::as_str))
+    }
+}
+
+/// Every definition of each name, local ones before imported ones, in source order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleScope {
+    values: IndexMap<Name, Vec<ScopeEntry<ValueDef>>>,
+    types: IndexMap<Name, Vec<ScopeEntry<TypeDef>>>,
+    modules: IndexMap<Name, Vec<ScopeEntry<Idx<Import>>>>,
+}
+
+impl ModuleScope {
+    pub fn resolve_value(&self, name: &str, target: Option<&str>) -> Option<ValueDef> {
+        resolve(&self.values, name, target)
+    }
+
+    pub fn resolve_type(&self, name: &str, target: Option<&str>) -> Option<TypeDef> {
+        resolve(&self.types, name, target)
+    }
+
+    /// The import binding the module `name`, as in `import gleam/list` for `list`.
+    pub fn resolve_module(&self, name: &str, target: Option<&str>) -> Option<Idx<Import>> {
+        resolve(&self.modules, name, target)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = (&Name, &[ScopeEntry<ValueDef>])> + '_ {
+        self.values
+            .iter()
+            .map(|(name, entries)| (name, &entries[..]))
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = (&Name, &[ScopeEntry<TypeDef>])> + '_ {
+        self.types
+            .iter()
+            .map(|(name, entries)| (name, &entries[..]))
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = (&Name, &[ScopeEntry<Idx<Import>>])> + '_ {
+        self.modules
+            .iter()
+            .map(|(name, entries)| (name, &entries[..]))
+    }
+}
+
+fn resolve<D: Copy>(
+    map: &IndexMap<Name, Vec<ScopeEntry<D>>>,
+    name: &str,
+    target: Option<&str>,
+) -> Option<D> {
+    map.get(name)?
+        .iter()
+        .find(|entry| entry.is_visible_for(target))
+        .map(|entry| entry.def)
+}
+
+fn push<D>(
+    map: &mut IndexMap<Name, Vec<ScopeEntry<D>>>,
+    name: &Name,
+    def: D,
+    target: &Option<Name>,
+) {
+    map.entry(name.clone()).or_default().push(ScopeEntry {
+        def,
+        target: target.clone(),
+    });
+}
+
+pub(crate) fn collect(db: &dyn DefDatabase, module: ModuleId) -> ModuleScope {
+    let tree = db.item_tree(module.0);
+    let mut scope = ModuleScope::default();
+
+    for &item in &tree.items {
+        match item {
+            ModuleItem::Function(id) => {
+                let it = &tree[id];
+                let def = ValueDef::Function(db.intern_function(ItemLoc::new(module, id)));
+                push(&mut scope.values, &it.name, def, &it.target);
+            }
+            ModuleItem::Constant(id) => {
+                let it = &tree[id];
+                let def = ValueDef::Constant(db.intern_constant(ItemLoc::new(module, id)));
+                push(&mut scope.values, &it.name, def, &it.target);
+            }
+            ModuleItem::CustomType(id) => {
+                let it = &tree[id];
+                let parent = db.intern_custom_type(ItemLoc::new(module, id));
+                push(
+                    &mut scope.types,
+                    &it.name,
+                    TypeDef::CustomType(parent),
+                    &it.target,
+                );
+                for (index, ctor) in (0u32..).zip(&it.constructors) {
+                    let def = ValueDef::Constructor(ConstructorId { parent, index });
+                    push(&mut scope.values, &ctor.name, def, &it.target);
+                }
+            }
+            ModuleItem::TypeAlias(id) => {
+                let it = &tree[id];
+                let def = TypeDef::TypeAlias(db.intern_type_alias(ItemLoc::new(module, id)));
+                push(&mut scope.types, &it.name, def, &it.target);
+            }
+            ModuleItem::Import(_) => {}
+        }
+    }
+
+    for (import, it) in tree.imports.iter() {
+        let local_name = Name::new(it.local_name());
+        push(&mut scope.modules, &local_name, import, &it.target);
+        for (index, uq) in (0u32..).zip(&it.unqualified) {
+            let imported = ImportedName { import, index };
+            // Upper case names are both a type and its constructor of the same name.
+            let name = uq.local_name();
+            if name.as_str().starts_with(|c: char| c.is_ascii_uppercase()) {
+                push(
+                    &mut scope.types,
+                    name,
+                    TypeDef::Import(imported),
+                    &it.target,
+                );
+            }
+            push(
+                &mut scope.values,
+                name,
+                ValueDef::Import(imported),
+                &it.target,
+            );
+        }
+    }
+
+    scope
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TypeDef, ValueDef};
+    use crate::def::{ModuleId, Name};
+    use crate::tests::TestDB;
+    use crate::DefDatabase;
+    use expect_test::{expect, Expect};
+    use std::fmt::Write;
+
+    #[track_caller]
+    fn check(src: &str, expect: Expect) {
+        let (db, file) = TestDB::single_file(src).unwrap();
+        let scope = db.module_scope(ModuleId(file));
+        let mut out = String::new();
+        let target = |target: &Option<Name>| match target {
+            Some(target) => format!(" [{target}]"),
+            None => String::new(),
+        };
+        for (name, entries) in scope.values() {
+            for entry in entries {
+                let kind = match entry.def {
+                    ValueDef::Function(_) => "fn",
+                    ValueDef::Constant(_) => "const",
+                    ValueDef::Constructor(_) => "constructor",
+                    ValueDef::Import(_) => "import",
+                };
+                writeln!(out, "value {name}: {kind}{}", target(&entry.target)).unwrap();
+            }
+        }
+        for (name, entries) in scope.types() {
+            for entry in entries {
+                let kind = match entry.def {
+                    TypeDef::CustomType(_) => "type",
+                    TypeDef::TypeAlias(_) => "alias",
+                    TypeDef::Import(_) => "import",
+                };
+                writeln!(out, "type {name}: {kind}{}", target(&entry.target)).unwrap();
+            }
+        }
+        for (name, entries) in scope.modules() {
+            for entry in entries {
+                writeln!(out, "module {name}{}", target(&entry.target)).unwrap();
+            }
+        }
+        expect.assert_eq(&out);
+    }
+
+    #[test]
+    fn items() {
+        check(
+            "
+import gleam/list.{map as m, Option}
+import a as b
+const x = 1
+pub fn f() { 1 }
+type T { A B(Int) }
+type L = List(Int)
+",
+            expect![[r#"
+                value x: const
+                value f: fn
+                value A: constructor
+                value B: constructor
+                value m: import
+                value Option: import
+                type T: type
+                type L: alias
+                type Option: import
+                module list
+                module b
+            "#]],
+        );
+    }
+
+    #[test]
+    fn targets() {
+        let src = "
+if erlang { fn f() { 1 } }
+if javascript { fn f() { 2 } }
+fn g() { 3 }
+";
+        check(
+            src,
+            expect![[r#"
+                value f: fn [erlang]
+                value f: fn [javascript]
+                value g: fn
+            "#]],
+        );
+
+        let (db, file) = TestDB::single_file(src).unwrap();
+        let scope = db.module_scope(ModuleId(file));
+        let (Some(ValueDef::Function(erl)), Some(ValueDef::Function(js))) = (
+            scope.resolve_value("f", Some("erlang")),
+            scope.resolve_value("f", Some("javascript")),
+        ) else {
+            panic!("not functions");
+        };
+        assert_ne!(erl, js);
+        assert!(scope.resolve_value("g", Some("erlang")).is_some());
+        assert!(scope.resolve_value("h", None).is_none());
+    }
+}
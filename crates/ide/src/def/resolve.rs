@@ -0,0 +1,445 @@
+//! Resolution of names across modules, following imports to the module defining an item.
+use std::collections::HashMap;
+
+use la_arena::{ArenaMap, Idx};
+
+use super::{
+    DefDatabase, Expr, ExprId, FunctionId, Import, ModuleId, ModuleName, Name, PatId, TypeDef,
+    ValueDef,
+};
+use crate::{SourceRootId, Target};
+
+/// The modules of a source root by their name, as in `gleam/list`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleMap {
+    modules: HashMap<ModuleName, ModuleId>,
+}
+
+impl ModuleMap {
+    pub fn get(&self, name: &str) -> Option<ModuleId> {
+        self.modules.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ModuleName, ModuleId)> + '_ {
+        self.modules.iter().map(|(name, &module)| (name, module))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    Value,
+    Type,
+}
+
+/// A reference to an item, as in `map`, `list.map` or `option.Option`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path {
+    pub namespace: Namespace,
+    /// The module name or alias before the dot.
+    pub qualifier: Option<Name>,
+    pub name: Name,
+}
+
+impl Path {
+    pub fn value(qualifier: Option<Name>, name: Name) -> Self {
+        Self {
+            namespace: Namespace::Value,
+            qualifier,
+            name,
+        }
+    }
+
+    pub fn type_(qualifier: Option<Name>, name: Name) -> Self {
+        Self {
+            namespace: Namespace::Type,
+            qualifier,
+            name,
+        }
+    }
+}
+
+/// The definition a path refers to. Imports are followed, so `Import` variants never appear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathResolution {
+    Value(ValueDef),
+    Type(TypeDef),
+}
+
+/// What a name in a function body refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResolveResult {
+    /// A local variable, by the pattern binding it.
+    Local(PatId),
+    Definition(PathResolution),
+}
+
+/// The resolution of each `Expr::Variable` and `Expr::ModuleAccess` of a function body, as a
+/// side table indexed by `ExprId`. Names which resolve to nothing have no entry, and neither
+/// have field accesses of local variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameResolution {
+    target: Target,
+    resolutions: ArenaMap<ExprId, ResolveResult>,
+}
+
+impl NameResolution {
+    /// The target names are resolved for, which is the only one a function limited to a target
+    /// is compiled for.
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    pub fn get(&self, expr: ExprId) -> Option<ResolveResult> {
+        self.resolutions.get(expr).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ExprId, ResolveResult)> + '_ {
+        self.resolutions.iter().map(|(expr, &res)| (expr, res))
+    }
+}
+
+pub(crate) fn name_resolution(db: &dyn DefDatabase, function: FunctionId) -> NameResolution {
+    let loc = function.lookup(db);
+    let target = db.item_tree(loc.module.0)[loc.id]
+        .target
+        .as_ref()
+        .and_then(|target| Target::from_name(target.as_str()))
+        .unwrap_or_else(|| db.file_target(loc.module.0));
+    let body = db.body(function);
+    let scopes = db.expr_scopes(function);
+    let mut resolutions = ArenaMap::default();
+    for (expr, kind) in body.exprs.iter() {
+        let path = match kind {
+            Expr::Variable(name) => {
+                if let Some(pat) = scopes.resolve_local(expr, name.as_str()) {
+                    resolutions.insert(expr, ResolveResult::Local(pat));
+                    continue;
+                }
+                Path::value(None, name.clone())
+            }
+            Expr::ModuleAccess { module, name }
+                if scopes.resolve_local(expr, module.as_str()).is_none() =>
+            {
+                Path::value(Some(module.clone()), name.clone())
+            }
+            _ => continue,
+        };
+        if let Some(def) = db.resolve_path(loc.module, target, path) {
+            resolutions.insert(expr, ResolveResult::Definition(def));
+        }
+    }
+    NameResolution {
+        target,
+        resolutions,
+    }
+}
+
+pub(crate) fn module_map(db: &dyn DefDatabase, sid: SourceRootId) -> ModuleMap {
+    let root = db.source_root(sid);
+    let modules = root
+        .files()
+        .filter_map(|(file, _)| {
+            let name = root.module_name_for_file(file)?;
+            Some((ModuleName::new(&name), ModuleId(file)))
+        })
+        .collect();
+    ModuleMap { modules }
+}
+
+/// The module named by `import`. Modules of the same source root take precedence over those of
+/// dependencies.
+pub(crate) fn resolve_import(
+    db: &dyn DefDatabase,
+    module: ModuleId,
+    import: Idx<Import>,
+) -> Option<ModuleId> {
+    let tree = db.item_tree(module.0);
+    let name = &tree[import].module;
+    let own_root = db.file_source_root(module.0);
+    std::iter::once(own_root)
+        .chain(
+            db.source_root_ids()
+                .iter()
+                .copied()
+                .filter(|&sid| sid != own_root),
+        )
+        .find_map(|sid| db.module_map(sid).get(name.as_str()))
+}
+
+/// Private items resolve too, so that references to them can still be navigated. Items only
+/// defined for another target than `target` do not resolve, in any module on the way.
+pub(crate) fn resolve_path(
+    db: &dyn DefDatabase,
+    module: ModuleId,
+    target: Target,
+    path: &Path,
+) -> Option<PathResolution> {
+    let scope = db.module_scope(module);
+    let target_name = Some(target.as_str());
+    if let Some(qualifier) = &path.qualifier {
+        let import = scope.resolve_module(qualifier.as_str(), target_name)?;
+        let imported_module = db.resolve_import(module, import)?;
+        return resolve_in_module(
+            db,
+            imported_module,
+            target,
+            path.namespace,
+            path.name.as_str(),
+        );
+    }
+
+    let imported = match path.namespace {
+        Namespace::Value => match scope.resolve_value(path.name.as_str(), target_name)? {
+            ValueDef::Import(imported) => imported,
+            def => return Some(PathResolution::Value(def)),
+        },
+        Namespace::Type => match scope.resolve_type(path.name.as_str(), target_name)? {
+            TypeDef::Import(imported) => imported,
+            def => return Some(PathResolution::Type(def)),
+        },
+    };
+    let tree = db.item_tree(module.0);
+    // Look up the original name, not the alias.
+    let name = &tree[imported.import].unqualified[imported.index as usize].name;
+    let imported_module = db.resolve_import(module, imported.import)?;
+    resolve_in_module(db, imported_module, target, path.namespace, name.as_str())
+}
+
+/// A definition of `module` itself. Its own imports are not re-exported.
+fn resolve_in_module(
+    db: &dyn DefDatabase,
+    module: ModuleId,
+    target: Target,
+    namespace: Namespace,
+    name: &str,
+) -> Option<PathResolution> {
+    let scope = db.module_scope(module);
+    let target = Some(target.as_str());
+    match namespace {
+        Namespace::Value => match scope.resolve_value(name, target)? {
+            ValueDef::Import(_) => None,
+            def => Some(PathResolution::Value(def)),
+        },
+        Namespace::Type => match scope.resolve_type(name, target)? {
+            TypeDef::Import(_) => None,
+            def => Some(PathResolution::Type(def)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Path, PathResolution, ResolveResult};
+    use crate::def::{Expr, ItemLoc, ModuleId, Name, TypeDef, ValueDef};
+    use crate::tests::TestDB;
+    use crate::{DefDatabase, SourceDatabase, Target};
+    use expect_test::{expect, Expect};
+
+    const LIB: &str = "
+#- /src/gleam/list.gleam
+pub fn map() { 1 }
+pub type Option { Some None }
+type Private = Int
+#- /src/gleam/dict.gleam
+import gleam/list.{map}
+pub fn new() { map() }
+#- /src/gleam/io.gleam
+@target(erlang)
+pub fn println() { 1 }
+if javascript {
+  pub fn println() { 2 }
+  pub fn log() { 3 }
+}
+";
+
+    #[track_caller]
+    fn check(src: &str, paths: &[Path], expect: Expect) {
+        check_for(Target::Erlang, src, paths, expect);
+    }
+
+    #[track_caller]
+    fn check_for(target: Target, src: &str, paths: &[Path], expect: Expect) {
+        let (db, f) = TestDB::from_fixture(&format!("{LIB}#- /src/main.gleam\n{src}")).unwrap();
+        let main = ModuleId(f["/src/main.gleam"]);
+        let got = paths
+            .iter()
+            .map(|path| {
+                let resolved = db.resolve_path(main, target, path.clone());
+                let (module, kind, name) = match resolved {
+                    None => return "unresolved".to_owned(),
+                    Some(PathResolution::Value(ValueDef::Function(id))) => {
+                        let loc = id.lookup(&db);
+                        (
+                            loc.module,
+                            "fn",
+                            db.item_tree(loc.module.0)[loc.id].name.clone(),
+                        )
+                    }
+                    Some(PathResolution::Value(ValueDef::Constructor(id))) => {
+                        let loc = id.parent.lookup(&db);
+                        let tree = db.item_tree(loc.module.0);
+                        let name = tree[loc.id].constructors[id.index as usize].name.clone();
+                        (loc.module, "constructor", name)
+                    }
+                    Some(PathResolution::Type(TypeDef::CustomType(id))) => {
+                        let loc = id.lookup(&db);
+                        (
+                            loc.module,
+                            "type",
+                            db.item_tree(loc.module.0)[loc.id].name.clone(),
+                        )
+                    }
+                    Some(PathResolution::Type(TypeDef::TypeAlias(id))) => {
+                        let loc = id.lookup(&db);
+                        (
+                            loc.module,
+                            "alias",
+                            db.item_tree(loc.module.0)[loc.id].name.clone(),
+                        )
+                    }
+                    Some(res) => return format!("{res:?}"),
+                };
+                let root = db.source_root(db.file_source_root(module.0));
+                let module = root.module_name_for_file(module.0).unwrap();
+                format!("{module}: {kind} {name}")
+            })
+            .map(|line| line + "\n")
+            .collect::<String>();
+        expect.assert_eq(&got);
+    }
+
+    fn value(qualifier: Option<&str>, name: &str) -> Path {
+        Path::value(qualifier.map(Name::new), Name::new(name))
+    }
+
+    fn type_(qualifier: Option<&str>, name: &str) -> Path {
+        Path::type_(qualifier.map(Name::new), Name::new(name))
+    }
+
+    #[test]
+    fn qualified() {
+        check(
+            "import gleam/list\nimport gleam/dict as d",
+            &[
+                value(Some("list"), "map"),
+                value(Some("list"), "Some"),
+                type_(Some("list"), "Option"),
+                type_(Some("list"), "Private"),
+                value(Some("d"), "new"),
+                value(Some("dict"), "new"),
+            ],
+            expect![[r#"
+                gleam/list: fn map
+                gleam/list: constructor Some
+                gleam/list: type Option
+                gleam/list: alias Private
+                gleam/dict: fn new
+                unresolved
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unqualified() {
+        check(
+            "import gleam/list.{map as m, Option}\nfn local() { 1 }",
+            &[
+                value(None, "m"),
+                value(None, "map"),
+                type_(None, "Option"),
+                value(None, "Option"),
+                value(None, "local"),
+            ],
+            expect![[r#"
+                gleam/list: fn map
+                unresolved
+                gleam/list: type Option
+                unresolved
+                main: fn local
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_reexport() {
+        // `dict` imports `map` but does not define it.
+        check(
+            "import gleam/dict.{map}\nimport gleam/nope",
+            &[value(None, "map"), value(Some("nope"), "x")],
+            expect![[r#"
+                unresolved
+                unresolved
+            "#]],
+        );
+    }
+
+    #[test]
+    fn targets() {
+        let src = "import gleam/io.{log}";
+        let paths = [
+            value(Some("io"), "println"),
+            value(Some("io"), "log"),
+            value(None, "log"),
+        ];
+        check_for(
+            Target::Erlang,
+            src,
+            &paths,
+            expect![[r#"
+                gleam/io: fn println
+                unresolved
+                unresolved
+            "#]],
+        );
+        check_for(
+            Target::JavaScript,
+            src,
+            &paths,
+            expect![[r#"
+                gleam/io: fn println
+                gleam/io: fn log
+                gleam/io: fn log
+            "#]],
+        );
+    }
+
+    #[test]
+    fn name_resolution() {
+        let src = "import gleam/list\nimport gleam/io.{log}\nfn f(x) { list.map(x) log() x.y missing() x }";
+        let (db, f) = TestDB::from_fixture(&format!("{LIB}#- /src/main.gleam\n{src}")).unwrap();
+        let main = ModuleId(f["/src/main.gleam"]);
+        let (id, _) = db.item_tree(main.0).functions.iter().next().unwrap();
+        let function = db.intern_function(ItemLoc::new(main, id));
+        let body = db.body(function);
+        let resolutions = db.name_resolution(function);
+        assert_eq!(resolutions.target(), Target::Erlang);
+        let got = body
+            .exprs
+            .iter()
+            .filter_map(|(expr, kind)| {
+                let name = match kind {
+                    Expr::Variable(name) => name.as_str().to_owned(),
+                    Expr::ModuleAccess { module, name } => {
+                        format!("{}.{}", module.as_str(), name.as_str())
+                    }
+                    _ => return None,
+                };
+                let res = match resolutions.get(expr) {
+                    None => "unresolved",
+                    Some(ResolveResult::Local(_)) => "local",
+                    Some(ResolveResult::Definition(_)) => "definition",
+                };
+                Some(format!("{name}: {res}\n"))
+            })
+            .collect::<String>();
+        expect![[r#"
+            list.map: definition
+            x: local
+            log: unresolved
+            x.y: unresolved
+            missing: unresolved
+            x: local
+        "#]]
+        .assert_eq(&got);
+    }
+}
@@ -0,0 +1,370 @@
+//! Local variables visible in each expression of a function body.
+//!
+//! Every binding site opens a scope: the parameters, each `let` and `use` for the rest of its
+//! block, each case clause for its guard and body, and the parameters of anonymous functions
+//! for their body. Inner scopes shadow outer ones.
+use std::slice;
+
+use la_arena::{Arena, ArenaMap, Idx};
+use syntax::TextSize;
+
+use super::{Body, BodySourceMap, Expr, ExprId, Name, PatId, Pattern, Statement};
+
+pub type ScopeId = Idx<ScopeData>;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExprScopes {
+    scopes: Arena<ScopeData>,
+    scope_by_expr: ArenaMap<ExprId, ScopeId>,
+    /// The scope which binding patterns introduce their variables into.
+    scope_by_pat: ArenaMap<PatId, ScopeId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeData {
+    parent: Option<ScopeId>,
+    bindings: Vec<LocalBinding>,
+}
+
+/// A local variable and the pattern binding it. The prefix name of a string prefix pattern,
+/// as in `"pre" as p <> rest`, points to the whole `Pattern::Concat`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalBinding {
+    pub name: Name,
+    pub pat: PatId,
+}
+
+impl ExprScopes {
+    pub fn scope_for_expr(&self, expr: ExprId) -> Option<ScopeId> {
+        self.scope_by_expr.get(expr).copied()
+    }
+
+    /// `scope` and its parents, innermost first.
+    pub fn ancestors(&self, scope: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        std::iter::successors(Some(scope), |&scope| self.scopes[scope].parent)
+    }
+
+    /// The variables bound directly in `scope`, not in its parents.
+    pub fn bindings(&self, scope: ScopeId) -> &[LocalBinding] {
+        &self.scopes[scope].bindings
+    }
+
+    pub fn resolve_in_scope(&self, scope: ScopeId, name: &str) -> Option<&LocalBinding> {
+        self.ancestors(scope).find_map(|scope| {
+            self.bindings(scope)
+                .iter()
+                .find(|binding| binding.name.as_str() == name)
+        })
+    }
+
+    /// The pattern binding the local variable `name` where `expr` is, if any. Names not bound
+    /// locally refer to module items.
+    pub fn resolve_local(&self, expr: ExprId, name: &str) -> Option<PatId> {
+        let scope = self.scope_for_expr(expr)?;
+        Some(self.resolve_in_scope(scope, name)?.pat)
+    }
+
+    /// The scope at `offset` in the function, for completion where there may be no expression
+    /// yet. After a `let` or `use`, its bindings are in scope even if the next statement is
+    /// not written yet.
+    pub fn scope_at_offset(
+        &self,
+        body: &Body,
+        source_map: &BodySourceMap,
+        offset: TextSize,
+    ) -> Option<ScopeId> {
+        let range_of = |expr| Some(source_map.node_for_expr(expr)?.text_range());
+        let start_of = |pat| Some(source_map.node_for_pattern(pat)?.text_range().start());
+        let (expr, mut scope) = self
+            .scope_by_expr
+            .iter()
+            .filter_map(|(expr, &scope)| Some((expr, scope, range_of(expr)?)))
+            .filter(|(_, _, range)| range.contains_inclusive(offset))
+            .min_by_key(|(_, _, range)| range.len())
+            .map(|(expr, scope, _)| (expr, scope))?;
+
+        match &body[expr] {
+            Expr::Block(stmts) => {
+                for stmt in stmts {
+                    let (value, pats) = match stmt {
+                        Statement::Let { pattern, value, .. } => (*value, slice::from_ref(pattern)),
+                        Statement::Use { patterns, callee } => (*callee, &patterns[..]),
+                        Statement::Expr(_) => continue,
+                    };
+                    if range_of(value).map_or(true, |range| range.end() > offset) {
+                        break;
+                    }
+                    if let Some(&inner) = pats.first().and_then(|pat| self.scope_by_pat.get(*pat)) {
+                        scope = inner;
+                    }
+                }
+            }
+            Expr::Case { clauses, .. } => {
+                for clause in clauses {
+                    let Some(&pat) = clause.alternatives.first().and_then(|alt| alt.first()) else {
+                        continue;
+                    };
+                    if start_of(pat).map_or(true, |start| start > offset) {
+                        break;
+                    }
+                    scope = self.scope_by_pat[pat];
+                }
+            }
+            _ => {}
+        }
+        Some(scope)
+    }
+
+    fn new_scope(&mut self, parent: Option<ScopeId>) -> ScopeId {
+        self.scopes.alloc(ScopeData {
+            parent,
+            bindings: Vec::new(),
+        })
+    }
+
+    fn add_bindings(&mut self, body: &Body, scope: ScopeId, pat: PatId) {
+        self.scope_by_pat.insert(pat, scope);
+        match &body[pat] {
+            Pattern::Missing | Pattern::Discard | Pattern::Literal(_) => {}
+            Pattern::Variable(name) => self.scopes[scope].bindings.push(LocalBinding {
+                name: name.clone(),
+                pat,
+            }),
+            Pattern::Tuple(elems) => {
+                for &elem in elems {
+                    self.add_bindings(body, scope, elem);
+                }
+            }
+            Pattern::List { elements, rest } => {
+                for &elem in elements.iter().chain(rest) {
+                    self.add_bindings(body, scope, elem);
+                }
+            }
+            Pattern::Constructor { args, .. } => {
+                for arg in args {
+                    self.add_bindings(body, scope, arg.pattern);
+                }
+            }
+            Pattern::Concat {
+                prefix_name, rest, ..
+            } => {
+                if let Some(name) = prefix_name {
+                    self.scopes[scope].bindings.push(LocalBinding {
+                        name: name.clone(),
+                        pat,
+                    });
+                }
+                self.add_bindings(body, scope, *rest);
+            }
+            Pattern::BitArray(segments) => {
+                for seg in segments {
+                    self.add_bindings(body, scope, seg.value);
+                    // Sizes may refer to variables bound by earlier segments.
+                    for value in seg.options.iter().filter_map(|opt| opt.value) {
+                        self.walk_expr(body, value, scope);
+                    }
+                }
+            }
+        }
+    }
+
+    fn walk_expr(&mut self, body: &Body, expr: ExprId, scope: ScopeId) {
+        self.scope_by_expr.insert(expr, scope);
+        match &body[expr] {
+            Expr::Block(stmts) => {
+                let mut scope = scope;
+                for stmt in stmts {
+                    match stmt {
+                        // The value is evaluated before the pattern binds, so `let x = x + 1`
+                        // refers to the previous `x`.
+                        Statement::Let { pattern, value, .. } => {
+                            self.walk_expr(body, *value, scope);
+                            scope = self.new_scope(Some(scope));
+                            self.add_bindings(body, scope, *pattern);
+                        }
+                        Statement::Use { patterns, callee } => {
+                            self.walk_expr(body, *callee, scope);
+                            scope = self.new_scope(Some(scope));
+                            for &pat in patterns {
+                                self.add_bindings(body, scope, pat);
+                            }
+                        }
+                        Statement::Expr(expr) => self.walk_expr(body, *expr, scope),
+                    }
+                }
+            }
+            Expr::Case { subjects, clauses } => {
+                for &subject in subjects {
+                    self.walk_expr(body, subject, scope);
+                }
+                for clause in clauses {
+                    // Every alternative binds the same names, so the first one shadows the rest.
+                    let clause_scope = self.new_scope(Some(scope));
+                    for &pat in clause.alternatives.iter().flatten() {
+                        self.add_bindings(body, clause_scope, pat);
+                    }
+                    if let Some(guard) = clause.guard {
+                        self.walk_expr(body, guard, clause_scope);
+                    }
+                    self.walk_expr(body, clause.body, clause_scope);
+                }
+            }
+            Expr::Lambda {
+                params,
+                body: lambda_body,
+            } => {
+                let lambda_scope = self.new_scope(Some(scope));
+                for param in params {
+                    self.add_bindings(body, lambda_scope, param.pattern);
+                }
+                self.walk_expr(body, *lambda_body, lambda_scope);
+            }
+            e => e.walk_child_exprs(|child| self.walk_expr(body, child, scope)),
+        }
+    }
+}
+
+pub(crate) fn compute(body: &Body) -> ExprScopes {
+    let mut this = ExprScopes::default();
+    let root = this.new_scope(None);
+    for param in &body.params {
+        this.add_bindings(body, root, param.pattern);
+    }
+    this.walk_expr(body, body.body_expr, root);
+    this
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::def::{FunctionId, ItemLoc, ModuleId, ModuleItem};
+    use crate::tests::TestDB;
+    use crate::DefDatabase;
+    use expect_test::{expect, Expect};
+    use syntax::ast;
+    use syntax::AstPtr;
+
+    fn function_at(db: &TestDB, file: crate::FileId) -> FunctionId {
+        let ModuleItem::Function(func) = db.item_tree(file).items[0] else {
+            panic!("not a function");
+        };
+        db.intern_function(ItemLoc::new(ModuleId(file), func))
+    }
+
+    /// The text of the pattern the variable at the marker resolves to.
+    #[track_caller]
+    fn check_resolve(fixture: &str, expect: Expect) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let pos = f[0];
+        let func = function_at(&db, pos.file_id);
+        let source_map = db.body_source_map(func);
+        let name_ref = db.node_at::<ast::NameRef>(pos).expect("no name at marker");
+        let name = name_ref.token().unwrap();
+        let expr = source_map
+            .expr_for_node(AstPtr::new(&ast::Expr::NameRef(name_ref)))
+            .unwrap();
+        let got = match db.expr_scopes(func).resolve_local(expr, name.text()) {
+            Some(pat) => {
+                let root = db.parse(pos.file_id).syntax_node();
+                let node = source_map.node_for_pattern(pat).unwrap().to_node(&root);
+                format!("{}: {}", u32::from(node.text_range().start()), node.text())
+            }
+            None => "unresolved".to_owned(),
+        };
+        expect.assert_eq(&got);
+    }
+
+    /// The names in scope at the marker, innermost first.
+    #[track_caller]
+    fn check_scope(fixture: &str, expect: Expect) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let pos = f[0];
+        let func = function_at(&db, pos.file_id);
+        let (body, source_map) = db.body_with_source_map(func);
+        let scopes = db.expr_scopes(func);
+        let scope = scopes
+            .scope_at_offset(&body, &source_map, pos.pos)
+            .expect("no scope at marker");
+        let names = scopes
+            .ancestors(scope)
+            .flat_map(|scope| scopes.bindings(scope))
+            .map(|binding| binding.name.to_string())
+            .collect::<Vec<_>>();
+        expect.assert_eq(&names.join(" "));
+    }
+
+    #[test]
+    fn param() {
+        check_resolve("fn f(a, with b) { a + $0b }", expect!["8: with b"]);
+    }
+
+    #[test]
+    fn let_shadowing() {
+        check_resolve("fn f(x) { let x = x + 1\n let y = $0x }", expect!["14: x"]);
+        check_resolve("fn f(x) { let x = $0x + 1 }", expect!["5: x"]);
+    }
+
+    #[test]
+    fn use_binding() {
+        check_resolve(
+            "fn f() { use a, #(b, _) <- list.map([])\n a + $0b }",
+            expect!["18: b"],
+        );
+    }
+
+    #[test]
+    fn case_clause() {
+        check_resolve(
+            "fn f(x) { case x { [x, ..rest] if x > 0 -> $0rest _ -> x } }",
+            expect!["25: rest"],
+        );
+        check_resolve(
+            "fn f(x) { case x { Ok(y) | Error(y) -> $0y _ -> x } }",
+            expect!["22: y"],
+        );
+        check_resolve(
+            "fn f(x) { case x { Ok(y) -> y _ -> $0y } }",
+            expect!["unresolved"],
+        );
+    }
+
+    #[test]
+    fn string_prefix() {
+        check_resolve(
+            "fn f(s) { let \"a\" as p <> rest = s\n $0p }",
+            expect![[r#"14: "a" as p <> rest"#]],
+        );
+    }
+
+    #[test]
+    fn bit_array_size() {
+        check_resolve(
+            "fn f(s) { let <<n, data:bytes-size($0n)>> = s\n data }",
+            expect!["16: n"],
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        check_resolve("fn f(xs) { list.map(xs, fn(x) { $0x }) }", expect!["27: x"]);
+        check_resolve(
+            "fn f(xs) { let g = fn(x) { x }\n $0x }",
+            expect!["unresolved"],
+        );
+    }
+
+    #[test]
+    fn item_is_not_local() {
+        check_resolve("fn f() { $0g() }\nfn g() { 1 }", expect!["unresolved"]);
+    }
+
+    #[test]
+    fn scope_at_offset() {
+        check_scope("fn f(a) { let b = 1\n $0 }", expect!["b a"]);
+        check_scope("fn f(a) { let b = $0 }", expect!["a"]);
+        check_scope(
+            "fn f(a) { use b <- g()\n let c = 1\n $0b }",
+            expect!["c b a"],
+        );
+        check_scope("fn f(a) { case a { #(x, y) -> $0 } }", expect!["x y a"]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::FileRange;
+use crate::{FileRange, Target};
 use core::fmt;
 use syntax::{ErrorKind as SynErrorKind, TextRange};
 
@@ -16,8 +16,18 @@ pub enum DiagnosticKind {
     // Lowering.
 
     // Name resolution.
+    DuplicateImport,
+    DuplicateDefinition,
+    DuplicateLabel,
+    /// A reference to an item which only exists for the given other target.
+    WrongTarget(Target),
 
     // Liveness.
+
+    // Style.
+    NonSnakeCase,
+    NonPascalCase,
+    DeprecatedExternal,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -43,20 +53,47 @@ impl Diagnostic {
 
     pub fn code(&self) -> &'static str {
         match self.kind {
+            DiagnosticKind::SyntaxError(SynErrorKind::ReservedWord) => "reserved_word",
             DiagnosticKind::SyntaxError(_) => "syntax_error",
+            DiagnosticKind::DuplicateImport => "duplicate_import",
+            DiagnosticKind::DuplicateDefinition => "duplicate_definition",
+            DiagnosticKind::DuplicateLabel => "duplicate_label",
+            DiagnosticKind::WrongTarget(_) => "wrong_target",
+            DiagnosticKind::NonSnakeCase => "non_snake_case",
+            DiagnosticKind::NonPascalCase => "non_pascal_case",
+            DiagnosticKind::DeprecatedExternal => "deprecated_external",
         }
     }
 
     pub fn severity(&self) -> Severity {
         match self.kind {
-            DiagnosticKind::SyntaxError(_) => Severity::Error,
+            DiagnosticKind::SyntaxError(_)
+            | DiagnosticKind::DuplicateImport
+            | DiagnosticKind::DuplicateDefinition
+            | DiagnosticKind::DuplicateLabel
+            | DiagnosticKind::WrongTarget(_) => Severity::Error,
+            DiagnosticKind::NonSnakeCase
+            | DiagnosticKind::NonPascalCase
+            | DiagnosticKind::DeprecatedExternal => Severity::Warning,
         }
     }
 
     pub fn message(&self) -> String {
         match self.kind {
             DiagnosticKind::SyntaxError(kind) => return kind.to_string(),
+            DiagnosticKind::DuplicateImport => "Duplicate import binding",
+            DiagnosticKind::DuplicateDefinition => "Duplicate definition",
+            DiagnosticKind::DuplicateLabel => "Duplicate label",
+            DiagnosticKind::WrongTarget(target) => {
+                return format!("Only defined for the {target} target")
+            }
+            DiagnosticKind::NonSnakeCase => "Name should be snake_case",
+            DiagnosticKind::NonPascalCase => "Name should be PascalCase",
+            DiagnosticKind::DeprecatedExternal => {
+                "The `external` syntax is deprecated, use the `@external` attribute instead"
+            }
         }
+        .into()
     }
 
     // pub fn is_unnecessary(&self) -> bool {
@@ -69,12 +106,9 @@ impl Diagnostic {
     //     )
     // }
 
-    // pub fn is_deprecated(&self) -> bool {
-    //     matches!(
-    //         self.kind,
-    //         DiagnosticKind::LetAttrset | DiagnosticKind::UriLiteral
-    //     )
-    // }
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self.kind, DiagnosticKind::DeprecatedExternal)
+    }
 
     pub fn debug_display(&self) -> impl fmt::Display + '_ {
         struct Wrapper<'a>(&'a Diagnostic);
@@ -93,8 +127,8 @@ impl Diagnostic {
     }
 }
 
-impl From<syntax::Error> for Diagnostic {
-    fn from(err: syntax::Error) -> Self {
+impl From<syntax::ParseError> for Diagnostic {
+    fn from(err: syntax::ParseError) -> Self {
         Self::new(err.range, DiagnosticKind::SyntaxError(err.kind))
     }
 }
@@ -0,0 +1,115 @@
+//! Subsequence fuzzy matching for completion filtering and symbol search.
+//!
+//! A query matches a candidate if all query chars appear in order. Among all possible
+//! alignments, the best scoring one is taken: matches at word boundaries (after `_`, `.`,
+//! `/`, or at a lower-to-upper case transition) and consecutive matches are preferred,
+//! gaps are penalized. So `lmap` matches `list.map` and `tpse` matches
+//! `to_parsed_string_expr`.
+//!
+//! Matching is smart-case: lowercase query chars match both cases, uppercase ones only
+//! match themselves.
+
+const MATCH: i32 = 16;
+const BOUNDARY: i32 = 8;
+const CONSECUTIVE: i32 = 8;
+const GAP: i32 = 1;
+const LEADING_GAP_MAX: i32 = 4;
+
+/// Returns the score of the best alignment of `query` in `candidate`, or `None` if it does not
+/// match at all. Higher is better. The empty query matches everything with score 0.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<i32> {
+    let query = query.chars().collect::<Vec<_>>();
+    let candidate = candidate.chars().collect::<Vec<_>>();
+    if query.is_empty() {
+        return Some(0);
+    }
+    if query.len() > candidate.len() {
+        return None;
+    }
+
+    let bonus = (0..candidate.len())
+        .map(|j| {
+            if is_boundary(j.checked_sub(1).map(|k| candidate[k]), candidate[j]) {
+                BOUNDARY
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // `prev[j]` is the best score of the query prefix so far, with its last char matched
+    // at `candidate[j]`.
+    let mut prev = vec![None::<i32>; candidate.len()];
+    for (i, &q) in query.iter().enumerate() {
+        let mut cur = vec![None; candidate.len()];
+        for (j, &c) in candidate.iter().enumerate() {
+            if !char_matches(q, c) {
+                continue;
+            }
+            let here = MATCH + bonus[j];
+            cur[j] = if i == 0 {
+                Some(here - (j as i32).min(LEADING_GAP_MAX))
+            } else {
+                (0..j)
+                    .filter_map(|k| {
+                        let score = prev[k]?;
+                        let link = if k + 1 == j {
+                            CONSECUTIVE
+                        } else {
+                            -GAP * (j - k - 1) as i32
+                        };
+                        Some(score + link)
+                    })
+                    .max()
+                    .map(|score| score + here)
+            };
+        }
+        prev = cur;
+    }
+    prev.into_iter().flatten().max()
+}
+
+fn char_matches(q: char, c: char) -> bool {
+    if q.is_uppercase() {
+        q == c
+    } else {
+        q == c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+fn is_boundary(prev: Option<char>, cur: char) -> bool {
+    match prev {
+        None => true,
+        Some(prev) if matches!(prev, '_' | '.' | '/' | ':' | '#') || prev.is_whitespace() => true,
+        Some(prev) => (prev.is_lowercase() || prev.is_ascii_digit()) && cur.is_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    #[test]
+    fn subsequence() {
+        assert!(fuzzy_match("lmap", "list.map").is_some());
+        assert!(fuzzy_match("tpse", "to_parsed_string_expr").is_some());
+        assert!(fuzzy_match("", "anything").is_some());
+        assert!(fuzzy_match("xyz", "list.map").is_none());
+        assert!(fuzzy_match("mapp", "map").is_none());
+    }
+
+    #[test]
+    fn smart_case() {
+        assert!(fuzzy_match("ok", "Ok").is_some());
+        assert!(fuzzy_match("O", "ok").is_none());
+        assert!(fuzzy_match("HR", "HttpRequest").is_some());
+    }
+
+    #[test]
+    fn ranking() {
+        let score = |q, c| fuzzy_match(q, c).unwrap();
+        assert!(score("map", "map") > score("map", "flat_map"));
+        assert!(score("lm", "list_map") > score("lm", "elm_x"));
+        assert!(score("hr", "HttpRequest") > score("hr", "shred"));
+    }
+}
@@ -0,0 +1,155 @@
+//! Quick fix for the `non_snake_case` lint. Parameters are renamed where they are used too.
+//!
+//! ```gleam
+//! const MaxPort = 8080
+//! ```
+//! =>
+//! ```gleam
+//! const max_port = 8080
+//! ```
+use super::{AssistKind, AssistsCtx};
+use crate::def::{Expr, ItemLoc, ModuleId, ModuleItem};
+use syntax::ast::{self, AstNode};
+use syntax::{AstPtr, SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken};
+
+pub(crate) fn convert_to_snake_case(ctx: &mut AssistsCtx) -> Option<()> {
+    let name = ctx.covering_node::<ast::Name>()?;
+    let parent = name.syntax().parent()?;
+    // Unqualified imports of types and constructors are upper case by design.
+    let binds_value = matches!(
+        parent.kind(),
+        SyntaxKind::MODULE_CONSTANT
+            | SyntaxKind::FUNCTION
+            | SyntaxKind::EXTERNAL_FUNCTION
+            | SyntaxKind::IMPORT_MODULE
+            | SyntaxKind::PARAM
+    );
+    let tok = name.token()?;
+    if !binds_value || tok.kind() != SyntaxKind::U_IDENT {
+        return None;
+    }
+    let new_name = to_snake_case(tok.text());
+    let mut editor = ctx.editor();
+    editor.replace(tok.clone(), &new_name);
+    // Labels are used by callers instead.
+    let is_param_name =
+        ast::Param::cast(parent.clone()).and_then(|param| param.name()) == Some(name);
+    if is_param_name {
+        for tok in param_uses(ctx, &parent)? {
+            editor.replace(tok, &new_name);
+        }
+    }
+    ctx.add(
+        "convert_to_snake_case",
+        format!("Rename to `{new_name}`"),
+        AssistKind::QuickFix,
+        tok.text_range(),
+        editor,
+    );
+    Some(())
+}
+
+/// The names referring to the parameter `param` in the body of its function.
+fn param_uses(ctx: &AssistsCtx, param: &SyntaxNode) -> Option<Vec<SyntaxToken>> {
+    let db = ctx.db;
+    let func = param.ancestors().find_map(ast::Function::cast)?;
+    let source_map = db.item_tree_source_map(ctx.frange.file_id);
+    let ModuleItem::Function(id) =
+        source_map.item_for_node(AstPtr::new(&ast::Statement::Function(func)))?
+    else {
+        return None;
+    };
+    let function = db.intern_function(ItemLoc::new(ModuleId(ctx.frange.file_id), id));
+    let (body, body_source_map) = db.body_with_source_map(function);
+    let scopes = db.expr_scopes(function);
+    let pat = body_source_map.pattern_for_node(SyntaxNodePtr::new(param))?;
+    let root = ctx.ast().syntax();
+    let uses = body
+        .exprs
+        .iter()
+        .filter(|(expr, kind)| match kind {
+            // A module access on a local variable is a field access.
+            Expr::Variable(name) | Expr::ModuleAccess { module: name, .. } => {
+                scopes.resolve_local(*expr, name.as_str()) == Some(pat)
+            }
+            _ => false,
+        })
+        .filter_map(|(expr, _)| body_source_map.node_for_expr(expr))
+        .filter_map(|ptr| ptr.to_node(root).syntax().first_token())
+        .collect();
+    Some(uses)
+}
+
+/// `HTTPServer2` becomes `http_server2`.
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut ret = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let prev_lower = chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit();
+            let next_lower = chars.get(i + 1).map_or(false, |c| c.is_lowercase());
+            if prev_lower || (next_lower && chars[i - 1].is_uppercase()) {
+                ret.push('_');
+            }
+        }
+        ret.extend(c.to_lowercase());
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check, check_no};
+    use super::to_snake_case;
+    use expect_test::expect;
+
+    #[test]
+    fn snake_case() {
+        assert_eq!(to_snake_case("MaxPort"), "max_port");
+        assert_eq!(to_snake_case("HTTPServer2"), "http_server2");
+        assert_eq!(to_snake_case("A"), "a");
+    }
+
+    #[test]
+    fn constant() {
+        check(
+            super::convert_to_snake_case,
+            "const Max$0Port = 1",
+            expect!["const max_port = 1"],
+        );
+    }
+
+    #[test]
+    fn module_alias() {
+        check(
+            super::convert_to_snake_case,
+            "import gleam/list as L$0i",
+            expect!["import gleam/list as li"],
+        );
+    }
+
+    #[test]
+    fn function() {
+        check(
+            super::convert_to_snake_case,
+            "fn Ma$0in() { 1 }",
+            expect!["fn main() { 1 }"],
+        );
+    }
+
+    #[test]
+    fn param() {
+        check(
+            super::convert_to_snake_case,
+            "fn f(Label $0X) { let y = X.0\n fn(X) { X } }",
+            expect!["fn f(Label x) { let y = x.0\n fn(X) { X } }"],
+        );
+    }
+
+    #[test]
+    fn no_fix() {
+        check_no(super::convert_to_snake_case, "const max_$0port = 1");
+        check_no(super::convert_to_snake_case, "import a.{$0B}");
+        check_no(super::convert_to_snake_case, "type $0A { B }");
+    }
+}
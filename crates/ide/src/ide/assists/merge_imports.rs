@@ -0,0 +1,166 @@
+//! Merge imports of the same module into one statement.
+//!
+//! ```gleam
+//! import gleam/list
+//! import gleam/list.{map}
+//! ```
+//! =>
+//! ```gleam
+//! import gleam/list.{map}
+//! ```
+use super::{AssistKind, AssistsCtx};
+use syntax::ast::{self, make, AstNode};
+
+pub(crate) fn merge_imports(ctx: &mut AssistsCtx) -> Option<()> {
+    let import = ctx.covering_node::<ast::Import>()?;
+    let key = ImportKey::new(&import)?;
+    let others = ctx
+        .ast()
+        .imports()
+        .filter(|it| *it != import)
+        .filter(|it| ImportKey::new(it).as_ref() == Some(&key))
+        .collect::<Vec<_>>();
+    if others.is_empty() {
+        return None;
+    }
+
+    let mut items = Vec::<String>::new();
+    for it in std::iter::once(&import).chain(&others) {
+        for uq in it.module()?.unqualified() {
+            let text = uq.syntax().text().to_string().trim().to_owned();
+            if !items.contains(&text) {
+                items.push(text);
+            }
+        }
+    }
+
+    let items = items.iter().map(String::as_str).collect::<Vec<_>>();
+    let merged = match &key.alias {
+        Some(alias) => make::import_as(&key.path, &items, alias),
+        None => make::import(&key.path, &items),
+    };
+
+    let mut editor = ctx.editor();
+    editor.replace(import.syntax().clone(), merged.syntax());
+    for it in &others {
+        // Target groups left without statements are removed altogether.
+        let group = it.syntax().parent().and_then(ast::TargetGroup::cast);
+        match group {
+            Some(group)
+                if group
+                    .statements()
+                    .all(|stmt| others.iter().any(|it| it.syntax() == stmt.syntax())) =>
+            {
+                editor.delete(group.syntax().clone())
+            }
+            _ => editor.delete(it.syntax().clone()),
+        }
+    }
+    ctx.add(
+        "merge_imports",
+        format!("Merge imports of {}", key.path),
+        AssistKind::RefactorRewrite,
+        import.syntax().text_range(),
+        editor,
+    );
+    Some(())
+}
+
+/// Imports can only be merged if they import the same module under the same name,
+/// for the same target.
+#[derive(Debug, PartialEq, Eq)]
+struct ImportKey {
+    path: String,
+    alias: Option<String>,
+    target: Option<String>,
+}
+
+impl ImportKey {
+    fn new(import: &ast::Import) -> Option<Self> {
+        let module = import.module()?;
+        let path = module
+            .module_path()
+            .map(|path| Some(path.token()?.text().to_owned()))
+            .collect::<Option<Vec<_>>>()?
+            .join("/");
+        if path.is_empty() {
+            return None;
+        }
+        let alias = module
+            .as_name()
+            .and_then(|name| name.token())
+            .map(|tok| tok.text().to_owned());
+        let target = import
+            .syntax()
+            .ancestors()
+            .find_map(ast::TargetGroup::cast)
+            .and_then(|group| group.target())
+            .map(|target| target.syntax().text().to_string());
+        Some(Self {
+            path,
+            alias,
+            target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check, check_no};
+    use expect_test::expect;
+
+    #[track_caller]
+    fn check_merge(fixture: &str, expect: expect_test::Expect) {
+        check(super::merge_imports, fixture, expect);
+    }
+
+    #[test]
+    fn merge_plain_import() {
+        check_merge(
+            "import gleam/list\nimport gleam/io\nimpo$0rt gleam/list.{map}",
+            expect!["import gleam/io\nimport gleam/list.{map}"],
+        );
+    }
+
+    #[test]
+    fn merge_unqualified() {
+        check_merge(
+            "impo$0rt a.{x, y as z}\nimport b\nimport a.{y as z, w}",
+            expect!["import a.{x, y as z, w}\nimport b"],
+        );
+    }
+
+    #[test]
+    fn merge_alias() {
+        check_merge(
+            "import a as b\nimport a.{x} a$0s b",
+            expect!["import a.{x} as b"],
+        );
+    }
+
+    #[test]
+    fn different_alias() {
+        check_no(super::merge_imports, "import a as b\nimport a.{x} a$0s c");
+        check_no(super::merge_imports, "import a\nimpor$0t a as c");
+    }
+
+    #[test]
+    fn merge_in_target_groups() {
+        check_merge(
+            "if erlang { impo$0rt a }\nimport b\nif erlang {\n  import a.{x}\n}",
+            expect!["if erlang { import a.{x} }\nimport b"],
+        );
+        check_merge(
+            "if erlang {\n  impo$0rt a\n}\nif erlang {\n  import a.{x}\n  import b\n}",
+            expect!["if erlang {\n  import a.{x}\n}\nif erlang {\n  import b\n}"],
+        );
+    }
+
+    #[test]
+    fn different_target() {
+        check_no(
+            super::merge_imports,
+            "if erlang { import a }\nif javascript { imp$0ort a }",
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Code actions which can be applied to the code at a given range.
+mod convert_to_snake_case;
+mod merge_imports;
+mod split_import;
+
+use crate::{DefDatabase, FileRange, SourceChange};
+use syntax::ast::edit::SyntaxEditor;
+use syntax::ast::{self, AstNode};
+use syntax::{algo, GleamLanguage, NodeOrToken, TextRange};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assist {
+    pub id: String,
+    pub label: String,
+    pub kind: AssistKind,
+    pub target: TextRange,
+    pub change: SourceChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssistKind {
+    QuickFix,
+    RefactorRewrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssistConfig {
+    /// Unqualified import lists longer than this can be split into qualified uses.
+    pub split_import_threshold: usize,
+}
+
+impl Default for AssistConfig {
+    fn default() -> Self {
+        Self {
+            split_import_threshold: 5,
+        }
+    }
+}
+
+pub(crate) fn assists(
+    db: &dyn DefDatabase,
+    frange: FileRange,
+    config: AssistConfig,
+) -> Vec<Assist> {
+    let handlers = [
+        convert_to_snake_case::convert_to_snake_case,
+        merge_imports::merge_imports,
+        split_import::split_import,
+    ];
+
+    let mut ctx = AssistsCtx::new(db, frange, config);
+    for handler in handlers {
+        handler(&mut ctx);
+    }
+    ctx.assists
+}
+
+pub(crate) struct AssistsCtx<'a> {
+    assists: Vec<Assist>,
+
+    pub(crate) db: &'a dyn DefDatabase,
+    pub(crate) config: AssistConfig,
+    pub(crate) frange: FileRange,
+    ast: ast::SourceFile,
+}
+
+impl<'a> AssistsCtx<'a> {
+    fn new(db: &'a dyn DefDatabase, frange: FileRange, config: AssistConfig) -> Self {
+        let ast = db.parse(frange.file_id).root();
+        Self {
+            assists: Vec::new(),
+            db,
+            config,
+            frange,
+            ast,
+        }
+    }
+
+    pub(crate) fn ast(&self) -> &ast::SourceFile {
+        &self.ast
+    }
+
+    /// An editor for the file, to be passed to `add` with the changes of an assist.
+    pub(crate) fn editor(&self) -> SyntaxEditor {
+        SyntaxEditor::new(self.ast.syntax().clone())
+    }
+
+    /// The smallest node of type `N` covering the selected range.
+    pub(crate) fn covering_node<N: AstNode<Language = GleamLanguage>>(&self) -> Option<N> {
+        match algo::covering_element(self.ast.syntax(), self.frange.range)? {
+            NodeOrToken::Token(t) => t.parent_ancestors().find_map(N::cast),
+            NodeOrToken::Node(n) => n.ancestors().find_map(N::cast),
+        }
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        id: &str,
+        label: impl Into<String>,
+        kind: AssistKind,
+        target: TextRange,
+        editor: SyntaxEditor,
+    ) {
+        let mut change = SourceChange::default();
+        for edit in editor.finish() {
+            change.insert(self.frange.file_id, edit);
+        }
+        self.assists.push(Assist {
+            id: id.into(),
+            label: label.into(),
+            kind,
+            target,
+            change,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssistConfig, AssistsCtx};
+    use crate::tests::TestDB;
+    use crate::SourceDatabase;
+    use expect_test::Expect;
+    use std::cmp::Reverse;
+
+    type Handler = fn(&mut AssistsCtx) -> Option<()>;
+
+    #[track_caller]
+    pub(crate) fn check(handler: Handler, fixture: &str, expect: Expect) {
+        check_with_config(handler, AssistConfig::default(), fixture, expect);
+    }
+
+    #[track_caller]
+    pub(crate) fn check_with_config(
+        handler: Handler,
+        config: AssistConfig,
+        fixture: &str,
+        expect: Expect,
+    ) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let frange = f.unwrap_single_range_marker();
+        let mut ctx = AssistsCtx::new(&db, frange, config);
+        handler(&mut ctx);
+        let [assist] = &ctx.assists[..] else {
+            panic!("Expect exactly one assist, got: {:#?}", ctx.assists);
+        };
+        let mut src = db.file_content(frange.file_id).to_string();
+        let mut edits = assist.change.file_edits[&frange.file_id].clone();
+        edits.sort_by_key(|edit| Reverse(edit.delete.start()));
+        for edit in edits {
+            edit.apply(&mut src);
+        }
+        expect.assert_eq(&src);
+    }
+
+    #[track_caller]
+    pub(crate) fn check_no(handler: Handler, fixture: &str) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let frange = f.unwrap_single_range_marker();
+        let mut ctx = AssistsCtx::new(&db, frange, AssistConfig::default());
+        handler(&mut ctx);
+        assert!(
+            ctx.assists.is_empty(),
+            "Unexpected assists: {:#?}",
+            ctx.assists
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Replace a long unqualified import list by qualified uses of the module.
+//!
+//! ```gleam
+//! import gleam/list.{filter, fold, map}
+//! fn f(xs) { map(xs, g) }
+//! ```
+//! =>
+//! ```gleam
+//! import gleam/list
+//! fn f(xs) { list.map(xs, g) }
+//! ```
+use super::{AssistKind, AssistsCtx};
+use crate::def::{
+    Expr, ImportedName, ItemLoc, ModuleId, ModuleItem, Name, Pattern, TypeDef, ValueDef,
+};
+use syntax::ast::{self, make, AstNode};
+use syntax::{AstPtr, SyntaxKind, SyntaxToken};
+
+pub(crate) fn split_import(ctx: &mut AssistsCtx) -> Option<()> {
+    let import = ctx.covering_node::<ast::Import>()?;
+    let module = import.module()?;
+    if module.unqualified().count() <= ctx.config.split_import_threshold {
+        return None;
+    }
+    let db = ctx.db;
+    let file = ctx.frange.file_id;
+    let item = db
+        .item_tree_source_map(file)
+        .item_for_node(AstPtr::new(&ast::Statement::Import(import.clone())))?;
+    let ModuleItem::Import(id) = item else {
+        return None;
+    };
+    let tree = db.item_tree(file);
+    let scope = db.module_scope(ModuleId(file));
+    let data = &tree[id];
+    // Aliases are dropped, uses refer to the original name.
+    let qualified = |imported: ImportedName| {
+        (imported.import == id).then(|| {
+            let name = &data.unqualified[imported.index as usize].name;
+            format!("{}.{name}", data.local_name())
+        })
+    };
+    let value = |name: &Name, target: Option<&Name>| match scope
+        .resolve_value(name.as_str(), target.map(Name::as_str))?
+    {
+        ValueDef::Import(imported) => qualified(imported),
+        _ => None,
+    };
+    let type_ =
+        |name: &str| match scope.resolve_type(name, data.target.as_ref().map(Name::as_str))? {
+            TypeDef::Import(imported) => qualified(imported),
+            _ => None,
+        };
+
+    let root = ctx.ast().syntax().clone();
+    let mut uses = Vec::<(SyntaxToken, String)>::new();
+
+    // Function bodies, where local variables may shadow the imported names.
+    for (fid, func) in tree.functions.iter() {
+        let function = db.intern_function(ItemLoc::new(ModuleId(file), fid));
+        let (body, source_map) = db.body_with_source_map(function);
+        let scopes = db.expr_scopes(function);
+        for (expr, kind) in body.exprs.iter() {
+            let Expr::Variable(name) = kind else {
+                continue;
+            };
+            if scopes.resolve_local(expr, name.as_str()).is_some() {
+                continue;
+            }
+            let Some(new) = value(name, func.target.as_ref()) else {
+                continue;
+            };
+            let tok = source_map
+                .node_for_expr(expr)
+                .and_then(|ptr| ptr.to_node(&root).syntax().first_token());
+            uses.extend(tok.map(|tok| (tok, new)));
+        }
+        for (pat, kind) in body.patterns.iter() {
+            let Pattern::Constructor {
+                module: None, name, ..
+            } = kind
+            else {
+                continue;
+            };
+            let Some(new) = value(name, func.target.as_ref()) else {
+                continue;
+            };
+            let tok = source_map
+                .node_for_pattern(pat)
+                .and_then(|ptr| ast::ConstructorPattern::cast(ptr.to_node(&root)))
+                .and_then(|pat| pat.constructor()?.token());
+            uses.extend(tok.map(|tok| (tok, new)));
+        }
+    }
+
+    // Constants and type annotations, which have no local variables.
+    for node in root.descendants() {
+        if let Some(ty) = ast::ConstructorType::cast(node.clone()) {
+            if ty.module().is_some() {
+                continue;
+            }
+            if let Some(tok) = ty.constructor().and_then(|name| name.token()) {
+                uses.extend(type_(tok.text()).map(|new| (tok, new)));
+            }
+        } else if let Some(name_ref) = ast::NameRef::cast(node) {
+            let in_constant = name_ref
+                .syntax()
+                .ancestors()
+                .any(|it| it.kind() == SyntaxKind::MODULE_CONSTANT);
+            let is_qualified = name_ref
+                .syntax()
+                .parent()
+                .map_or(false, |it| it.kind() == SyntaxKind::QUALIFIED_REF);
+            if !in_constant || is_qualified {
+                continue;
+            }
+            if let Some(tok) = name_ref.token() {
+                let new = value(&tok.text().into(), data.target.as_ref());
+                uses.extend(new.map(|new| (tok, new)));
+            }
+        }
+    }
+
+    let new_import = match &data.alias {
+        Some(alias) => make::import_as(data.module.as_str(), &[], alias.as_str()),
+        None => make::import(data.module.as_str(), &[]),
+    };
+    let new_module = new_import.module()?.syntax().to_string();
+    let mut editor = ctx.editor();
+    editor.replace(module.syntax().clone(), new_module.trim_start());
+    for (tok, new) in uses {
+        editor.replace(tok, new);
+    }
+    ctx.add(
+        "split_import",
+        format!("Use {} qualified", data.module.as_str()),
+        AssistKind::RefactorRewrite,
+        import.syntax().text_range(),
+        editor,
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check_no, check_with_config};
+    use super::super::AssistConfig;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check_split(fixture: &str, expect: Expect) {
+        let config = AssistConfig {
+            split_import_threshold: 1,
+        };
+        check_with_config(super::split_import, config, fixture, expect);
+    }
+
+    #[test]
+    fn split() {
+        check_split(
+            r#"
+#- /src/main.gleam
+imp$0ort gleam/option.{None, Option, Some as Just, map}
+const default: Option(Int) = None
+fn f(x: Option(Int)) -> Option(Int) {
+  case x {
+    Just(y) -> map(x, fn(map) { map })
+    None -> Just(0)
+  }
+}
+#- /src/gleam/option.gleam
+pub type Option(a) { Some(a) None }
+pub fn map(x, f) { x }
+            "#,
+            expect![[r#"
+                import gleam/option
+                const default: option.Option(Int) = option.None
+                fn f(x: option.Option(Int)) -> option.Option(Int) {
+                  case x {
+                    option.Some(y) -> option.map(x, fn(map) { map })
+                    option.None -> option.Some(0)
+                  }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn keep_alias() {
+        check_split(
+            "import gleam/list.{filter, map} as l$0\nfn f(xs) { map(filter(xs, g), g) }",
+            expect!["import gleam/list as l\nfn f(xs) { l.map(l.filter(xs, g), g) }"],
+        );
+    }
+
+    #[test]
+    fn short_list() {
+        check_no(
+            super::split_import,
+            "import gleam/list.{filter, map}$0\nfn f(xs) { map(xs, g) }",
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{DefDatabase, FileId};
+use syntax::{TextRange, TextSize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeLens {
+    pub range: TextRange,
+    pub kind: CodeLensKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeLensKind {
+    /// Run all tests of a test module, given by its module name.
+    RunModuleTests { module: String, tests: usize },
+}
+
+pub(crate) fn code_lenses(db: &dyn DefDatabase, file: FileId) -> Vec<CodeLens> {
+    let root = db.source_root(db.file_source_root(file));
+    if !root.is_test_file(file) {
+        return Vec::new();
+    }
+    let Some(module) = root.module_name_for_file(file) else {
+        return Vec::new();
+    };
+    // Test runners call public functions without parameters whose names end in `_test`.
+    let tree = db.item_tree(file);
+    let tests = tree
+        .functions
+        .iter()
+        .filter(|(_, f)| f.is_public && f.params.is_empty() && f.name.as_str().ends_with("_test"))
+        .count();
+    if tests == 0 {
+        return Vec::new();
+    }
+    vec![CodeLens {
+        range: TextRange::empty(TextSize::from(0)),
+        kind: CodeLensKind::RunModuleTests { module, tests },
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::TestDB;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file) = TestDB::single_file(fixture).unwrap();
+        let lenses = super::code_lenses(&db, file)
+            .into_iter()
+            .map(|lens| format!("{:?}: {:?}\n", lens.range, lens.kind))
+            .collect::<String>();
+        expect.assert_eq(&lenses);
+    }
+
+    #[test]
+    fn module_tests() {
+        check(
+            r#"
+#- /test/app/parser_test.gleam
+pub fn parse_test() { 1 }
+pub fn lex_test() { 2 }
+fn private_test() { 3 }
+pub fn helper(x_test) { 4 }
+            "#,
+            expect![[r#"
+                0..0: RunModuleTests { module: "app/parser_test", tests: 2 }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_test_module() {
+        check("#- /src/app.gleam\npub fn parse_test() { 1 }", expect![""]);
+        check(
+            "#- /test/app_test.gleam\npub fn helper() { 1 }",
+            expect![""],
+        );
+    }
+}
@@ -1,18 +1,353 @@
-use crate::{DefDatabase, Diagnostic, FileId};
+use crate::def::{
+    Expr, ItemLoc, ItemTreeSourceMap, ModuleId, ModuleItem, Path, ScopeEntry, TypeDef, ValueDef,
+};
+use crate::{profile, DefDatabase, Diagnostic, DiagnosticKind, FileId, FileRange};
+use syntax::ast::{self, AstNode, HasName};
+use syntax::{SyntaxKind, SyntaxNode, SyntaxToken, TextRange};
 
 pub(crate) fn diagnostics(db: &dyn DefDatabase, file: FileId) -> Vec<Diagnostic> {
+    let _p = profile::span("diagnostics");
     let mut diags = Vec::new();
 
     // Parsing.
     let parse = db.parse(file);
     diags.extend(parse.errors().iter().map(|&err| Diagnostic::from(err)));
 
+    // Later passes may be expensive. Bail out early if the input already changed,
+    // so pending typing-latency requests are not blocked behind us.
+    db.unwind_if_cancelled();
+
+    // Imports.
+    check_duplicate_imports(file, &parse.root(), &mut diags);
+
+    // Items.
+    check_duplicate_definitions(db, file, &mut diags);
+    check_duplicate_labels(file, &parse.root(), &mut diags);
+
+    // References.
+    check_wrong_targets(db, file, &mut diags);
+
+    // Style.
+    check_naming(&parse.root(), &mut diags);
+    check_local_naming(db, file, &mut diags);
+    check_deprecated_external(&parse.root(), &mut diags);
+
     diags
 }
 
+/// An import binding, with the target of its enclosing target group if any.
+struct ImportBinding {
+    name: String,
+    target: Option<String>,
+    range: TextRange,
+}
+
+/// Report imports binding the same name in the same namespace, since resolution would silently
+/// pick one of them. Module names share their namespace with values, as `x.y` could refer to
+/// either, and types and constructors have their own.
+/// Bindings in target groups of different targets never conflict with each other.
+fn check_duplicate_imports(file: FileId, root: &ast::SourceFile, diags: &mut Vec<Diagnostic>) {
+    let mut values = Vec::new();
+    let mut types = Vec::new();
+    let mut check = |seen: &mut Vec<ImportBinding>, target: Option<String>, tok: SyntaxToken| {
+        let prev = seen.iter().find(|prev| {
+            prev.name == tok.text()
+                && (prev.target.is_none() || target.is_none() || prev.target == target)
+        });
+        match prev {
+            Some(prev) => diags.push(
+                Diagnostic::new(tok.text_range(), DiagnosticKind::DuplicateImport)
+                    .with_note(FileRange::new(file, prev.range), "Previously imported here"),
+            ),
+            None => seen.push(ImportBinding {
+                name: tok.text().to_owned(),
+                target,
+                range: tok.text_range(),
+            }),
+        }
+    };
+
+    for group in root.statements() {
+        let target = group
+            .target()
+            .map(|target| target.syntax().text().to_string());
+        for stmt in group.statements() {
+            let ast::Statement::Import(import) = stmt else {
+                continue;
+            };
+            let Some(module) = import.module() else {
+                continue;
+            };
+            let binding = match module.as_name() {
+                Some(name) => name.token(),
+                None => module.module_path().last().and_then(|path| path.token()),
+            };
+            if let Some(tok) = binding {
+                check(&mut values, target.clone(), tok);
+            }
+            for uq in module.unqualified() {
+                if let Some(tok) = uq.as_name().or_else(|| uq.name()).and_then(|n| n.token()) {
+                    let seen = if tok.kind() == SyntaxKind::U_IDENT {
+                        &mut types
+                    } else {
+                        &mut values
+                    };
+                    check(seen, target.clone(), tok);
+                }
+            }
+        }
+    }
+}
+
+/// Report items defining a name which another item of the same namespace already defines.
+/// Imports are left to `check_duplicate_imports`.
+fn check_duplicate_definitions(db: &dyn DefDatabase, file: FileId, diags: &mut Vec<Diagnostic>) {
+    let scope = db.module_scope(ModuleId(file));
+    let source_map = db.item_tree_source_map(file);
+    let root = db.parse(file).syntax_node();
+
+    let mut dups = Vec::new();
+    for (_, entries) in scope.values() {
+        dups.extend(duplicates(entries, |def| value_item(db, def)));
+    }
+    for (_, entries) in scope.types() {
+        dups.extend(duplicates(entries, |def| type_item(db, def)));
+    }
+    for (item, prev) in dups {
+        let range = def_name_range(&source_map, &root, item);
+        let prev_range = def_name_range(&source_map, &root, prev);
+        if let (Some(range), Some(prev_range)) = (range, prev_range) {
+            diags.push(
+                Diagnostic::new(range, DiagnosticKind::DuplicateDefinition)
+                    .with_note(FileRange::new(file, prev_range), "Previously defined here"),
+            );
+        }
+    }
+}
+
+/// Report labels repeated in the fields of a constructor definition, or in the arguments of a
+/// call or record update.
+fn check_duplicate_labels(file: FileId, root: &ast::SourceFile, diags: &mut Vec<Diagnostic>) {
+    for node in root.syntax().descendants() {
+        let labels = if let Some(constructor) = ast::Constructor::cast(node.clone()) {
+            constructor
+                .fields()
+                .filter_map(|field| field.label()?.token())
+                .collect::<Vec<_>>()
+        } else if let Some(args) = ast::ArgList::cast(node) {
+            args.args().filter_map(|arg| arg.label()?.token()).collect()
+        } else {
+            continue;
+        };
+        for (i, tok) in labels.iter().enumerate() {
+            if let Some(prev) = labels[..i].iter().find(|prev| prev.text() == tok.text()) {
+                diags.push(
+                    Diagnostic::new(tok.text_range(), DiagnosticKind::DuplicateLabel)
+                        .with_note(FileRange::new(file, prev.text_range()), "First used here"),
+                );
+            }
+        }
+    }
+}
+
+/// An item, or the `index`th constructor of a custom type.
+type DefItem = (ModuleItem, Option<u32>);
+
+/// Each local definition overlapping an earlier one, with the first such one.
+fn duplicates<D: Copy>(
+    entries: &[ScopeEntry<D>],
+    item: impl Fn(D) -> Option<DefItem>,
+) -> Vec<(DefItem, DefItem)> {
+    let locals = entries
+        .iter()
+        .filter_map(|entry| Some((entry, item(entry.def)?)))
+        .collect::<Vec<_>>();
+    let mut ret = Vec::new();
+    for (i, (entry, item)) in locals.iter().enumerate() {
+        if let Some((_, prev)) = locals[..i].iter().find(|(prev, _)| prev.overlaps(entry)) {
+            ret.push((*item, *prev));
+        }
+    }
+    ret
+}
+
+fn value_item(db: &dyn DefDatabase, def: ValueDef) -> Option<DefItem> {
+    Some(match def {
+        ValueDef::Function(id) => (ModuleItem::Function(id.lookup(db).id), None),
+        ValueDef::Constant(id) => (ModuleItem::Constant(id.lookup(db).id), None),
+        ValueDef::Constructor(id) => (
+            ModuleItem::CustomType(id.parent.lookup(db).id),
+            Some(id.index),
+        ),
+        ValueDef::Import(_) => return None,
+    })
+}
+
+fn type_item(db: &dyn DefDatabase, def: TypeDef) -> Option<DefItem> {
+    Some(match def {
+        TypeDef::CustomType(id) => (ModuleItem::CustomType(id.lookup(db).id), None),
+        TypeDef::TypeAlias(id) => (ModuleItem::TypeAlias(id.lookup(db).id), None),
+        TypeDef::Import(_) => return None,
+    })
+}
+
+fn def_name_range(
+    source_map: &ItemTreeSourceMap,
+    root: &SyntaxNode,
+    (item, ctor): DefItem,
+) -> Option<TextRange> {
+    let stmt = source_map.node_for_item(item)?.to_node(root);
+    let name = match (ctor, stmt) {
+        (None, stmt) => stmt.name()?,
+        // Constructors without a name are not lowered, so they do not count.
+        (Some(index), ast::Statement::CustomType(ty)) => ty
+            .constructors()
+            .filter_map(|ctor| ctor.name())
+            .filter(|name| name.token().is_some())
+            .nth(index as usize)?,
+        (Some(_), _) => return None,
+    };
+    Some(name.syntax().text_range())
+}
+
+/// Report references to items which only exist for the other target, as in calling an
+/// `@target(javascript)` function when compiling for Erlang. References which resolve for
+/// neither target are left alone.
+fn check_wrong_targets(db: &dyn DefDatabase, file: FileId, diags: &mut Vec<Diagnostic>) {
+    let module = ModuleId(file);
+    let tree = db.item_tree(file);
+    for (id, _) in tree.functions.iter() {
+        // Lowering, scopes and resolution are computed per function, check in between.
+        db.unwind_if_cancelled();
+        let function = db.intern_function(ItemLoc::new(module, id));
+        let body = db.body(function);
+        let scopes = db.expr_scopes(function);
+        let resolutions = db.name_resolution(function);
+        let target = resolutions.target();
+        let source_map = db.body_source_map(function);
+        for (expr, kind) in body.exprs.iter() {
+            if resolutions.get(expr).is_some() {
+                continue;
+            }
+            let path = match kind {
+                Expr::Variable(name) => Path::value(None, name.clone()),
+                Expr::ModuleAccess {
+                    module: qualifier,
+                    name,
+                } if scopes.resolve_local(expr, qualifier.as_str()).is_none() => {
+                    Path::value(Some(qualifier.clone()), name.clone())
+                }
+                _ => continue,
+            };
+            if db.resolve_path(module, target.other(), path).is_none() {
+                continue;
+            }
+            if let Some(ptr) = source_map.node_for_expr(expr) {
+                diags.push(Diagnostic::new(
+                    ptr.text_range(),
+                    DiagnosticKind::WrongTarget(target.other()),
+                ));
+            }
+        }
+    }
+}
+
+/// Report item names which are not snake_case for values or PascalCase for types and
+/// constructors. The parser accepts names of either case where items are defined, leaving them
+/// to this lint.
+fn check_naming(root: &ast::SourceFile, diags: &mut Vec<Diagnostic>) {
+    let mut check = |name: Option<ast::Name>, kind: DiagnosticKind| {
+        let Some(tok) = name.and_then(|name| name.token()) else {
+            return;
+        };
+        let is_upper = tok.kind() == SyntaxKind::U_IDENT;
+        if is_upper == (kind == DiagnosticKind::NonSnakeCase) {
+            diags.push(Diagnostic::new(tok.text_range(), kind));
+        }
+    };
+    for stmt in root.items() {
+        match stmt {
+            ast::Statement::ModuleConstant(it) => check(it.name(), DiagnosticKind::NonSnakeCase),
+            ast::Statement::Function(it) => check(it.name(), DiagnosticKind::NonSnakeCase),
+            ast::Statement::ExternalFunction(it) => check(it.name(), DiagnosticKind::NonSnakeCase),
+            ast::Statement::CustomType(it) => {
+                check(it.name(), DiagnosticKind::NonPascalCase);
+                for ctor in it.constructors() {
+                    check(ctor.name(), DiagnosticKind::NonPascalCase);
+                }
+            }
+            ast::Statement::TypeAlias(it) => check(it.name(), DiagnosticKind::NonPascalCase),
+            ast::Statement::ExternalType(it) => check(it.name(), DiagnosticKind::NonPascalCase),
+            ast::Statement::Import(it) => check(
+                it.module().and_then(|module| module.as_name()),
+                DiagnosticKind::NonSnakeCase,
+            ),
+        }
+    }
+}
+
+/// Report local variables and labels which are not snake_case. Only parameters can be upper
+/// case, since upper case patterns are constructors.
+fn check_local_naming(db: &dyn DefDatabase, file: FileId, diags: &mut Vec<Diagnostic>) {
+    let module = ModuleId(file);
+    let root = db.parse(file).syntax_node();
+    for (id, _) in db.item_tree(file).functions.iter() {
+        db.unwind_if_cancelled();
+        let function = db.intern_function(ItemLoc::new(module, id));
+        let (body, source_map) = db.body_with_source_map(function);
+        for (pat, _) in body.patterns.iter() {
+            let Some(node) = source_map
+                .node_for_pattern(pat)
+                .map(|ptr| ptr.to_node(&root))
+            else {
+                continue;
+            };
+            let names = if let Some(param) = ast::Param::cast(node.clone()) {
+                vec![param.label(), param.name()]
+            } else if let Some(var) = ast::VariablePattern::cast(node.clone()) {
+                vec![var.name()]
+            } else if let Some(concat) = ast::ConcatPattern::cast(node) {
+                vec![concat.prefix_name()]
+            } else {
+                continue;
+            };
+            for tok in names.into_iter().flatten().filter_map(|name| name.token()) {
+                if tok.kind() == SyntaxKind::U_IDENT {
+                    diags.push(Diagnostic::new(
+                        tok.text_range(),
+                        DiagnosticKind::NonSnakeCase,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Report the `external` keyword, which was replaced by the `@external` attribute.
+fn check_deprecated_external(root: &ast::SourceFile, diags: &mut Vec<Diagnostic>) {
+    for stmt in root.items() {
+        let node = match &stmt {
+            ast::Statement::ExternalFunction(it) => it.syntax(),
+            ast::Statement::ExternalType(it) => it.syntax(),
+            _ => continue,
+        };
+        let kw = node
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|tok| tok.kind() == SyntaxKind::EXTERNAL_KW);
+        if let Some(kw) = kw {
+            diags.push(Diagnostic::new(
+                kw.text_range(),
+                DiagnosticKind::DeprecatedExternal,
+            ));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::TestDB;
+    use crate::{Change, PackageInfo, SourceDatabase, SourceRootId};
     use expect_test::{expect, Expect};
 
     fn check(fixture: &str, expect: Expect) {
@@ -30,8 +365,190 @@ mod tests {
         expect.assert_eq(&got);
     }
 
+    #[test]
+    fn duplicate_module_import() {
+        check(
+            "import a/b\nimport c/b",
+            expect![[r#"
+                20..21: DuplicateImport
+                    9..10: Previously imported here
+            "#]],
+        );
+    }
+
+    #[test]
+    fn duplicate_unqualified_import() {
+        check(
+            "import a.{x}\nimport b.{y as x}",
+            expect![[r#"
+                28..29: DuplicateImport
+                    10..11: Previously imported here
+            "#]],
+        );
+    }
+
+    #[test]
+    fn module_and_value_import() {
+        check(
+            "import a/x\nimport b.{x}",
+            expect![[r#"
+                21..22: DuplicateImport
+                    9..10: Previously imported here
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_duplicate_import() {
+        let src = "import a.{A, x}\nif erlang { import b as c }\nif javascript { import d as c }";
+        let (db, file_id) = TestDB::single_file(src).unwrap();
+        assert_eq!(super::diagnostics(&db, file_id), Vec::new());
+    }
+
+    #[test]
+    fn duplicate_definition() {
+        check(
+            "fn f() { 1 }\nconst f = 2\ntype T { A }\ntype A = Int\ntype B { A }",
+            expect![[r#"
+                19..20: DuplicateDefinition
+                    3..4: Previously defined here
+                60..61: DuplicateDefinition
+                    34..35: Previously defined here
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_duplicate_definition() {
+        let src = "if erlang { fn f() { 1 } }\nif javascript { fn f() { 2 } }\ntype A { A }";
+        let (db, file_id) = TestDB::single_file(src).unwrap();
+        assert_eq!(super::diagnostics(&db, file_id), Vec::new());
+    }
+
+    #[test]
+    fn duplicate_label() {
+        check(
+            "type A { A(x: Int, y: Int, x: Float) B(x: Int) }\nfn f(a) { g(x: 1, x: 2) A(..a, y: 1, y: 2) }",
+            expect![[r#"
+                27..28: DuplicateLabel
+                    11..12: First used here
+                67..68: DuplicateLabel
+                    61..62: First used here
+                86..87: DuplicateLabel
+                    80..81: First used here
+            "#]],
+        );
+    }
+
+    #[test]
+    fn wrong_target() {
+        check(
+            "
+@target(javascript)
+fn now() { 1 }
+if erlang { fn pid() { 2 } }
+fn f(x) { now() + x + pid() }
+@target(javascript)
+fn g() { now() + pid() + missing() }
+",
+            expect![[r#"
+                74..77: WrongTarget(JavaScript)
+                131..134: WrongTarget(Erlang)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_snake_case() {
+        check(
+            "const MaxPort = 1\nimport a as B\nimport c.{D}",
+            expect![[r#"
+                6..13: NonSnakeCase
+                30..31: NonSnakeCase
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_pascal_case() {
+        check(
+            "type my_type { Good bad_one(Int) }\ntype alias = Int",
+            expect![[r#"
+                5..12: NonPascalCase
+                20..27: NonPascalCase
+                40..45: NonPascalCase
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_snake_case_local() {
+        check(
+            "fn f(X, Label y) { let g = fn(Z) { Z }\n let \"a\" as p <> q = y\n X }",
+            expect![[r#"
+                5..6: NonSnakeCase
+                8..13: NonSnakeCase
+                30..31: NonSnakeCase
+            "#]],
+        );
+    }
+
+    #[test]
+    fn deprecated_external() {
+        check(
+            "external type Foo\npub external fn now() -> Int = \"os\" \"timestamp\"",
+            expect![[r#"
+                0..8: DeprecatedExternal
+                22..30: DeprecatedExternal
+            "#]],
+        );
+    }
+
+    #[test]
+    fn reserved_word() {
+        check(
+            "const derive = 1",
+            expect!["6..12: SyntaxError(ReservedWord)"],
+        );
+    }
+
     #[test]
     fn syntax_error() {
         check("bla = bla", expect!["7..9: SyntaxError(MultipleNoAssoc)"]);
     }
+
+    #[test]
+    fn unsupported_syntax() {
+        let (mut db, file_id) = TestDB::single_file("fn main() { echo 1 }").unwrap();
+        assert_eq!(super::diagnostics(&db, file_id), Vec::new());
+
+        let mut check_version = |gleam_version: &str, expect: Expect| {
+            let root = (*db.source_root(SourceRootId(0))).clone();
+            let mut change = Change::default();
+            change.set_roots(vec![root.with_package(PackageInfo {
+                name: "app".into(),
+                root: "/".into(),
+                target: None,
+                gleam_version: Some(gleam_version.into()),
+                dependencies: Vec::new(),
+                path_dependencies: Vec::new(),
+            })]);
+            change.apply(&mut db);
+            let got = super::diagnostics(&db, file_id)
+                .iter()
+                .map(|d| d.debug_display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect.assert_eq(&got);
+        };
+        check_version(">= 1.9.0", expect![""]);
+        check_version("~> 1.10", expect![""]);
+        check_version(
+            ">= 1.4.0 and < 2.0.0",
+            expect![
+                "12..16: SyntaxError(UnsupportedSyntax(LanguageVersion { major: 1, minor: 9 }))"
+            ],
+        );
+        check_version("< 2.0.0", expect![""]);
+    }
 }
@@ -0,0 +1,388 @@
+use crate::def::{Expr, FunctionId, ItemLoc, ModuleId, ModuleItem, Path, PathResolution, ValueDef};
+use crate::{DefDatabase, FileId, FilePos, Target};
+use syntax::ast::{self, AstNode, HasAttributes};
+use syntax::lexer::split_radix;
+use syntax::{AstPtr, SyntaxKind, SyntaxToken, TextRange, T};
+
+const TARGETS: [Target; 2] = [Target::Erlang, Target::JavaScript];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverResult {
+    pub range: TextRange,
+    /// Markdown.
+    pub markup: String,
+}
+
+pub(crate) fn hover(db: &dyn DefDatabase, fpos: FilePos) -> Option<HoverResult> {
+    let parse = db.parse(fpos.file_id);
+    let tok = parse
+        .syntax_node()
+        .token_at_offset(fpos.pos)
+        .find(|tok| matches!(tok.kind(), SyntaxKind::INTEGER | SyntaxKind::IDENT))?;
+    match tok.kind() {
+        SyntaxKind::INTEGER => hover_integer(&tok),
+        _ => hover_function(db, fpos.file_id, &tok),
+    }
+}
+
+/// Show an integer literal in the other bases, eg. for bit array segments and flags.
+fn hover_integer(tok: &SyntaxToken) -> Option<HoverResult> {
+    let value = parse_integer(tok.text())?;
+    let decimal = value.to_string();
+    let mut markup = format!("```gleam\n{}\n```\n\n", tok.text());
+    markup += &format!("- Decimal: `{decimal}`");
+    if decimal.len() > 4 {
+        markup += &format!(" (`{}`)", group_digits(&decimal, 3));
+    }
+    markup += &format!("\n- Hex: `0x{value:X}`");
+    markup += &format!("\n- Binary: `0b{}`", group_digits(&format!("{value:b}"), 4));
+    Some(HoverResult {
+        range: tok.text_range(),
+        markup,
+    })
+}
+
+/// Show which targets implement a function limited by `@target` or `@external`, at its
+/// definition or at references in function bodies.
+fn hover_function(db: &dyn DefDatabase, file: FileId, tok: &SyntaxToken) -> Option<HoverResult> {
+    let module = ModuleId(file);
+    let stmt = tok.parent_ancestors().find_map(ast::Statement::cast)?;
+    let item = db
+        .item_tree_source_map(file)
+        .item_for_node(AstPtr::new(&stmt))?;
+    let ModuleItem::Function(id) = item else {
+        return None;
+    };
+    let function = db.intern_function(ItemLoc::new(module, id));
+
+    let is_definition = function_name(&stmt).as_ref() == Some(tok);
+    // The definition each target resolves to, which may be a different one per target.
+    let defs = if is_definition {
+        TARGETS.iter().map(|&target| (target, function)).collect()
+    } else {
+        let (body, source_map) = db.body_with_source_map(function);
+        let scopes = db.expr_scopes(function);
+        let expr = tok
+            .parent_ancestors()
+            .filter_map(ast::Expr::cast)
+            .find_map(|expr| source_map.expr_for_node(AstPtr::new(&expr)))?;
+        let path = match &body[expr] {
+            Expr::Variable(name) if scopes.resolve_local(expr, name.as_str()).is_none() => {
+                Path::value(None, name.clone())
+            }
+            Expr::ModuleAccess { module, name }
+                if name.as_str() == tok.text()
+                    && scopes.resolve_local(expr, module.as_str()).is_none() =>
+            {
+                Path::value(Some(module.clone()), name.clone())
+            }
+            _ => return None,
+        };
+        TARGETS
+            .iter()
+            .filter_map(
+                |&target| match db.resolve_path(module, target, path.clone())? {
+                    PathResolution::Value(ValueDef::Function(def)) => Some((target, def)),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>()
+    };
+
+    let nodes = defs
+        .iter()
+        .map(|&(target, def)| Some((target, def, function_node(db, def)?)))
+        .collect::<Option<Vec<_>>>()?;
+    let (_, _, first_node) = nodes.first()?;
+    if !nodes
+        .iter()
+        .any(|(_, def, node)| is_limited(db, *def, node))
+    {
+        return None;
+    }
+    let available = nodes
+        .iter()
+        .filter(|(target, def, node)| implements(db, *def, node, *target))
+        .map(|(target, _, _)| *target)
+        .collect::<Vec<_>>();
+
+    let mut markup = format!("```gleam\n{}\n```\n\n", signature(first_node));
+    markup += "Available on: ";
+    if available.is_empty() {
+        markup += "none";
+    } else {
+        let names = available.iter().map(|target| target.as_str());
+        markup += &names.collect::<Vec<_>>().join(", ");
+    }
+    let current = db.file_target(file);
+    if !available.contains(&current) {
+        markup +=
+            &format!("\n\n**Warning**: Not implemented for the configured `{current}` target");
+    }
+    Some(HoverResult {
+        range: tok.text_range(),
+        markup,
+    })
+}
+
+fn function_name(stmt: &ast::Statement) -> Option<SyntaxToken> {
+    match stmt {
+        ast::Statement::Function(it) => it.name()?.token(),
+        ast::Statement::ExternalFunction(it) => it.name()?.token(),
+        _ => None,
+    }
+}
+
+fn function_node(db: &dyn DefDatabase, function: FunctionId) -> Option<ast::Statement> {
+    let loc = function.lookup(db);
+    let ptr = db
+        .item_tree_source_map(loc.module.0)
+        .node_for_item(ModuleItem::Function(loc.id))?;
+    Some(ptr.to_node(&db.parse(loc.module.0).syntax_node()))
+}
+
+/// Whether a function is limited to a target by `@target`, a target group or `@external`.
+fn is_limited(db: &dyn DefDatabase, function: FunctionId, node: &ast::Statement) -> bool {
+    let loc = function.lookup(db);
+    db.item_tree(loc.module.0)[loc.id].target.is_some() || external_targets(node).next().is_some()
+}
+
+/// Whether a function can be called when compiling for `target`. Without a body, it needs an
+/// `@external` for that target. The deprecated `external fn` is implemented for all targets.
+fn implements(
+    db: &dyn DefDatabase,
+    function: FunctionId,
+    node: &ast::Statement,
+    target: Target,
+) -> bool {
+    let loc = function.lookup(db);
+    let limited_to = db.item_tree(loc.module.0)[loc.id]
+        .target
+        .as_ref()
+        .and_then(|name| Target::from_name(name.as_str()));
+    if matches!(limited_to, Some(limited_to) if limited_to != target) {
+        return false;
+    }
+    match node {
+        ast::Statement::Function(func) if func.body().is_none() => {
+            external_targets(node).any(|it| it == Some(target))
+        }
+        _ => true,
+    }
+}
+
+/// The targets of the `@external(target, "module", "name")` attributes of an item.
+fn external_targets(node: &ast::Statement) -> impl Iterator<Item = Option<Target>> {
+    node.attributes()
+        .filter(|attr| {
+            attr.name()
+                .map_or(false, |name| name.syntax().text() == "external")
+        })
+        .map(|attr| {
+            let target = attr.arguments().next()?;
+            Target::from_name(&target.syntax().text().to_string())
+        })
+}
+
+/// The function without its attributes, documentation and body.
+fn signature(node: &ast::Statement) -> String {
+    let node = node.syntax();
+    let start = node
+        .children_with_tokens()
+        .find(|it| matches!(it.kind(), T!["pub"] | T!["fn"] | T!["external"]))
+        .map_or(node.text_range().start(), |it| it.text_range().start());
+    let end = node
+        .children()
+        .find(|it| it.kind() == SyntaxKind::BLOCK)
+        .map_or(node.text_range().end(), |it| it.text_range().start());
+    let text = node
+        .text()
+        .slice(TextRange::new(start, end) - node.text_range().start());
+    text.to_string().trim_end().to_owned()
+}
+
+fn parse_integer(text: &str) -> Option<u128> {
+    let (radix, digits) = split_radix(text);
+    u128::from_str_radix(&digits.replace('_', ""), radix).ok()
+}
+
+/// Insert `_` between every `n` digits, counting from the least significant one.
+fn group_digits(digits: &str, n: usize) -> String {
+    let mut ret = String::with_capacity(digits.len() * 2);
+    for (i, c) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i) % n == 0 {
+            ret.push('_');
+        }
+        ret.push(c);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::TestDB;
+    use crate::SourceDatabase;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let ret = super::hover(&db, f[0]).expect("No hover");
+        let src = db.file_content(f[0].file_id);
+        let got = format!("{}\n---\n{}\n", &src[ret.range], ret.markup);
+        expect.assert_eq(&got);
+    }
+
+    #[test]
+    fn hex() {
+        check(
+            "const a = 0x1$0F4",
+            expect![[r#"
+                0x1F4
+                ---
+                ```gleam
+                0x1F4
+                ```
+
+                - Decimal: `500`
+                - Hex: `0x1F4`
+                - Binary: `0b1_1111_0100`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn decimal() {
+        check(
+            "const a = $01000000",
+            expect![[r#"
+                1000000
+                ---
+                ```gleam
+                1000000
+                ```
+
+                - Decimal: `1000000` (`1_000_000`)
+                - Hex: `0xF4240`
+                - Binary: `0b1111_0100_0010_0100_0000`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn external_function() {
+        check(
+            r#"
+@external(erlang, "os", "system_time")
+@external(javascript, "./ffi.mjs", "now")
+pub fn now() -> Int
+fn f() { no$0w() }
+            "#,
+            expect![[r#"
+                now
+                ---
+                ```gleam
+                pub fn now() -> Int
+                ```
+
+                Available on: erlang, javascript
+            "#]],
+        );
+        check(
+            r#"
+/// Log to the console.
+@external(javascript, "./ffi.mjs", "log")
+pub fn lo$0g(x: String) -> Nil
+            "#,
+            expect![[r#"
+                log
+                ---
+                ```gleam
+                pub fn log(x: String) -> Nil
+                ```
+
+                Available on: javascript
+
+                **Warning**: Not implemented for the configured `erlang` target
+            "#]],
+        );
+        check(
+            r#"
+@external(erlang, "lists", "reverse")
+pub fn reverse(xs) { xs }
+fn f(xs) { reverse$0(xs) }
+            "#,
+            expect![[r#"
+                reverse
+                ---
+                ```gleam
+                pub fn reverse(xs)
+                ```
+
+                Available on: erlang, javascript
+            "#]],
+        );
+    }
+
+    #[test]
+    fn target_function() {
+        check(
+            r#"
+#- /src/main.gleam
+import gleam/io
+fn f() { io.print$0ln() }
+#- /src/gleam/io.gleam
+@target(erlang)
+pub fn println() { 1 }
+if javascript { pub fn println() { 2 } }
+            "#,
+            expect![[r#"
+                println
+                ---
+                ```gleam
+                pub fn println()
+                ```
+
+                Available on: erlang, javascript
+            "#]],
+        );
+        check(
+            r#"
+if javascript { pub fn log() { 1 } }
+fn f() { lo$0g() }
+            "#,
+            expect![[r#"
+                log
+                ---
+                ```gleam
+                pub fn log()
+                ```
+
+                Available on: javascript
+
+                **Warning**: Not implemented for the configured `erlang` target
+            "#]],
+        );
+    }
+
+    #[test]
+    fn range() {
+        let (db, f) = TestDB::from_fixture(
+            "
+const a = 1_0$000
+//        ^^^^^^^
+            ",
+        )
+        .unwrap();
+        let ret = super::hover(&db, f[0]).unwrap();
+        assert_eq!(ret.range, f.annotations()[0].0.range);
+    }
+
+    #[test]
+    fn no_hover() {
+        let (db, f) = TestDB::from_fixture("const a$0 = \"1\"").unwrap();
+        assert_eq!(super::hover(&db, f[0]), None);
+        let (db, f) = TestDB::from_fixture("fn f() { 1 }\nfn g(f) { f$0() }").unwrap();
+        assert_eq!(super::hover(&db, f[0]), None);
+    }
+}
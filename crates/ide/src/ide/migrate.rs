@@ -0,0 +1,288 @@
+use crate::{DefDatabase, FileId, FileRange, SourceChange, TextEdit};
+use std::iter::successors;
+use syntax::ast::{self, AstNode};
+use syntax::{NodeOrToken, SyntaxKind, SyntaxToken, TextRange, T};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyntaxMigration {
+    pub change: SourceChange,
+    /// The number of `if <target> { ... }` groups rewritten to `@target` attributes.
+    pub target_groups: usize,
+    /// Removed syntax which cannot be rewritten mechanically, eg. `try` and the old `assert`.
+    pub manual: Vec<FileRange>,
+}
+
+/// Rewrite removed or deprecated syntax in all workspace files to the current forms.
+pub(crate) fn migrate_syntax(db: &dyn DefDatabase) -> SyntaxMigration {
+    let mut ret = SyntaxMigration::default();
+    for &sid in db.source_root_ids().iter() {
+        let root = db.source_root(sid);
+        if root.is_library() {
+            continue;
+        }
+        for (file, _) in root.files() {
+            if root.module_name_for_file(file).is_some() {
+                db.unwind_if_cancelled();
+                migrate_file(db, file, &mut ret);
+            }
+        }
+    }
+    ret
+}
+
+fn migrate_file(db: &dyn DefDatabase, file: FileId, ret: &mut SyntaxMigration) {
+    let parse = db.parse(file);
+    let root = parse.root();
+
+    for group in root.statements() {
+        if let Some(edit) = migrate_target_group(&group) {
+            ret.change.insert(file, edit);
+            ret.target_groups += 1;
+        }
+    }
+
+    ret.manual.extend(
+        root.syntax()
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|tok| match tok.kind() {
+                T!["try"] => true,
+                // `let assert` is the current form.
+                T!["assert"] => tok
+                    .parent()
+                    .map_or(true, |it| it.kind() != SyntaxKind::LET_STATEMENT),
+                _ => false,
+            })
+            .map(|tok| FileRange::new(file, tok.text_range())),
+    );
+}
+
+/// `if erlang { const a = 1 }` becomes `@target(erlang)` on each item. Items and the
+/// comments between them are dedented to the top level.
+fn migrate_target_group(group: &ast::TargetGroup) -> Option<TextEdit> {
+    let target = group.target()?.syntax().text().to_string();
+    // Leave incomplete groups alone, they would be rewritten wrongly.
+    let close = group
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T!["}"])?
+        .into_token()?;
+    let open = group
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T!["{"])?
+        .into_token()?;
+
+    let mut lines = Vec::<String>::new();
+    // Whether the last line ends with code, so that a comment may follow on it.
+    let mut after_code = false;
+    let mut tok = open.next_token();
+    while let Some(t) = tok.filter(|t| *t != close) {
+        tok = t.next_token();
+        match t.kind() {
+            SyntaxKind::WHITESPACE => {
+                after_code &= !t.text().contains('\n');
+                // Keep blank lines between items.
+                if t.text().matches('\n').count() > 1
+                    && lines.last().map_or(false, |l| !l.is_empty())
+                {
+                    lines.push(String::new());
+                }
+            }
+            kind if kind.is_whitespace() => match lines.last_mut() {
+                Some(line) if after_code => {
+                    line.push(' ');
+                    line.push_str(t.text());
+                }
+                _ => lines.push(t.text().to_owned()),
+            },
+            _ => {
+                let Some(stmt) = t
+                    .parent_ancestors()
+                    .find(|node| node.parent().as_ref() == Some(group.syntax()))
+                else {
+                    lines.push(t.text().to_owned());
+                    after_code = true;
+                    continue;
+                };
+                let last = stmt
+                    .last_token()
+                    .into_iter()
+                    .flat_map(|last| successors(Some(last), SyntaxToken::prev_token))
+                    .find(|tok| !tok.kind().is_whitespace())
+                    .unwrap_or_else(|| t.clone());
+                let range = TextRange::new(t.text_range().start(), last.text_range().end());
+                let text = stmt
+                    .text()
+                    .slice(range - stmt.text_range().start())
+                    .to_string();
+                let indent = t
+                    .prev_token()
+                    .filter(|prev| prev.kind() == SyntaxKind::WHITESPACE)
+                    .map_or(String::new(), |prev| {
+                        let text = prev.text();
+                        text[text.rfind('\n').map_or(0, |i| i + 1)..].to_owned()
+                    });
+                let text = text
+                    .split('\n')
+                    .map(|line| line.strip_prefix(&*indent).unwrap_or(line))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                lines.push(format!("@target({target})\n{text}"));
+                after_code = true;
+                tok = last.next_token();
+            }
+        }
+    }
+
+    if lines.last().map_or(false, String::is_empty) {
+        lines.pop();
+    }
+    Some(TextEdit {
+        delete: group.syntax().text_range(),
+        insert: lines.join("\n").into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::TestDB;
+    use crate::SourceDatabase;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        let migration = super::migrate_syntax(&db);
+        let file = f.files()[0];
+        let mut src = db.file_content(file).to_string();
+        let mut edits = migration
+            .change
+            .file_edits
+            .get(&file)
+            .cloned()
+            .unwrap_or_default();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.delete.start()));
+        for edit in edits {
+            edit.apply(&mut src);
+        }
+        let got = format!(
+            "{src}\n---\ntarget groups: {}, manual: {}\n",
+            migration.target_groups,
+            migration.manual.len(),
+        );
+        expect.assert_eq(&got);
+    }
+
+    #[test]
+    fn target_group() {
+        check(
+            "
+#- /src/a.gleam
+if erlang {
+  const a = 1
+  // Comment.
+  pub const b = 2 // Trailing.
+
+  fn f() {
+    b
+  }
+}
+const c = 3
+            ",
+            expect![[r#"
+                @target(erlang)
+                const a = 1
+                // Comment.
+                @target(erlang)
+                pub const b = 2 // Trailing.
+
+                @target(erlang)
+                fn f() {
+                  b
+                }
+                const c = 3
+                ---
+                target groups: 1, manual: 0
+            "#]],
+        );
+    }
+
+    #[test]
+    fn incomplete_group() {
+        check(
+            "
+#- /src/a.gleam
+if erlang {
+  const a = 1
+            ",
+            expect![[r#"
+                if erlang {
+                  const a = 1
+                ---
+                target groups: 0, manual: 0
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_module_file() {
+        check(
+            "
+#- /gleam.toml
+if erlang { const a = 1 }
+            ",
+            expect![[r#"
+                if erlang { const a = 1 }
+                ---
+                target groups: 0, manual: 0
+            "#]],
+        );
+    }
+
+    #[test]
+    fn removed_syntax() {
+        check(
+            "
+#- /src/a.gleam
+fn f(y) {
+  assert Ok(x) = y
+  let assert Ok(z) = y
+  try w = y
+  x
+}
+            ",
+            expect![[r#"
+                fn f(y) {
+                  assert Ok(x) = y
+                  let assert Ok(z) = y
+                  try w = y
+                  x
+                }
+                ---
+                target groups: 0, manual: 2
+            "#]],
+        );
+    }
+
+    #[test]
+    fn let_assert() {
+        check(
+            "
+#- /src/a.gleam
+fn f(y) {
+  let assert Ok(x) = y
+  x
+}
+            ",
+            expect![[r#"
+                fn f(y) {
+                  let assert Ok(x) = y
+                  x
+                }
+                ---
+                target groups: 0, manual: 0
+            "#]],
+        );
+    }
+}
@@ -1,8 +1,14 @@
+mod assists;
+mod code_lens;
 mod diagnostics;
+mod hover;
+mod migrate;
+mod module_docs;
+mod workspace_symbol;
 
 use crate::base::SourceDatabaseStorage;
 use crate::def::DefDatabaseStorage;
-use crate::{Change, Diagnostic, FileId, FileSet, SourceRoot, VfsPath};
+use crate::{Change, Diagnostic, FileId, FilePos, FileRange, FileSet, SourceRoot, VfsPath};
 use salsa::{Database, Durability, ParallelDatabase};
 use std::fmt;
 use syntax::TextRange;
@@ -16,7 +22,12 @@ pub struct NavigationTarget {
     pub focus_range: TextRange,
 }
 
+pub use assists::{Assist, AssistConfig, AssistKind};
+pub use code_lens::{CodeLens, CodeLensKind};
+pub use hover::HoverResult;
+pub use migrate::SyntaxMigration;
 pub use salsa::Cancelled;
+pub use workspace_symbol::{SymbolKind, WorkspaceSymbol};
 
 pub type Cancellable<T> = Result<T, Cancelled>;
 
@@ -53,6 +64,8 @@ impl Default for RootDatabase {
             .in_db_mut(&mut db)
             .set_lru_capacity(DEFAULT_LRU_CAP);
         db.set_module_graph_with_durability(Default::default(), Durability::MEDIUM);
+        db.set_source_root_ids_with_durability(Default::default(), Durability::HIGH);
+        db.set_active_target_with_durability(None, Durability::MEDIUM);
         db
     }
 }
@@ -113,4 +126,36 @@ impl Analysis {
     pub fn diagnostics(&self, file: FileId) -> Cancellable<Vec<Diagnostic>> {
         self.with_db(|db| diagnostics::diagnostics(db, file))
     }
+
+    pub fn assists(&self, frange: FileRange, config: AssistConfig) -> Cancellable<Vec<Assist>> {
+        self.with_db(|db| assists::assists(db, frange, config))
+    }
+
+    pub fn hover(&self, fpos: FilePos) -> Cancellable<Option<HoverResult>> {
+        self.with_db(|db| hover::hover(db, fpos))
+    }
+
+    pub fn code_lenses(&self, file: FileId) -> Cancellable<Vec<CodeLens>> {
+        self.with_db(|db| code_lens::code_lenses(db, file))
+    }
+
+    pub fn workspace_symbols(&self, query: &str) -> Cancellable<Vec<WorkspaceSymbol>> {
+        self.with_db(|db| workspace_symbol::workspace_symbols(db, query))
+    }
+
+    //// Custom extensions ////
+
+    pub fn migrate_syntax(&self) -> Cancellable<SyntaxMigration> {
+        self.with_db(|db| migrate::migrate_syntax(db))
+    }
+
+    /// The `////` documentation of a module.
+    pub fn module_doc(&self, file: FileId) -> Cancellable<Option<String>> {
+        self.with_db(|db| module_docs::module_doc(db, file))
+    }
+
+    /// The full documentation of a module and its public items, rendered as markdown.
+    pub fn module_docs(&self, file: FileId) -> Cancellable<String> {
+        self.with_db(|db| module_docs::module_docs(db, file))
+    }
 }
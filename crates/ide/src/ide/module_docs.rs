@@ -0,0 +1,200 @@
+use crate::{DefDatabase, FileId};
+use syntax::ast::{self, AstNode, HasDocComments, HasVisibility};
+use syntax::comments::code_range;
+use syntax::{SyntaxNode, TextRange, TextSize, T};
+
+/// The `////` module documentation of a file.
+pub(crate) fn module_doc(db: &dyn DefDatabase, file: FileId) -> Option<String> {
+    db.parse(file).root().module_doc()
+}
+
+/// Render the documentation of a module and all its public items as markdown.
+pub(crate) fn module_docs(db: &dyn DefDatabase, file: FileId) -> String {
+    let root = db.source_root(db.file_source_root(file));
+    let parse = db.parse(file);
+
+    let mut out = String::new();
+    if let Some(name) = root.module_name_for_file(file) {
+        out += &format!("# {name}\n\n");
+    }
+    if let Some(doc) = module_doc(db, file) {
+        out += &doc;
+        out += "\n\n";
+    }
+
+    let root = parse.root();
+    let types = root
+        .type_definitions()
+        .filter(|it| it.is_public())
+        .map(|it| {
+            // The constructors of opaque types are private.
+            let end = match &it {
+                ast::TypeDefinition::CustomType(ty) if ty.is_opaque() => ty
+                    .syntax()
+                    .children_with_tokens()
+                    .find(|it| it.kind() == T!["{"])
+                    .map(|it| it.text_range().start()),
+                _ => None,
+            };
+            (code_text(it.syntax(), end), it.doc_comment_text())
+        })
+        .collect();
+    render_section(&mut out, "Types", types);
+
+    let constants = root
+        .constants()
+        .filter(|it| it.is_public())
+        .map(|it| (code_text(it.syntax(), None), it.doc_comment_text()))
+        .collect();
+    render_section(&mut out, "Constants", constants);
+
+    // Only the signatures of functions.
+    let functions = root
+        .items()
+        .filter_map(|it| match it {
+            ast::Statement::Function(f) if f.is_public() => {
+                let end = f.body().map(|body| body.syntax().text_range().start());
+                Some((code_text(f.syntax(), end), f.doc_comment_text()))
+            }
+            ast::Statement::ExternalFunction(f) if f.is_public() => {
+                Some((code_text(f.syntax(), None), f.doc_comment_text()))
+            }
+            _ => None,
+        })
+        .collect();
+    render_section(&mut out, "Functions", functions);
+
+    out.truncate(out.trim_end().len());
+    out
+}
+
+fn render_section(out: &mut String, title: &str, items: Vec<(String, Option<String>)>) {
+    if items.is_empty() {
+        return;
+    }
+    *out += &format!("## {title}\n\n");
+    for (code, doc) in items {
+        *out += &format!("```gleam\n{code}\n```\n\n");
+        if let Some(doc) = doc {
+            *out += &doc;
+            *out += "\n\n";
+        }
+    }
+}
+
+/// The code of `node` up to `end`, without the comments and whitespace around it.
+fn code_text(node: &SyntaxNode, end: Option<TextSize>) -> String {
+    let range = code_range(node);
+    let range = TextRange::new(range.start(), end.unwrap_or(range.end()));
+    let text = node.text().slice(range - node.text_range().start());
+    text.to_string().trim_end().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::TestDB;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, f) = TestDB::from_fixture(fixture).unwrap();
+        expect.assert_eq(&super::module_docs(&db, f.files()[0]));
+    }
+
+    #[test]
+    fn module_doc() {
+        let (db, file) = TestDB::single_file("//// Hello\n////world\nconst a = 1").unwrap();
+        assert_eq!(super::module_doc(&db, file).unwrap(), "Hello\nworld");
+        let (db, file) = TestDB::single_file("/// Not a module doc\nconst a = 1").unwrap();
+        assert_eq!(super::module_doc(&db, file), None);
+    }
+
+    #[test]
+    fn render() {
+        check(
+            "
+#- /src/app/config.gleam
+//// Configuration.
+//// Second line.
+
+import gleam/io
+
+/// The answer.
+/// To everything.
+pub const answer = 42
+
+/// Hidden.
+const private = 1
+
+pub const port: Int = 8080
+
+/// A colour.
+pub type Colour {
+  Red
+  Green
+}
+
+pub opaque type Secret {
+  Secret(String)
+}
+
+type Internal =
+  Int
+
+/// Add one.
+pub fn inc(x: Int) -> Int {
+  x + 1
+}
+
+fn helper() { 1 }
+
+pub external fn now() -> Int = \"os\" \"timestamp\"
+            ",
+            expect![[r##"
+                # app/config
+
+                Configuration.
+                Second line.
+
+                ## Types
+
+                ```gleam
+                pub type Colour {
+                  Red
+                  Green
+                }
+                ```
+
+                A colour.
+
+                ```gleam
+                pub opaque type Secret
+                ```
+
+                ## Constants
+
+                ```gleam
+                pub const answer = 42
+                ```
+
+                The answer.
+                To everything.
+
+                ```gleam
+                pub const port: Int = 8080
+                ```
+
+                ## Functions
+
+                ```gleam
+                pub fn inc(x: Int) -> Int
+                ```
+
+                Add one.
+
+                ```gleam
+                pub external fn now() -> Int = "os" "timestamp"
+                ```"##]],
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use super::NavigationTarget;
+use crate::fuzzy::fuzzy_match;
+use crate::{DefDatabase, FileId};
+use syntax::ast::{self, AstNode, HasName};
+use syntax::TextRange;
+
+const MAX_RESULTS: usize = 128;
+/// Added to the score of matches on the name alone, so they rank above those needing the
+/// module too.
+const NAME_BONUS: i32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Module,
+    Function,
+    Constant,
+    Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The module defining this symbol. `None` for modules themselves.
+    pub container_name: Option<String>,
+    pub nav: NavigationTarget,
+}
+
+/// A parsed workspace symbol query.
+///
+/// Words of the form `#kind` or `kind:rest` restrict results to the given kinds, where kind is
+/// one of `fn`, `type`, `const` or `mod`. `#deps` also searches dependency packages, which are
+/// skipped by default. Everything else is fuzzy matched against symbol names, qualified with
+/// their module as in `gleam/list.map`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SymbolQuery {
+    text: String,
+    kinds: Vec<SymbolKind>,
+    include_libraries: bool,
+}
+
+impl SymbolQuery {
+    fn parse(query: &str) -> Self {
+        let mut this = Self::default();
+        for word in query.split_whitespace() {
+            let rest = if let Some(flag) = word.strip_prefix('#') {
+                if flag == "deps" {
+                    this.include_libraries = true;
+                    continue;
+                }
+                match kind_for_flag(flag) {
+                    Some(kind) => {
+                        this.kinds.push(kind);
+                        continue;
+                    }
+                    None => word,
+                }
+            } else if let Some((flag, rest)) = word.split_once(':') {
+                match kind_for_flag(flag) {
+                    Some(kind) => {
+                        this.kinds.push(kind);
+                        rest
+                    }
+                    None => word,
+                }
+            } else {
+                word
+            };
+            this.text.push_str(rest);
+        }
+        this
+    }
+
+    fn accepts_kind(&self, kind: SymbolKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+fn kind_for_flag(flag: &str) -> Option<SymbolKind> {
+    Some(match flag {
+        "fn" => SymbolKind::Function,
+        "type" => SymbolKind::Type,
+        "const" => SymbolKind::Constant,
+        "mod" => SymbolKind::Module,
+        _ => return None,
+    })
+}
+
+pub(crate) fn workspace_symbols(db: &dyn DefDatabase, query: &str) -> Vec<WorkspaceSymbol> {
+    let query = SymbolQuery::parse(query);
+    let mut scored = Vec::new();
+    for &sid in db.source_root_ids().iter() {
+        let root = db.source_root(sid);
+        if root.is_library() && !query.include_libraries {
+            continue;
+        }
+        for (file, _) in root.files() {
+            db.unwind_if_cancelled();
+            let module_name = root.module_name_for_file(file);
+            for sym in file_symbols(db, file, module_name) {
+                if !query.accepts_kind(sym.kind) {
+                    continue;
+                }
+                if let Some(score) = symbol_score(&query.text, &sym) {
+                    scored.push((score, sym));
+                }
+            }
+        }
+    }
+
+    scored.sort_by(|(lscore, lhs), (rscore, rhs)| {
+        rscore.cmp(lscore).then_with(|| lhs.name.cmp(&rhs.name))
+    });
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, sym)| sym)
+        .collect()
+}
+
+fn symbol_score(query: &str, sym: &WorkspaceSymbol) -> Option<i32> {
+    let name = fuzzy_match(query, &sym.name).map(|score| score + NAME_BONUS);
+    let qualified = sym
+        .container_name
+        .as_ref()
+        .and_then(|module| fuzzy_match(query, &format!("{module}.{}", sym.name)));
+    name.max(qualified)
+}
+
+fn file_symbols(
+    db: &dyn DefDatabase,
+    file_id: FileId,
+    module_name: Option<String>,
+) -> Vec<WorkspaceSymbol> {
+    let parse = db.parse(file_id);
+    let root = parse.root();
+    let mut ret = Vec::new();
+
+    if let Some(name) = &module_name {
+        let full_range = root.syntax().text_range();
+        ret.push(WorkspaceSymbol {
+            name: name.clone(),
+            kind: SymbolKind::Module,
+            container_name: None,
+            nav: NavigationTarget {
+                file_id,
+                full_range,
+                focus_range: TextRange::empty(full_range.start()),
+            },
+        });
+    }
+
+    for stmt in root.items() {
+        let kind = match &stmt {
+            ast::Statement::ModuleConstant(_) => SymbolKind::Constant,
+            ast::Statement::Function(_) | ast::Statement::ExternalFunction(_) => {
+                SymbolKind::Function
+            }
+            ast::Statement::CustomType(_)
+            | ast::Statement::TypeAlias(_)
+            | ast::Statement::ExternalType(_) => SymbolKind::Type,
+            ast::Statement::Import(_) => continue,
+        };
+        let Some(tok) = stmt.name().and_then(|name| name.token()) else {
+            continue;
+        };
+        ret.push(WorkspaceSymbol {
+            name: tok.text().to_owned(),
+            kind,
+            container_name: module_name.clone(),
+            nav: NavigationTarget {
+                file_id,
+                full_range: stmt.syntax().text_range(),
+                focus_range: tok.text_range(),
+            },
+        });
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SymbolKind, SymbolQuery};
+    use crate::tests::TestDB;
+    use expect_test::{expect, Expect};
+
+    #[track_caller]
+    fn check(fixture: &str, query: &str, expect: Expect) {
+        let (db, _) = TestDB::from_fixture(fixture).unwrap();
+        let got = super::workspace_symbols(&db, query)
+            .into_iter()
+            .map(|sym| {
+                format!(
+                    "{:?} {} in {}\n",
+                    sym.kind,
+                    sym.name,
+                    sym.container_name.as_deref().unwrap_or("-"),
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&got);
+    }
+
+    #[test]
+    fn parse_query() {
+        assert_eq!(
+            SymbolQuery::parse("#const fn:ma p #deps"),
+            SymbolQuery {
+                text: "map".into(),
+                kinds: vec![SymbolKind::Constant, SymbolKind::Function],
+                include_libraries: true,
+            }
+        );
+        assert_eq!(
+            SymbolQuery::parse("a:b #c"),
+            SymbolQuery {
+                text: "a:b#c".into(),
+                kinds: Vec::new(),
+                include_libraries: false,
+            }
+        );
+    }
+
+    #[test]
+    fn fuzzy() {
+        check(
+            "
+#- /src/app/config.gleam
+const max_port_number = 1
+const other = 2
+#- /src/port.gleam
+const p = 3
+fn make_proper_name() {}
+            ",
+            "mpn",
+            expect![[r#"
+                Constant max_port_number in app/config
+                Function make_proper_name in port
+            "#]],
+        );
+    }
+
+    #[test]
+    fn qualified() {
+        check(
+            "
+#- /src/gleam/list.gleam
+pub fn map() {}
+pub fn filter() {}
+#- /src/app.gleam
+fn lazy_map() {}
+            ",
+            "lmap",
+            expect![[r#"
+                Function lazy_map in app
+                Function map in gleam/list
+            "#]],
+        );
+    }
+
+    #[test]
+    fn filter_kind() {
+        check(
+            "
+#- /src/port.gleam
+const port = 1
+type Port { Port }
+            ",
+            "#const port",
+            expect![[r#"
+                Constant port in port
+            "#]],
+        );
+        check(
+            "
+#- /src/port.gleam
+const port = 1
+            ",
+            "mod:port",
+            expect![[r#"
+                Module port in -
+            "#]],
+        );
+        check(
+            "
+#- /src/port.gleam
+const port = 1
+type Port { Port }
+            ",
+            "#type",
+            expect![[r#"
+                Type Port in port
+            "#]],
+        );
+    }
+}
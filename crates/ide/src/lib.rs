@@ -1,17 +1,25 @@
 mod base;
 mod def;
 mod diagnostic;
+pub mod fuzzy;
 mod ide;
+pub mod profile;
+mod ty;
 
 #[cfg(test)]
 mod tests;
 
 pub const DEFAULT_IMPORT_FILE: &str = "gleam.toml";
 
-pub use self::ide::{Analysis, AnalysisHost, Cancelled};
+pub use self::ide::{
+    Analysis, AnalysisHost, Assist, AssistConfig, AssistKind, Cancelled, CodeLens, CodeLensKind,
+    HoverResult, NavigationTarget, SymbolKind, SyntaxMigration, WorkspaceSymbol,
+};
 pub use base::{
-    Change, FileId, FilePos, FileRange, FileSet, InFile, ModuleGraph, ModuleInfo, SourceDatabase,
-    SourceRoot, SourceRootId, VfsPath,
+    Change, FileId, FilePos, FileRange, FileSet, InFile, LineCol, LineColUtf16, LineIndex,
+    ModuleGraph, ModuleInfo, PackageInfo, SourceChange, SourceDatabase, SourceRoot, SourceRootId,
+    Target, TextEdit, VfsPath,
 };
 pub use def::DefDatabase;
 pub use diagnostic::{Diagnostic, DiagnosticKind, Severity};
+pub use ty::{Ty, TyDisplay};
@@ -0,0 +1,188 @@
+//! Lightweight hierarchical profiling.
+//!
+//! Disabled by default. Set `GLEAMALYZER_PROFILE` to a threshold in milliseconds to enable it.
+//! Every finished top-level span is logged if it took at least the threshold. Only request
+//! spans are kept as the last profile, so that background work on other threads, like
+//! publishing diagnostics, does not replace it.
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+use std::{env, fmt};
+
+pub const PROFILE_ENV: &str = "GLEAMALYZER_PROFILE";
+
+/// A finished span with its nested spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub label: &'static str,
+    pub duration: Duration,
+    pub children: Vec<Frame>,
+}
+
+impl Frame {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{}ms {}",
+            "",
+            self.duration.as_millis(),
+            self.label,
+            indent = depth * 2,
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<(&'static str, Instant, Vec<Frame>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+static LAST_PROFILE: Mutex<Option<Frame>> = Mutex::new(None);
+
+/// `u64::MAX` means disabled.
+static THRESHOLD_MS: AtomicU64 = AtomicU64::new(u64::MAX);
+
+fn threshold() -> Option<Duration> {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        if let Some(ms) = env::var(PROFILE_ENV).ok().and_then(|v| v.parse().ok()) {
+            THRESHOLD_MS.store(ms, Ordering::Relaxed);
+        }
+    });
+    let ms = THRESHOLD_MS.load(Ordering::Relaxed);
+    (ms != u64::MAX).then(|| Duration::from_millis(ms))
+}
+
+/// Start a profiling span, which ends when the returned guard is dropped.
+/// Spans started while another one is alive on the same thread are recorded as its children.
+pub fn span(label: &'static str) -> Span {
+    if threshold().is_none() {
+        return Span {
+            active: false,
+            request: false,
+        };
+    }
+    STACK.with(|stack| stack.borrow_mut().push((label, Instant::now(), Vec::new())));
+    Span {
+        active: true,
+        request: false,
+    }
+}
+
+/// Start the span of a request handler, which is kept as the last profile when it ends.
+pub fn request_span(label: &'static str) -> Span {
+    let mut span = span(label);
+    span.request = span.active;
+    span
+}
+
+/// The breakdown of the last finished request, if profiling is enabled.
+pub fn last_profile() -> Option<Frame> {
+    LAST_PROFILE.lock().unwrap().clone()
+}
+
+#[must_use = "The span ends immediately if not bound"]
+#[derive(Debug)]
+pub struct Span {
+    active: bool,
+    request: bool,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let root = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let (label, start, children) = stack.pop().expect("Unbalanced profiling spans");
+            let frame = Frame {
+                label,
+                duration: start.elapsed(),
+                children,
+            };
+            match stack.last_mut() {
+                Some((.., siblings)) => {
+                    siblings.push(frame);
+                    None
+                }
+                None => Some(frame),
+            }
+        });
+
+        if let Some(frame) = root {
+            if threshold().map_or(false, |t| frame.duration >= t) {
+                tracing::info!("Profile:\n{frame}");
+            }
+            if self.request {
+                *LAST_PROFILE.lock().unwrap() = Some(frame);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{last_profile, request_span, span, Frame, THRESHOLD_MS};
+    use crate::def::{ModuleId, Path};
+    use crate::tests::TestDB;
+    use crate::{DefDatabase, Target};
+    use expect_test::expect;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    #[test]
+    fn display() {
+        let frame = Frame {
+            label: "diagnostics",
+            duration: Duration::from_millis(12),
+            children: vec![Frame {
+                label: "parse",
+                duration: Duration::from_millis(3),
+                children: Vec::new(),
+            }],
+        };
+        expect![[r#"
+                12ms diagnostics
+                  3ms parse
+        "#]]
+        .assert_eq(&frame.to_string());
+    }
+
+    #[test]
+    fn only_requests_are_kept() {
+        // Enabled, but too high to log anything.
+        THRESHOLD_MS.store(u64::MAX - 1, Ordering::Relaxed);
+        {
+            let _p = request_span("hover");
+            let _p = span("parse");
+        }
+        drop(span("publish_diagnostics"));
+        let frame = last_profile().unwrap();
+        assert_eq!(frame.label, "hover");
+        assert_eq!(frame.children[0].label, "parse");
+
+        // Name resolution, with the queries it runs nested in it.
+        let (db, file) = TestDB::single_file("fn f() { g() }\nfn g() { 1 }").unwrap();
+        {
+            let _p = request_span("goto_definition");
+            let path = Path::value(None, "g".into());
+            db.resolve_path(ModuleId(file), Target::Erlang, path);
+        }
+        let frame = last_profile().unwrap();
+        let resolve = &frame.children[0];
+        assert_eq!(resolve.label, "resolve_path");
+        assert_eq!(resolve.children[0].label, "module_scope");
+    }
+}
@@ -9,7 +9,7 @@ use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::{mem, ops};
 use syntax::ast::AstNode;
-use syntax::{GleamLanguage, SyntaxNode, TextSize};
+use syntax::{GleamLanguage, SyntaxNode, TextRange, TextSize};
 
 use crate::DEFAULT_IMPORT_FILE;
 
@@ -47,6 +47,7 @@ impl TestDB {
             nodes: HashMap::from_iter(f.module_info.clone().map(|info| (SourceRootId(0), info))),
         };
         change.set_module_graph(module_graph);
+        change.set_active_target(None);
         change.apply(&mut db);
         Ok((db, f))
     }
@@ -70,6 +71,7 @@ pub struct Fixture {
     files: IndexMap<VfsPath, String>,
     file_ids: Vec<FileId>,
     markers: Vec<FilePos>,
+    annotations: Vec<(FileRange, String)>,
     module_info: Option<ModuleInfo>,
 }
 
@@ -101,6 +103,8 @@ impl Fixture {
         let mut cur_path = None;
         let mut cur_text = String::new();
         let mut cur_file = FileId(0);
+        let mut cur_line_start = 0;
+        let mut cur_line_markers = Vec::new();
         let mut markers = [None; 10];
         for line in fixture.lines().skip_while(|line| line.is_empty()) {
             if let Some(header) = line.strip_prefix("#- ") {
@@ -138,12 +142,29 @@ impl Fixture {
                     cur_path = Some(VfsPath::new(format!("/{DEFAULT_IMPORT_FILE}")));
                 }
 
-                let mut iter = line.chars().peekable();
-                while let Some(ch) = iter.next() {
+                if let Some((col, len, label)) = parse_annotation(line) {
+                    // Columns are on the line as written, markers included.
+                    let stripped = |col: usize| {
+                        let markers = cur_line_markers.iter().filter(|&&m| m < col).count();
+                        cur_line_start + col - 2 * markers
+                    };
+                    let start = TextSize::try_from(stripped(col)).unwrap();
+                    let end = TextSize::try_from(stripped(col + len)).unwrap();
+                    let range = TextRange::new(start, end);
+                    this.annotations
+                        .push((FileRange::new(cur_file, range), label.into()));
+                    continue;
+                }
+                cur_line_start = cur_text.len();
+                cur_line_markers.clear();
+
+                let mut iter = line.char_indices().peekable();
+                while let Some((col, ch)) = iter.next() {
                     if ch == MARKER_INDICATOR
-                        && matches!(iter.peek(), Some(c) if c.is_ascii_digit())
+                        && matches!(iter.peek(), Some((_, c)) if c.is_ascii_digit())
                     {
-                        let n = iter.next().unwrap().to_digit(10).unwrap() as usize;
+                        let n = iter.next().unwrap().1.to_digit(10).unwrap() as usize;
+                        cur_line_markers.push(col);
                         let pos =
                             FilePos::new(cur_file, TextSize::try_from(cur_text.len()).unwrap());
                         ensure!(
@@ -194,6 +215,11 @@ impl Fixture {
         &self.markers
     }
 
+    /// Ranges marked by `^^^` annotation lines, with their labels.
+    pub fn annotations(&self) -> &[(FileRange, String)] {
+        &self.annotations
+    }
+
     #[track_caller]
     pub fn unwrap_single_range_marker(&self) -> FileRange {
         match *self.markers() {
@@ -203,3 +229,53 @@ impl Fixture {
         }
     }
 }
+
+/// An annotation line `//   ^^^ label` is not part of the file. It marks the range above the
+/// carets on the previous line, by byte columns.
+fn parse_annotation(line: &str) -> Option<(usize, usize, &str)> {
+    let rest = line
+        .trim_start()
+        .strip_prefix("//")?
+        .trim_start_matches(' ');
+    let carets = rest.len() - rest.trim_start_matches('^').len();
+    if carets == 0 {
+        return None;
+    }
+    let col = line.len() - rest.len();
+    Some((col, carets, rest[carets..].trim()))
+}
+
+#[test]
+fn fixture_annotations() {
+    let f = Fixture::new(
+        "
+#- /a.gleam
+const abc = 1
+//    ^^^ name
+//          ^
+#- /b.gleam
+const b = 2
+      // ^ indented
+const c$0 = 1_0$100
+//    ^ name
+//          ^^^^^^^ number
+",
+    )
+    .unwrap();
+    let got = f
+        .annotations()
+        .iter()
+        .map(|(frange, label)| (frange.file_id, frange.range, &**label))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        got,
+        [
+            (FileId(0), TextRange::at(6.into(), 3.into()), "name"),
+            (FileId(0), TextRange::at(12.into(), 1.into()), ""),
+            (FileId(1), TextRange::at(9.into(), 1.into()), "indented"),
+            (FileId(1), TextRange::at(18.into(), 1.into()), "name"),
+            (FileId(1), TextRange::at(22.into(), 5.into()), "number"),
+        ]
+    );
+    assert_eq!(f.files.get_index(0).unwrap().1, "const abc = 1");
+}
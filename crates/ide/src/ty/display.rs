@@ -0,0 +1,214 @@
+//! Printing of types the way the Gleam compiler prints them, as in
+//! `fn(List(a)) -> Result(a, Nil)`.
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::{Ty, PRELUDE_MODULE};
+use crate::def::{DefDatabase, ModuleId, ModuleName, Name, TypeDef};
+
+/// Types are qualified as they are referred to in `module`: unqualified for types of the
+/// prelude, of `module` itself or imported unqualified, and by the module alias otherwise.
+///
+/// Type variables are named `a`, `b`, ... `z`, `aa`, `ab`, ... in order of appearance. The
+/// names are kept across all types displayed by the same `TyDisplay`, so that the parameters
+/// and the return type of a signature agree.
+pub struct TyDisplay<'a> {
+    db: &'a dyn DefDatabase,
+    module: ModuleId,
+    var_names: RefCell<HashMap<u32, String>>,
+}
+
+impl<'a> TyDisplay<'a> {
+    pub fn new(db: &'a dyn DefDatabase, module: ModuleId) -> Self {
+        Self {
+            db,
+            module,
+            var_names: RefCell::default(),
+        }
+    }
+
+    pub fn display(&self, ty: &Ty) -> String {
+        let mut out = String::new();
+        self.write_ty(&mut out, ty);
+        out
+    }
+
+    fn write_ty(&self, out: &mut String, ty: &Ty) {
+        match ty {
+            Ty::Unknown => out.push('_'),
+            Ty::Named { module, name, args } => {
+                out.push_str(&self.qualified_name(module, name));
+                if !args.is_empty() {
+                    self.write_list(out, "(", args, ")");
+                }
+            }
+            Ty::Fn { params, ret } => {
+                self.write_list(out, "fn(", params, ") -> ");
+                self.write_ty(out, ret);
+            }
+            Ty::Tuple(elems) => self.write_list(out, "#(", elems, ")"),
+            Ty::Var(var) => {
+                let mut names = self.var_names.borrow_mut();
+                let next = names.len();
+                out.push_str(names.entry(*var).or_insert_with(|| var_name(next)));
+            }
+        }
+    }
+
+    fn write_list(&self, out: &mut String, open: &str, tys: &[Ty], close: &str) {
+        out.push_str(open);
+        for (i, ty) in tys.iter().enumerate() {
+            if i != 0 {
+                out.push_str(", ");
+            }
+            self.write_ty(out, ty);
+        }
+        out.push_str(close);
+    }
+
+    fn qualified_name(&self, module: &ModuleName, name: &Name) -> String {
+        let db = self.db;
+        let current = db
+            .source_root(db.file_source_root(self.module.0))
+            .module_name_for_file(self.module.0);
+        if module.as_str() == PRELUDE_MODULE || current.as_deref() == Some(module.as_str()) {
+            return name.to_string();
+        }
+
+        let tree = db.item_tree(self.module.0);
+        let scope = db.module_scope(self.module);
+        for (local_name, entries) in scope.types() {
+            let imported = entries.iter().any(|entry| match entry.def {
+                TypeDef::Import(imported) => {
+                    let import = &tree[imported.import];
+                    import.module == *module
+                        && import.unqualified[imported.index as usize].name == *name
+                }
+                _ => false,
+            });
+            if imported {
+                return local_name.to_string();
+            }
+        }
+        let alias = scope
+            .modules()
+            .find(|(_, entries)| {
+                entries
+                    .iter()
+                    .any(|entry| tree[entry.def].module == *module)
+            })
+            .map_or(module.last_segment(), |(alias, _)| alias.as_str());
+        format!("{alias}.{name}")
+    }
+}
+
+/// The `n`th type variable name, counting from 0, as the compiler names them.
+fn var_name(mut n: usize) -> String {
+    let mut chars = Vec::new();
+    loop {
+        chars.push(char::from(b'a' + (n % 26) as u8));
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+        n -= 1;
+    }
+    chars.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{var_name, TyDisplay};
+    use crate::def::{ModuleId, ModuleName, Name};
+    use crate::tests::TestDB;
+    use crate::ty::Ty;
+    use expect_test::{expect, Expect};
+
+    const FIXTURE: &str = "
+#- /src/gleam/option.gleam
+pub type Option(a) { Some(a) None }
+#- /src/gleam/dict.gleam
+pub type Dict(k, v) { Dict }
+#- /src/other/json.gleam
+pub type Json { Json }
+#- /src/main.gleam
+import gleam/option.{Option as Maybe}
+import gleam/dict as d
+pub type Local { Local }
+";
+
+    fn named(module: &str, name: &str, args: Vec<Ty>) -> Ty {
+        Ty::Named {
+            module: ModuleName::new(module),
+            name: Name::new(name),
+            args,
+        }
+    }
+
+    fn func(params: Vec<Ty>, ret: Ty) -> Ty {
+        Ty::Fn {
+            params,
+            ret: Box::new(ret),
+        }
+    }
+
+    #[track_caller]
+    fn check(tys: &[Ty], expect: Expect) {
+        let (db, f) = TestDB::from_fixture(FIXTURE).unwrap();
+        let display = TyDisplay::new(&db, ModuleId(f["/src/main.gleam"]));
+        let got = tys
+            .iter()
+            .map(|ty| display.display(ty) + "\n")
+            .collect::<String>();
+        expect.assert_eq(&got);
+    }
+
+    #[test]
+    fn prelude() {
+        let int = Ty::prelude("Int", Vec::new());
+        let string = Ty::prelude("String", Vec::new());
+        check(
+            &[
+                Ty::prelude("List", vec![Ty::Tuple(vec![string, int.clone()])]),
+                func(
+                    vec![Ty::Var(7)],
+                    Ty::prelude("Result", vec![Ty::Var(7), Ty::Var(3)]),
+                ),
+                func(Vec::new(), Ty::Tuple(Vec::new())),
+                Ty::prelude("List", vec![Ty::Unknown]),
+                func(vec![func(vec![int], Ty::Var(3))], Ty::Var(9)),
+            ],
+            expect![[r#"
+                List(#(String, Int))
+                fn(a) -> Result(a, b)
+                fn() -> #()
+                List(_)
+                fn(fn(Int) -> b) -> c
+            "#]],
+        );
+    }
+
+    #[test]
+    fn qualification() {
+        check(
+            &[
+                named("main", "Local", Vec::new()),
+                named("gleam/option", "Option", vec![Ty::Var(0)]),
+                named("gleam/dict", "Dict", vec![Ty::Var(0), Ty::Var(1)]),
+                named("other/json", "Json", Vec::new()),
+            ],
+            expect![[r#"
+                Local
+                Maybe(a)
+                d.Dict(a, b)
+                json.Json
+            "#]],
+        );
+    }
+
+    #[test]
+    fn var_names() {
+        let names = [0, 1, 25, 26, 27, 701, 702].map(var_name);
+        assert_eq!(names, ["a", "b", "z", "aa", "ab", "zz", "aaa"]);
+    }
+}
@@ -0,0 +1,39 @@
+//! Types as the type checker sees them, independent of how they were written.
+mod display;
+
+use crate::def::{ModuleName, Name};
+
+pub use self::display::TyDisplay;
+
+/// The module defining the built-in types, like `Int` and `List`.
+pub const PRELUDE_MODULE: &str = "gleam";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ty {
+    /// Not inferred, eg. because of an error. Printed as `_`.
+    Unknown,
+    /// A custom or built-in type applied to its arguments, as in `List(Int)`.
+    Named {
+        module: ModuleName,
+        name: Name,
+        args: Vec<Ty>,
+    },
+    Fn {
+        params: Vec<Ty>,
+        ret: Box<Ty>,
+    },
+    Tuple(Vec<Ty>),
+    /// A type variable, unique within the types displayed together.
+    Var(u32),
+}
+
+impl Ty {
+    /// A type of the prelude, as in `Int` or `List(a)`.
+    pub fn prelude(name: &str, args: Vec<Ty>) -> Self {
+        Self::Named {
+            module: ModuleName::new(PRELUDE_MODULE),
+            name: Name::new(name),
+            args,
+        }
+    }
+}
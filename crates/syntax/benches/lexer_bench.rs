@@ -1,18 +1,47 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use syntax::lexer::GleamLexer;
 
+const MODULE: &str = r#"
+//// A module with a bit of everything the lexer has to deal with.
+
+import gleam/io
+import gleam/list.{map, filter as keep}
+
+/// The answer.
+pub const answer = 42
+
+const greeting = "Hello, \"world\"! ßℝ💣"
+
+const pairs: #(Int, Float) = #(1_000, 1.5e3)
+"#;
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("lexer", |b| {
         b.iter(|| GleamLexer::new(black_box("This is a test file!!")).collect::<Vec<_>>())
     });
 
+    let mut group = c.benchmark_group("lexer_module");
+    for n in [1, 100] {
+        let src = MODULE.repeat(n);
+        group.throughput(Throughput::Bytes(src.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &src, |b, src| {
+            b.iter(|| GleamLexer::new(black_box(src)).count())
+        });
+    }
+    group.finish();
+
+    let strings = "\"a long string literal with \\\"escapes\\\" and ünïcödé\" ".repeat(1000);
+    c.bench_function("lexer_strings", |b| {
+        b.iter(|| GleamLexer::new(black_box(&strings)).count())
+    });
+
     #[cfg(feature = "comparison")]
     {
         use gleam_core::parse::lexer::make_tokenizer;
         let mut group = c.benchmark_group("lexers");
         for i in ["1//this ()is a comment\n//more comment\n()Create ridiculously fast Lexers.23123 1231 23123124 124 124 1", "fast ist the impossible"].iter() {
-          group.bench_with_input(BenchmarkId::new("Logox Lexer", i), i, 
-              |b, i| b.iter(|| lexer::LexToken::lexer(*i)));
+          group.bench_with_input(BenchmarkId::new("Gleamalyzer lexer", i), i, 
+              |b, i| b.iter(|| GleamLexer::new(*i).count()));
           group.bench_with_input(BenchmarkId::new("Gleam hand-crafted lexer", i), i, 
               |b, i| b.iter(|| make_tokenizer(*i)));
       }
@@ -0,0 +1,115 @@
+//! Finding tokens and elements at positions in the tree, eg. for the cursor of the editor.
+use std::iter::successors;
+
+use crate::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize};
+
+/// The token at `offset` which the user most likely means.
+///
+/// At the boundary of two tokens, as in `foo|(`, names and keywords win over literals, which
+/// win over symbols, which win over whitespace and comments. Ties go to the right one.
+pub fn best_token_at(node: &SyntaxNode, offset: TextSize) -> Option<SyntaxToken> {
+    node.token_at_offset(offset)
+        .max_by_key(|tok| token_priority(tok.kind()))
+}
+
+fn token_priority(kind: SyntaxKind) -> u8 {
+    match kind {
+        SyntaxKind::IDENT | SyntaxKind::U_IDENT | SyntaxKind::DISCARD_IDENT => 4,
+        _ if kind.is_keyword() => 4,
+        SyntaxKind::INTEGER | SyntaxKind::FLOAT | SyntaxKind::STRING => 3,
+        _ if kind.is_symbol() => 2,
+        _ if kind.is_whitespace() => 0,
+        _ => 1,
+    }
+}
+
+/// The smallest element covering the non-trivia tokens in `range`, so that selecting
+/// `  foo ` gives `foo` instead of its parent. An empty range gives the `best_token_at` it.
+pub fn covering_element(node: &SyntaxNode, range: TextRange) -> Option<SyntaxElement> {
+    let range = range.intersect(node.text_range())?;
+    if range.is_empty() {
+        return best_token_at(node, range.start()).map(Into::into);
+    }
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|tok| !tok.kind().is_whitespace())
+        .filter(|tok| {
+            tok.text_range()
+                .intersect(range)
+                .map_or(false, |it| !it.is_empty())
+        });
+    let trimmed = match tokens.next() {
+        Some(first) => {
+            let last = tokens.last().unwrap_or_else(|| first.clone());
+            first.text_range().cover(last.text_range())
+        }
+        None => range,
+    };
+    Some(node.covering_element(trimmed))
+}
+
+/// `token` itself or the first token after it which is not whitespace or a comment.
+pub fn skip_trivia_forwards(token: SyntaxToken) -> Option<SyntaxToken> {
+    successors(Some(token), SyntaxToken::next_token).find(|tok| !tok.kind().is_whitespace())
+}
+
+/// `token` itself or the first token before it which is not whitespace or a comment.
+pub fn skip_trivia_backwards(token: SyntaxToken) -> Option<SyntaxToken> {
+    successors(Some(token), SyntaxToken::prev_token).find(|tok| !tok.kind().is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_token_at, covering_element, skip_trivia_backwards, skip_trivia_forwards};
+    use crate::{parse_file, SyntaxKind, SyntaxNode, TextRange, TextSize};
+
+    fn parse_with_cursor(src: &str) -> (SyntaxNode, TextSize) {
+        let offset = src.find('$').unwrap();
+        let src = src.replace('$', "");
+        (
+            parse_file(&src).syntax_node(),
+            TextSize::from(offset as u32),
+        )
+    }
+
+    #[test]
+    fn best_token() {
+        let check = |src: &str, expect: &str| {
+            let (root, offset) = parse_with_cursor(src);
+            let tok = best_token_at(&root, offset).unwrap();
+            assert_eq!(tok.text(), expect, "{src}");
+        };
+        check("fn f() { foo$(1) }", "foo");
+        check("fn f() { ($foo) }", "foo");
+        check("fn f() { foo$ }", "foo");
+        check("fn f() { 1$+ 2 }", "1");
+        check("fn f() { 1 +$ 2 }", "+");
+        check("fn$ f() {}", "fn");
+    }
+
+    #[test]
+    fn covering() {
+        let root = parse_file("fn f() { g(  a  ) }").syntax_node();
+        let elem = covering_element(&root, TextRange::new(11.into(), 16.into())).unwrap();
+        assert_eq!(elem.to_string(), "a");
+        assert_eq!(elem.kind(), SyntaxKind::IDENT);
+
+        let elem = covering_element(&root, TextRange::new(9.into(), 18.into())).unwrap();
+        assert_eq!(elem.kind(), SyntaxKind::CALL_EXPR);
+
+        let elem = covering_element(&root, TextRange::empty(13.into())).unwrap();
+        assert_eq!(elem.to_string(), "a");
+
+        assert!(covering_element(&root, TextRange::new(100.into(), 101.into())).is_none());
+    }
+
+    #[test]
+    fn skip_trivia() {
+        let (root, offset) = parse_with_cursor("fn f() {$ // comment\n  a }");
+        let tok = root.token_at_offset(offset).right_biased().unwrap();
+        assert_eq!(tok.kind(), SyntaxKind::WHITESPACE);
+        assert_eq!(skip_trivia_forwards(tok.clone()).unwrap().text(), "a");
+        assert_eq!(skip_trivia_backwards(tok).unwrap().text(), "{");
+    }
+}
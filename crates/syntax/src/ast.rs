@@ -1,30 +1,152 @@
+pub mod edit;
+mod generated;
+pub mod make;
+pub mod visit;
+
 use crate::SyntaxKind::{self, *};
-use crate::{GleamLanguage, SyntaxNode, SyntaxToken};
+use crate::{GleamLanguage, SyntaxNode, SyntaxToken, TextSize};
 use rowan::ast::support::{child, children};
 use rowan::NodeOrToken;
+use std::borrow::Cow;
 
+pub use self::generated::*;
 pub use rowan::ast::{AstChildren, AstNode};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BinaryOpKind {
-    Imply,
     Or,
     And,
 
     Equal,
     NotEqual,
+
     Less,
     Greater,
     LessEqual,
     GreaterEqual,
+    LessFloat,
+    GreaterFloat,
+    LessEqualFloat,
+    GreaterEqualFloat,
 
-    Update,
     Concat,
 
+    Pipe,
+
     Add,
     Sub,
+    AddFloat,
+    SubFloat,
+
     Mul,
     Div,
+    Rem,
+    MulFloat,
+    DivFloat,
+}
+
+impl BinaryOpKind {
+    pub fn from_token(kind: SyntaxKind) -> Option<Self> {
+        Some(match kind {
+            T!["||"] => Self::Or,
+            T!["&&"] => Self::And,
+            T!["=="] => Self::Equal,
+            T!["!="] => Self::NotEqual,
+            T!["<"] => Self::Less,
+            T![">"] => Self::Greater,
+            T!["<="] => Self::LessEqual,
+            T![">="] => Self::GreaterEqual,
+            T!["<."] => Self::LessFloat,
+            T![">."] => Self::GreaterFloat,
+            T!["<=."] => Self::LessEqualFloat,
+            T![">=."] => Self::GreaterEqualFloat,
+            T!["<>"] => Self::Concat,
+            T!["|>"] => Self::Pipe,
+            T!["+"] => Self::Add,
+            T!["-"] => Self::Sub,
+            T!["+."] => Self::AddFloat,
+            T!["-."] => Self::SubFloat,
+            T!["*"] => Self::Mul,
+            T!["/"] => Self::Div,
+            T!["%"] => Self::Rem,
+            T!["*."] => Self::MulFloat,
+            T!["/."] => Self::DivFloat,
+            _ => return None,
+        })
+    }
+
+    pub fn token(self) -> SyntaxKind {
+        match self {
+            Self::Or => T!["||"],
+            Self::And => T!["&&"],
+            Self::Equal => T!["=="],
+            Self::NotEqual => T!["!="],
+            Self::Less => T!["<"],
+            Self::Greater => T![">"],
+            Self::LessEqual => T!["<="],
+            Self::GreaterEqual => T![">="],
+            Self::LessFloat => T!["<."],
+            Self::GreaterFloat => T![">."],
+            Self::LessEqualFloat => T!["<=."],
+            Self::GreaterEqualFloat => T![">=."],
+            Self::Concat => T!["<>"],
+            Self::Pipe => T!["|>"],
+            Self::Add => T!["+"],
+            Self::Sub => T!["-"],
+            Self::AddFloat => T!["+."],
+            Self::SubFloat => T!["-."],
+            Self::Mul => T!["*"],
+            Self::Div => T!["/"],
+            Self::Rem => T!["%"],
+            Self::MulFloat => T!["*."],
+            Self::DivFloat => T!["/."],
+        }
+    }
+
+    /// Operators of a higher precedence bind tighter, eg. `*` over `+`.
+    pub const fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Equal | Self::NotEqual => 3,
+            Self::Less
+            | Self::Greater
+            | Self::LessEqual
+            | Self::GreaterEqual
+            | Self::LessFloat
+            | Self::GreaterFloat
+            | Self::LessEqualFloat
+            | Self::GreaterEqualFloat => 4,
+            Self::Concat => 5,
+            Self::Pipe => 6,
+            Self::Add | Self::Sub | Self::AddFloat | Self::SubFloat => 7,
+            Self::Mul | Self::Div | Self::Rem | Self::MulFloat | Self::DivFloat => 8,
+        }
+    }
+
+    /// All binary operators of Gleam are left associative, `a - b - c` is `(a - b) - c`.
+    pub const fn associativity(self) -> Associativity {
+        Associativity::Left
+    }
+
+    /// Whether `operand` needs parentheses on the given side of this operator to keep its
+    /// meaning, eg. `a + b` as the left operand of `*`.
+    pub fn needs_parens(self, operand: Self, is_rhs: bool) -> bool {
+        match operand.precedence().cmp(&self.precedence()) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Greater => false,
+            std::cmp::Ordering::Equal => match self.associativity() {
+                Associativity::Left => is_rhs,
+                Associativity::Right => !is_rhs,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    Left,
+    Right,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -33,6 +155,29 @@ pub enum UnaryOpKind {
     Negate,
 }
 
+impl UnaryOpKind {
+    pub fn from_token(kind: SyntaxKind) -> Option<Self> {
+        Some(match kind {
+            T!["!"] => Self::Not,
+            T!["-"] => Self::Negate,
+            _ => return None,
+        })
+    }
+
+    pub fn token(self) -> SyntaxKind {
+        match self {
+            Self::Not => T!["!"],
+            Self::Negate => T!["-"],
+        }
+    }
+
+    /// Prefix operators bind tighter than all binary ones, but looser than calls and field
+    /// access.
+    pub const fn precedence(self) -> u8 {
+        9
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LiteralKind {
     Int,
@@ -40,242 +185,606 @@ pub enum LiteralKind {
     String,
 }
 
-trait NodeWrapper {
-    const KIND: SyntaxKind;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BitArrayOptionKind {
+    Size,
+    Unit,
+
+    Bits,
+    Bytes,
+    Int,
+    Float,
+    Utf8,
+    Utf16,
+    Utf32,
+    Utf8Codepoint,
+    Utf16Codepoint,
+    Utf32Codepoint,
+
+    Signed,
+    Unsigned,
+
+    Big,
+    Little,
+    Native,
 }
 
-macro_rules! enums {
-    ($($name:ident { $($variant:ident,)* },)*) => {
-        $(
-        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-        pub enum $name {
-            $($variant($variant),)*
+/// Items which can be documented with preceding `///` comments.
+pub trait HasDocComments: AstNode<Language = GleamLanguage> {
+    /// The text of the doc comments directly preceding this node, one line per comment.
+    fn doc_comment_text(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut tok = self.syntax().first_token()?.prev_token();
+        while let Some(t) = tok {
+            match t.kind() {
+                COMMENT_STATEMENT => lines.push(strip_comment(t.text(), "///").to_owned()),
+                WHITESPACE => {}
+                _ => break,
+            }
+            tok = t.prev_token();
+        }
+        if lines.is_empty() {
+            return None;
         }
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+}
 
-        impl AstNode for $name {
-            type Language = GleamLanguage;
+/// Items which define a name.
+pub trait HasName: AstNode<Language = GleamLanguage> {
+    fn name(&self) -> Option<Name> {
+        child(self.syntax())
+    }
+}
 
-            fn can_cast(kind: SyntaxKind) -> bool
-                where Self: Sized
-            {
-                matches!(kind, $(<$variant as NodeWrapper>::KIND)|*)
-            }
+/// Items which can be exported with `pub`.
+pub trait HasVisibility: AstNode<Language = GleamLanguage> {
+    fn is_public(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == T!["pub"])
+    }
+}
 
-            fn cast(node: SyntaxNode) -> Option<Self>
-            where
-                Self: Sized
-            {
-                match node.kind() {
-                    $(<$variant as NodeWrapper>::KIND => Some(Self::$variant($variant(node))),)*
-                    _ => None,
-                }
-            }
+/// Items which can be annotated with attributes like `@deprecated("...")`.
+pub trait HasAttributes: AstNode<Language = GleamLanguage> {
+    fn attributes(&self) -> AstChildren<Attribute> {
+        children(self.syntax())
+    }
+}
+
+fn strip_comment<'a>(text: &'a str, prefix: &str) -> &'a str {
+    let text = text.strip_prefix(prefix).unwrap_or(text);
+    text.strip_prefix(' ').unwrap_or(text)
+}
+
+/// A statement in a block, not to be confused with the module level [`Statement`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Stmt {
+    Let(LetStatement),
+    Use(UseExpr),
+    Expr(Expr),
+}
+
+impl AstNode for Stmt {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool
+    where
+        Self: Sized,
+    {
+        matches!(kind, LET_STATEMENT | USE_EXPR) || Expr::can_cast(kind)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match node.kind() {
+            LET_STATEMENT => Some(Self::Let(LetStatement(node))),
+            USE_EXPR => Some(Self::Use(UseExpr(node))),
+            _ => Expr::cast(node).map(Self::Expr),
+        }
+    }
 
-            fn syntax(&self) -> &SyntaxNode {
-                match self {
-                    $(Self::$variant(e) => &e.0,)*
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Let(e) => e.syntax(),
+            Self::Use(e) => e.syntax(),
+            Self::Expr(e) => e.syntax(),
+        }
+    }
+}
+
+/// A chain of `|>` applications, viewed as a whole.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PipelineExpr(BinaryExpr);
+
+impl PipelineExpr {
+    pub fn cast(expr: BinaryExpr) -> Option<Self> {
+        (expr.op_kind() == Some(BinaryOpKind::Pipe)).then_some(Self(expr))
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode {
+        self.0.syntax()
+    }
+
+    /// The piped value followed by every function it is piped through, in evaluation order.
+    pub fn stages(&self) -> impl Iterator<Item = Expr> {
+        let mut stages = Vec::new();
+        let mut cur = self.0.clone();
+        loop {
+            stages.extend(cur.rhs());
+            match cur.lhs() {
+                Some(Expr::BinaryExpr(lhs)) if lhs.op_kind() == Some(BinaryOpKind::Pipe) => {
+                    cur = lhs;
+                }
+                lhs => {
+                    stages.extend(lhs);
+                    break;
                 }
             }
         }
-        )*
-    };
-}
-
-macro_rules! asts {
-    (
-        $(
-            $kind:ident = $name:ident $([$trait:tt])?
-            { $($impl:tt)* },
-        )*
-    ) => {
-        $(
-        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-        pub struct $name(SyntaxNode);
-
-        impl $name {
-            ast_impl!($($impl)*);
+        stages.into_iter().rev()
+    }
+}
+
+/// The documentation of a source file is its module documentation.
+impl HasDocComments for SourceFile {
+    fn doc_comment_text(&self) -> Option<String> {
+        self.module_doc()
+    }
+}
+
+impl HasDocComments for CustomType {}
+impl HasDocComments for ExternalFunction {}
+impl HasDocComments for ExternalType {}
+impl HasDocComments for Function {}
+impl HasDocComments for ModuleConstant {}
+impl HasDocComments for TypeAlias {}
+
+/// Only aliased imports have a name, others are referred to by the last segment of their path.
+impl HasName for Import {
+    fn name(&self) -> Option<Name> {
+        self.module()?.as_name()
+    }
+}
+
+/// Imports cannot be public, but still are items like any other.
+impl HasVisibility for Import {}
+
+impl HasName for Statement {
+    fn name(&self) -> Option<Name> {
+        match self {
+            Self::Import(it) => it.name(),
+            _ => child(self.syntax()),
         }
+    }
+}
+
+impl HasVisibility for Statement {}
 
-        $(impl $trait for $name {})*
+impl HasAttributes for Statement {}
 
-        impl NodeWrapper for $name {
-            const KIND: SyntaxKind = SyntaxKind::$kind;
+impl HasName for ModuleItem {
+    fn name(&self) -> Option<Name> {
+        match self {
+            Self::Import(it) => it.name(),
+            _ => child(self.syntax()),
         }
+    }
+}
 
-        impl AstNode for $name {
-            type Language = GleamLanguage;
+impl HasVisibility for ModuleItem {}
 
-            fn can_cast(kind: SyntaxKind) -> bool
-                where Self: Sized
-            {
-                kind == SyntaxKind::$kind
-            }
+impl HasAttributes for ModuleItem {}
 
-            fn cast(node: SyntaxNode) -> Option<Self>
-            where
-                Self: Sized
-            {
-                if node.kind() == SyntaxKind::$kind {
-                    Some(Self(node))
-                } else {
-                    None
-                }
-            }
+impl HasName for TypeDefinition {}
+impl HasVisibility for TypeDefinition {}
+impl HasAttributes for TypeDefinition {}
+impl HasDocComments for TypeDefinition {}
 
-            fn syntax(&self) -> &SyntaxNode {
-                &self.0
+impl AlternativePattern {
+    /// The patterns of each alternative, which are several ones if the case has multiple subjects.
+    pub fn alternatives(&self) -> Vec<Vec<Pattern>> {
+        let mut ret = vec![Vec::new()];
+        for it in self.0.children_with_tokens() {
+            match it {
+                NodeOrToken::Token(tok) if tok.kind() == T!["|"] => ret.push(Vec::new()),
+                NodeOrToken::Node(node) => ret.last_mut().unwrap().extend(Pattern::cast(node)),
+                NodeOrToken::Token(_) => {}
             }
         }
-        )*
-    };
-}
-
-macro_rules! ast_impl {
-    () => {};
-    ($field:ident: $ast:ident, $($tt:tt)*) => {
-        pub fn $field(&self) -> Option<$ast> { child(&self.0) }
-        ast_impl!($($tt)*);
-    };
-    ($field:ident[$k:tt]: $ast:ident, $($tt:tt)*) => {
-        pub fn $field(&self) -> Option<$ast> { children(&self.0).nth($k) }
-        ast_impl!($($tt)*);
-    };
-    ($field:ident: [$ast:ident], $($tt:tt)*) => {
-        pub fn $field(&self) -> AstChildren<$ast> { children(&self.0) }
-        ast_impl!($($tt)*);
-    };
-    ($field:ident: T![$tok:tt], $($tt:tt)*) => {
-        pub fn $field(&self) -> Option<SyntaxToken> {
-            token(&self.0, T![$tok])
-        }
-        ast_impl!($($tt)*);
-    };
-    ($field:ident[$k:tt]: T![$tok:tt], $($tt:tt)*) => {
-        pub fn $field(&self) -> Option<SyntaxToken> {
-            self.0
-                .children_with_tokens()
-                .filter_map(|it| it.into_token())
-                .filter(|it| it.kind() == T![$tok])
-                .nth($k)
-        }
-        ast_impl!($($tt)*);
-    };
-    ($($item:item)*) => {
-        $($item)*
-    };
-}
-
-enums! {
-    Statement {
-        ModuleConstant,
-        Import,
-    },
-    ConstantValue {
-        Literal,
-        Tuple,
-        List,
-    },
-    TypeAnnotation {
-        FnType,
-        VarType,
-        TupleType,
-        ConstructorType,
-    },
-}
-
-asts! {
-    LIST = List {
-        elements: [ConstantValue],
-    },
-    LITERAL = Literal {
-        pub fn token(&self) -> Option<SyntaxToken> {
-            self.0.children_with_tokens().find_map(NodeOrToken::into_token)
+        ret
+    }
+}
+
+impl Attribute {
+    pub fn arguments(&self) -> impl Iterator<Item = Expr> {
+        self.arg_list()
+            .into_iter()
+            .flat_map(|args| args.args())
+            .filter_map(|arg| arg.value())
+    }
+}
+
+impl BinaryExpr {
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|tok| !tok.kind().is_whitespace())
+    }
+
+    pub fn op_kind(&self) -> Option<BinaryOpKind> {
+        BinaryOpKind::from_token(self.op_token()?.kind())
+    }
+}
+
+impl BitArrayOption {
+    pub fn kind(&self) -> Option<BitArrayOptionKind> {
+        let Some(name) = self.name() else {
+            return self.value().map(|_| BitArrayOptionKind::Size);
+        };
+        Some(match name.text() {
+            "size" => BitArrayOptionKind::Size,
+            "unit" => BitArrayOptionKind::Unit,
+            "bits" | "bit_string" | "bit_array" => BitArrayOptionKind::Bits,
+            "bytes" | "binary" => BitArrayOptionKind::Bytes,
+            "int" => BitArrayOptionKind::Int,
+            "float" => BitArrayOptionKind::Float,
+            "utf8" => BitArrayOptionKind::Utf8,
+            "utf16" => BitArrayOptionKind::Utf16,
+            "utf32" => BitArrayOptionKind::Utf32,
+            "utf8_codepoint" => BitArrayOptionKind::Utf8Codepoint,
+            "utf16_codepoint" => BitArrayOptionKind::Utf16Codepoint,
+            "utf32_codepoint" => BitArrayOptionKind::Utf32Codepoint,
+            "signed" => BitArrayOptionKind::Signed,
+            "unsigned" => BitArrayOptionKind::Unsigned,
+            "big" => BitArrayOptionKind::Big,
+            "little" => BitArrayOptionKind::Little,
+            "native" => BitArrayOptionKind::Native,
+            _ => return None,
+        })
+    }
+}
+
+impl Block {
+    /// The last statement if it is an expression, which is the value of the block.
+    pub fn tail_expr(&self) -> Option<Expr> {
+        match self.statements().last()? {
+            Stmt::Expr(expr) => Some(expr),
+            _ => None,
         }
+    }
+}
 
-        pub fn kind(&self) -> Option<LiteralKind> {
-            Some(match self.token()?.kind() {
-                INTEGER => LiteralKind::Int,
-                FLOAT => LiteralKind::Float,
-                STRING => LiteralKind::String,
-                _ => return None,
-            })
+impl CaseClause {
+    pub fn guard(&self) -> Option<Expr> {
+        child::<CaseGuard>(&self.0)?.expr()
+    }
+}
+
+impl ConcatPattern {
+    pub fn prefix(&self) -> Option<Literal> {
+        self.0.children().find_map(LiteralPattern::cast)?.literal()
+    }
+
+    /// The binding of the remainder of the string, `None` if it is discarded.
+    pub fn rest_name(&self) -> Option<Name> {
+        match self.rest()? {
+            Pattern::VariablePattern(it) => it.name(),
+            _ => None,
         }
-    },
-    IMPORT = Import {
-        module: ImportModule,
-    },
-    IMPORT_MODULE = ImportModule {
-        module_path: [Path],
-        as_name: Name,
-        unqualified: [UnqualifiedImport],
-    },
-    SOURCE_FILE = SourceFile {
-        statements: [TargetGroup],
-    },
-    MODULE_NAME = ModuleName {
-        pub fn token(&self) -> Option<SyntaxToken> {
-            self.0.children_with_tokens().find_map(NodeOrToken::into_token)
+    }
+}
+
+impl ConstructorPattern {
+    /// The constructor name, whether it is qualified by a module or not.
+    pub fn constructor(&self) -> Option<NameRef> {
+        match self.qualified_ref() {
+            Some(qualified) => qualified.name(),
+            None => self.0.children().find_map(NameRef::cast),
         }
-    },
-    MODULE_CONSTANT = ModuleConstant {
-        name: Name,
-        value: ConstantValue,
-        annotation: TypeAnnotation,
-        pub fn is_public(&self) -> bool {
-            self.syntax().children_with_tokens().find(|it| it.kind() == T!["pub"]).is_some()
+    }
+
+    /// Whether the remaining fields are ignored with `..`.
+    pub fn has_spread(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == T![".."])
+    }
+
+    pub fn positional_args(&self) -> impl Iterator<Item = PatternArg> {
+        self.args().filter(|arg| arg.label().is_none())
+    }
+
+    pub fn labelled_args(&self) -> impl Iterator<Item = PatternArg> {
+        self.args().filter(|arg| arg.label().is_some())
+    }
+}
+
+impl CustomType {
+    /// Opaque types do not expose their constructors outside of the defining module.
+    pub fn is_opaque(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == T!["opaque"])
+    }
+}
+
+impl CallExpr {
+    /// The updated record if this is a record update, as in `Person(..old, name: "x")`.
+    /// The overridden fields are the labelled arguments.
+    pub fn record_spread(&self) -> Option<RecordSpread> {
+        self.arg_list()?.record_spread()
+    }
+
+    /// Whether this is a function capture like `add(1, _)` rather than a call.
+    pub fn is_capture(&self) -> bool {
+        self.arg_list().map_or(false, |args| {
+            args.args()
+                .any(|arg| matches!(arg.value(), Some(Expr::CaptureHole(_))))
+        })
+    }
+}
+
+impl Function {
+    /// Type variables mentioned in the parameter and return annotations, in order of first
+    /// occurrence. Gleam has no explicit type parameter list, so these are the generics of
+    /// the function.
+    pub fn type_params_in_signature(&self) -> Vec<VarType> {
+        let mut ret: Vec<VarType> = Vec::new();
+        let signature = self
+            .param_list()
+            .map(|it| it.syntax().clone())
+            .into_iter()
+            .chain(self.return_annotation().map(|it| it.syntax().clone()));
+        for var in signature
+            .flat_map(|it| it.descendants())
+            .filter_map(VarType::cast)
+        {
+            let Some(name) = var.name() else {
+                continue;
+            };
+            let seen = ret
+                .iter()
+                .any(|prev| prev.name().map_or(false, |tok| tok.text() == name.text()));
+            if !seen {
+                ret.push(var);
+            }
         }
-    },
-    NAME = Name {
-        pub fn token(&self) -> Option<SyntaxToken> {
-            self.0.children_with_tokens().find_map(NodeOrToken::into_token)
+        ret
+    }
+}
+
+impl DiscardPattern {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+}
+
+impl FieldAccess {
+    /// The container can be a `NameRef` too, so only look after the `.`.
+    pub fn label(&self) -> Option<NameRef> {
+        self.0
+            .children_with_tokens()
+            .skip_while(|it| it.kind() != T!["."])
+            .find_map(|it| it.into_node().and_then(NameRef::cast))
+    }
+}
+
+impl LetStatement {
+    /// Bit arrays are patterns as well, so only look after the `=`.
+    pub fn value(&self) -> Option<Expr> {
+        self.0
+            .children_with_tokens()
+            .skip_while(|it| it.kind() != T!["="])
+            .find_map(|it| it.into_node().and_then(Expr::cast))
+    }
+
+    pub fn is_assert(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == T!["assert"])
+    }
+}
+
+impl Literal {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+
+    pub fn kind(&self) -> Option<LiteralKind> {
+        Some(match self.token()?.kind() {
+            INTEGER => LiteralKind::Int,
+            FLOAT => LiteralKind::Float,
+            STRING => LiteralKind::String,
+            _ => return None,
+        })
+    }
+
+    /// The value of a float literal, or `None` if it overflows, which the parser reports.
+    pub fn float_value(&self) -> Option<f64> {
+        let tok = self.token().filter(|tok| tok.kind() == FLOAT)?;
+        crate::lexer::parse_float(tok.text())
+    }
+
+    /// The radix of an integer literal, as given by its `0x`, `0o` or `0b` prefix.
+    pub fn radix(&self) -> Option<u32> {
+        let tok = self.token().filter(|tok| tok.kind() == INTEGER)?;
+        Some(crate::lexer::split_radix(tok.text()).0)
+    }
+
+    /// The contents of a string literal with its escapes decoded, or `None` if any escape is
+    /// invalid, which the parser reports.
+    ///
+    /// The token text cannot be borrowed from the node, use
+    /// `lexer::unescape_string_literal` on the token to avoid copying strings without escapes.
+    pub fn string_value(&self) -> Option<String> {
+        let tok = self.token().filter(|tok| tok.kind() == STRING)?;
+        crate::lexer::unescape_string_literal(tok.text()).map(Cow::into_owned)
+    }
+}
+
+impl SourceFile {
+    /// All module level statements, including those in target groups.
+    pub fn items(&self) -> impl Iterator<Item = Statement> {
+        self.statements().flat_map(|group| group.statements())
+    }
+
+    pub fn imports(&self) -> impl Iterator<Item = Import> {
+        self.items().filter_map(|it| match it {
+            Statement::Import(it) => Some(it),
+            _ => None,
+        })
+    }
+
+    /// Functions with a body or an `@external` attribute, but not the deprecated
+    /// `external fn`.
+    pub fn functions(&self) -> impl Iterator<Item = Function> {
+        self.items().filter_map(|it| match it {
+            Statement::Function(it) => Some(it),
+            _ => None,
+        })
+    }
+
+    pub fn constants(&self) -> impl Iterator<Item = ModuleConstant> {
+        self.items().filter_map(|it| match it {
+            Statement::ModuleConstant(it) => Some(it),
+            _ => None,
+        })
+    }
+
+    pub fn type_definitions(&self) -> impl Iterator<Item = TypeDefinition> {
+        self.items()
+            .filter_map(|it| TypeDefinition::cast(it.syntax().clone()))
+    }
+
+    /// Items without expressions, eg. to collect the declarations of a module without
+    /// walking function bodies.
+    pub fn module_items(&self) -> impl Iterator<Item = ModuleItem> {
+        self.items()
+            .filter_map(|it| ModuleItem::cast(it.syntax().clone()))
+    }
+
+    /// The statement containing `offset`, or else the one ending at it, eg. at the end of the
+    /// file.
+    pub fn item_at_offset(&self, offset: TextSize) -> Option<Statement> {
+        self.items()
+            .find(|it| it.syntax().text_range().contains(offset))
+            .or_else(|| {
+                self.items()
+                    .find(|it| it.syntax().text_range().end() == offset)
+            })
+    }
+
+    /// The `////` module documentation.
+    pub fn module_doc(&self) -> Option<String> {
+        let lines = self
+            .0
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|tok| tok.kind() == COMMENT_MODULE)
+            .map(|tok| strip_comment(tok.text(), "////").to_owned())
+            .collect::<Vec<_>>();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+impl ModuleName {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+}
+
+impl Name {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+}
+
+impl NameRef {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+}
+
+impl Path {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .find_map(NodeOrToken::into_token)
+    }
+}
+
+impl Param {
+    /// The external label, as in `fn insert(into dict: Dict(k, v))`. Parameters of the
+    /// deprecated `external fn` only have a type, so their only `Name` is the label, as in
+    /// `external fn now(unit: Atom) -> Int`.
+    pub fn label(&self) -> Option<Name> {
+        let mut names = children::<Name>(&self.0);
+        let first = names.next();
+        if self.is_external() {
+            return first;
         }
-    },
-    PATH = Path {
-        pub fn token(&self) -> Option<SyntaxToken> {
-            self.0.children_with_tokens().find_map(NodeOrToken::into_token)
+        names.next().and(first)
+    }
+
+    pub fn name(&self) -> Option<Name> {
+        if self.is_external() {
+            return None;
         }
-    },
-    UNQUALIFIED_IMPORT = UnqualifiedImport {
-      name: Name,
-      as_name[1]: Name,
-    },
-    PARAM = Param {
-        // pat: Pat,
-        ty: TypeAnnotation,
-    },
-    PARAM_LIST = ParamList {
-        params: [Param],
-    },
-    TARGET = Target {
-        name: Name,
-    },
-    TARGET_GROUP = TargetGroup {
-        target: Target,
-        statements: [Statement],
-    },
-    TUPLE = Tuple {
-        elements: [ConstantValue],
-    },
-    CONSTRUCTOR_TYPE = ConstructorType {
-      constructor: Name,
-      module: ModuleName,
-    },
-    TUPLE_TYPE = TupleType{
-      field_types: [TypeAnnotation],
-    },
-    FN_TYPE = FnType {
-        param_list: ParamList,
-        return_: TypeAnnotation,
-    },
-    VAR_TYPE = VarType {
-        name: Name,
-    },
+        children(&self.0).last()
+    }
+
+    fn is_external(&self) -> bool {
+        self.0
+            .ancestors()
+            .nth(2)
+            .map_or(false, |it| it.kind() == EXTERNAL_FUNCTION)
+    }
+}
+
+impl UnaryExpr {
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|tok| !tok.kind().is_whitespace())
+    }
+
+    pub fn op_kind(&self) -> Option<UnaryOpKind> {
+        UnaryOpKind::from_token(self.op_token()?.kind())
+    }
+}
+
+impl UseExpr {
+    /// The rest of the enclosing block, which is passed to the callee as the last argument.
+    pub fn continuation(&self) -> impl Iterator<Item = Stmt> {
+        self.0
+            .siblings(rowan::Direction::Next)
+            .skip(1)
+            .filter_map(Stmt::cast)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::parse;
+    use crate::{ErrorKind, LanguageVersion, TextRange};
 
     trait HasSyntaxNode {
         fn has_syntax_node(&self) -> &SyntaxNode;
@@ -315,6 +824,84 @@ mod tests {
         println!("{:#?}", e.syntax_node())
     }
 
+    #[test]
+    fn list_recovery() {
+        let parse = crate::parse_file("const a = #(1,, 2)\nconst b = [1 ? 2 3]");
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                ErrorKind::ExpectedElement,
+                ErrorKind::ExpectToken(T!["]"]),
+                ErrorKind::ExpectToken(T![","]),
+            ]
+        );
+        let expected = parse.errors()[1].expected;
+        assert_eq!(expected.iter().collect::<Vec<_>>(), [T!["]"], T![","]]);
+        let tuple = parse
+            .syntax_node()
+            .descendants()
+            .find_map(Tuple::cast)
+            .unwrap();
+        assert_eq!(tuple.elements().count(), 2);
+        let list = parse
+            .syntax_node()
+            .descendants()
+            .find_map(List::cast)
+            .unwrap();
+        assert_eq!(list.elements().count(), 3);
+
+        let parse = crate::parse_file("import aa/a.{m as a, M as A as e\nconst b = 1");
+        let import = parse
+            .syntax_node()
+            .descendants()
+            .find_map(ImportModule::cast)
+            .unwrap();
+        let mut iter = import.unqualified();
+        iter.next().unwrap().name().unwrap().syntax().should_eq("m");
+        iter.next().unwrap().name().unwrap().syntax().should_eq("M");
+        iter.next().unwrap().name().unwrap().syntax().should_eq("e");
+        assert!(iter.next().is_none());
+        assert!(parse
+            .root()
+            .syntax()
+            .descendants()
+            .any(|it| it.kind() == MODULE_CONSTANT));
+
+        let parse = crate::parse_file("fn f(g: fn(Int,, ? String) -> Int) { <<1,, 2 ?>> }");
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                ErrorKind::ExpectedElement,
+                ErrorKind::ExpectToken(T![")"]),
+                ErrorKind::ExpectedElement,
+                ErrorKind::ExpectToken(T![">>"]),
+            ]
+        );
+        let fn_type = parse
+            .syntax_node()
+            .descendants()
+            .find_map(FnType::cast)
+            .unwrap();
+        assert_eq!(fn_type.param_list().unwrap().params().count(), 2);
+        fn_type.return_().unwrap().syntax().should_eq("Int");
+        let bit_array = parse
+            .syntax_node()
+            .descendants()
+            .find_map(BitArray::cast)
+            .unwrap();
+        assert_eq!(bit_array.segments().count(), 2);
+    }
+
     #[test]
     fn pub_const() {
         let e = parse::<ModuleConstant>("pub const a = \"123\"");
@@ -322,6 +909,986 @@ mod tests {
         assert!(e.is_public());
     }
 
+    #[test]
+    fn upper_case_names() {
+        let e = parse::<ModuleConstant>("const MaxPort = 1");
+        e.name().unwrap().token().unwrap().should_eq("MaxPort");
+        let e = parse::<ImportModule>("import a as B");
+        e.as_name().unwrap().token().unwrap().should_eq("B");
+    }
+
+    #[test]
+    fn function() {
+        let e = parse::<Function>("pub fn add(x: Int, _y) -> Int { x }");
+        assert!(e.is_public());
+        e.name().unwrap().syntax().should_eq("add");
+        let mut iter = e.param_list().unwrap().params();
+        let x = iter.next().unwrap();
+        x.name().unwrap().syntax().should_eq("x");
+        x.ty().unwrap().syntax().should_eq("Int");
+        let y = iter.next().unwrap();
+        y.name().unwrap().syntax().should_eq("_y");
+        assert!(y.ty().is_none());
+        assert!(iter.next().is_none());
+        e.return_annotation().unwrap().syntax().should_eq("Int");
+        e.body().unwrap().syntax().should_eq("{ x }");
+
+        let e = parse::<Function>("fn main() {}");
+        assert!(!e.is_public());
+        assert!(e.return_annotation().is_none());
+        assert!(e.body().unwrap().statements().next().is_none());
+    }
+
+    #[test]
+    fn call() {
+        let e = parse::<CallExpr>("fn main() { println(\"hi\", #(1, [2])) 1 }");
+        e.callee().unwrap().syntax().should_eq("println");
+        let mut iter = e.arg_list().unwrap().args();
+        iter.next().unwrap().syntax().should_eq("\"hi\"");
+        iter.next().unwrap().syntax().should_eq("#(1, [2])");
+        assert!(iter.next().is_none());
+
+        let e = parse::<Block>("fn main() { f(1)(2) Ok }");
+        let mut iter = e.statements();
+        iter.next().unwrap().syntax().should_eq("f(1)(2)");
+        iter.next().unwrap().syntax().should_eq("Ok");
+    }
+
+    #[test]
+    fn let_statement() {
+        let e = parse::<LetStatement>("fn main() { let x: Int = 1 x }");
+        assert!(!e.is_assert());
+        e.pattern().unwrap().syntax().should_eq("x");
+        e.annotation().unwrap().syntax().should_eq("Int");
+        e.value().unwrap().syntax().should_eq("1");
+
+        let e = parse::<LetStatement>("fn main() { let assert <<a>> = <<1>> }");
+        assert!(e.is_assert());
+        assert!(e.annotation().is_none());
+        e.pattern().unwrap().syntax().should_eq("<<a>>");
+        e.value().unwrap().syntax().should_eq("<<1>>");
+
+        let e = parse::<Block>("fn main() { let assert Ok(x) = f() x }");
+        let mut iter = e.statements();
+        assert!(matches!(iter.next(), Some(Stmt::Let(_))));
+        assert!(matches!(iter.next(), Some(Stmt::Expr(Expr::NameRef(_)))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn use_expr() {
+        let e =
+            parse::<UseExpr>("fn main() { use a, #(b, c): #(Int, Int) <- then(f()) let d = a d }");
+        let mut iter = e.assignments();
+        let a = iter.next().unwrap();
+        a.pattern().unwrap().syntax().should_eq("a");
+        assert!(a.annotation().is_none());
+        let bc = iter.next().unwrap();
+        bc.pattern().unwrap().syntax().should_eq("#(b, c)");
+        bc.annotation().unwrap().syntax().should_eq("#(Int, Int)");
+        assert!(iter.next().is_none());
+        e.callee().unwrap().syntax().should_eq("then(f())");
+        let mut iter = e.continuation();
+        iter.next().unwrap().syntax().should_eq("let d = a");
+        iter.next().unwrap().syntax().should_eq("d");
+        assert!(iter.next().is_none());
+
+        let e = parse::<UseExpr>("fn main() { use <- defer(f) 1 }");
+        assert!(e.assignments().next().is_none());
+        e.callee().unwrap().syntax().should_eq("defer(f)");
+    }
+
+    #[test]
+    fn pipeline() {
+        let e = parse::<BinaryExpr>("fn main() { x |> f(1) |> g }");
+        assert_eq!(e.op_kind(), Some(BinaryOpKind::Pipe));
+        e.lhs().unwrap().syntax().should_eq("x |> f(1)");
+        e.rhs().unwrap().syntax().should_eq("g");
+
+        let stages = PipelineExpr::cast(e)
+            .unwrap()
+            .stages()
+            .map(|it| it.syntax().to_string().trim().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(stages, ["x", "f(1)", "g"]);
+    }
+
+    #[test]
+    fn capture() {
+        let e = parse::<CallExpr>("fn main() { add(1, _) }");
+        assert!(e.is_capture());
+        let mut iter = e.arg_list().unwrap().args();
+        assert!(matches!(
+            iter.next().unwrap().value(),
+            Some(Expr::Literal(_))
+        ));
+        assert!(matches!(
+            iter.next().unwrap().value(),
+            Some(Expr::CaptureHole(_))
+        ));
+
+        let e = parse::<CallExpr>("fn main() { add(1, 2) }");
+        assert!(!e.is_capture());
+    }
+
+    #[test]
+    fn labelled() {
+        let e = parse::<Function>("fn insert(into dict: Dict, for _key, value) { 1 }");
+        let mut iter = e.param_list().unwrap().params();
+        let into = iter.next().unwrap();
+        into.label().unwrap().syntax().should_eq("into");
+        into.name().unwrap().syntax().should_eq("dict");
+        into.ty().unwrap().syntax().should_eq("Dict");
+        let key = iter.next().unwrap();
+        key.label().unwrap().syntax().should_eq("for");
+        key.name().unwrap().syntax().should_eq("_key");
+        let value = iter.next().unwrap();
+        assert!(value.label().is_none());
+        value.name().unwrap().syntax().should_eq("value");
+
+        let e = parse::<ArgList>("fn main() { insert(into: d, 1) }");
+        let mut iter = e.args();
+        let into = iter.next().unwrap();
+        into.label().unwrap().syntax().should_eq("into");
+        into.value().unwrap().syntax().should_eq("d");
+        let one = iter.next().unwrap();
+        assert!(one.label().is_none());
+        one.value().unwrap().syntax().should_eq("1");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn field_access() {
+        let e = parse::<FieldAccess>("fn main() { user.person.name(1) }");
+        e.container().unwrap().syntax().should_eq("user.person");
+        e.label().unwrap().syntax().should_eq("name");
+        assert!(e.index().is_none());
+
+        let e = parse::<FieldAccess>("fn main() { f(x).0 }");
+        e.container().unwrap().syntax().should_eq("f(x)");
+        assert!(e.label().is_none());
+        e.index().unwrap().should_eq("0");
+
+        let e = parse::<FieldAccess>("fn main() { pair.1 }");
+        e.container().unwrap().syntax().should_eq("pair");
+        assert!(e.label().is_none());
+    }
+
+    #[test]
+    fn qualified_ref() {
+        let e = parse::<CallExpr>("fn main() { list.map(xs, option.Some) }");
+        let Some(Expr::QualifiedRef(callee)) = e.callee() else {
+            panic!()
+        };
+        callee.module().unwrap().syntax().should_eq("list");
+        callee.name().unwrap().syntax().should_eq("map");
+        let some = e
+            .arg_list()
+            .unwrap()
+            .args()
+            .nth(1)
+            .unwrap()
+            .value()
+            .unwrap();
+        assert!(matches!(some, Expr::QualifiedRef(_)));
+
+        let e = parse::<ConstructorPattern>("fn f() { case x { option.Some(y) -> y } }");
+        let qualified = e.qualified_ref().unwrap();
+        qualified.module().unwrap().syntax().should_eq("option");
+        e.constructor().unwrap().syntax().should_eq("Some");
+        assert_eq!(e.args().count(), 1);
+
+        let e = parse::<ConstructorPattern>("fn f() { case x { None -> 1 } }");
+        assert!(e.qualified_ref().is_none());
+        e.constructor().unwrap().syntax().should_eq("None");
+    }
+
+    #[test]
+    fn record_update() {
+        let e = parse::<CallExpr>("fn main() { Person(..old, name: \"x\") }");
+        e.record_spread()
+            .unwrap()
+            .expr()
+            .unwrap()
+            .syntax()
+            .should_eq("old");
+        let mut iter = e.arg_list().unwrap().args();
+        iter.next()
+            .unwrap()
+            .label()
+            .unwrap()
+            .syntax()
+            .should_eq("name");
+        assert!(iter.next().is_none());
+
+        let e = parse::<CallExpr>("fn main() { Person(\"x\") }");
+        assert!(e.record_spread().is_none());
+    }
+
+    #[test]
+    fn todo_panic() {
+        let e = parse::<TodoExpr>("fn main() { todo }");
+        assert!(e.message().is_none());
+
+        let e = parse::<TodoExpr>("fn main() { todo as \"later\" }");
+        e.message().unwrap().syntax().should_eq("\"later\"");
+
+        let e = parse::<PanicExpr>("fn main() { panic as msg(1) }");
+        e.message().unwrap().syntax().should_eq("msg(1)");
+    }
+
+    #[test]
+    fn block() {
+        let e = parse::<LetStatement>("fn main() { let y = { let x = 1 f(x) } y }");
+        let Some(Expr::Block(block)) = e.value() else {
+            panic!()
+        };
+        assert_eq!(block.statements().count(), 2);
+        block.tail_expr().unwrap().syntax().should_eq("f(x)");
+
+        let e = parse::<Block>("fn main() { let x = 1 }");
+        assert!(e.tail_expr().is_none());
+    }
+
+    #[test]
+    fn block_recovery() {
+        let parse = crate::parse_file("fn a() { let x = \"abc\n  case x {\npub fn b() { 1 }");
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                ErrorKind::UnterminatedString,
+                ErrorKind::ExpectToken(T!["}"]),
+                ErrorKind::ExpectToken(T!["}"]),
+            ]
+        );
+        let mut iter = parse.syntax_node().descendants().filter_map(Function::cast);
+        iter.next().unwrap().name().unwrap().syntax().should_eq("a");
+        let b = iter.next().unwrap();
+        assert!(b.is_public());
+        b.body().unwrap().syntax().should_eq("{ 1 }");
+    }
+
+    #[test]
+    fn incomplete_items() {
+        let src = "pub fn foo(\nimport gleam/\nconst a =\ntype Foo(\npub fn bar(a: ";
+        let parse = crate::parse_file(src);
+        let mut iter = parse
+            .root()
+            .statements()
+            .flat_map(|group| group.statements());
+        let Some(Statement::Function(foo)) = iter.next() else {
+            panic!()
+        };
+        foo.name().unwrap().syntax().should_eq("foo");
+        assert!(foo.param_list().is_some());
+        assert!(matches!(iter.next(), Some(Statement::Import(_))));
+        let Some(Statement::ModuleConstant(a)) = iter.next() else {
+            panic!()
+        };
+        a.name().unwrap().syntax().should_eq("a");
+        assert!(matches!(iter.next(), Some(Statement::CustomType(_))));
+        let Some(Statement::Function(bar)) = iter.next() else {
+            panic!()
+        };
+        let param = bar.param_list().unwrap().params().next().unwrap();
+        param.name().unwrap().syntax().should_eq("a");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn incomplete_fn_type() {
+        for src in ["const a: fn(", "const a: fn(Int"] {
+            let parse = crate::parse_file(src);
+            assert!(!parse.errors().is_empty());
+            let fn_type = parse
+                .syntax_node()
+                .descendants()
+                .find_map(FnType::cast)
+                .unwrap();
+            assert!(fn_type.param_list().is_some());
+        }
+
+        let parse = crate::parse_file("fn f(x: fn(\npub fn g() { 1 }");
+        let names = parse
+            .root()
+            .functions()
+            .map(|it| it.name().unwrap().syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["f", "g"]);
+    }
+
+    #[test]
+    fn lambda() {
+        let parse = crate::parse_file("fn f(xs) { list.map(xs, fn(x: Int) -> Int { x + 1 }) }");
+        assert_eq!(parse.errors(), []);
+        let lambda = parse
+            .syntax_node()
+            .descendants()
+            .find_map(Lambda::cast)
+            .unwrap();
+        assert_eq!(lambda.param_list().unwrap().params().count(), 1);
+        lambda
+            .return_annotation()
+            .unwrap()
+            .syntax()
+            .should_eq("Int");
+        lambda.body().unwrap().syntax().should_eq("{ x + 1 }");
+
+        // A function item after a missing delimiter is not an anonymous function.
+        for src in [
+            "fn f() { let x = 1\nfn g() { 1 }",
+            "fn f() { g(1,\nfn g() { 1 }",
+            "fn f() { echo\nfn g() { 1 }",
+        ] {
+            let parse = crate::parse_file(src);
+            assert!(!parse.errors().is_empty());
+            let names = parse
+                .root()
+                .functions()
+                .map(|it| it.name().unwrap().syntax().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["f", "g"], "{src}");
+        }
+    }
+
+    #[test]
+    fn reserved_word() {
+        let parse = crate::parse_file("fn test(auto) { let else = 1 }");
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(errors, [ErrorKind::ReservedWord; 3]);
+        let e = parse
+            .root()
+            .syntax()
+            .descendants()
+            .find_map(Function::cast)
+            .unwrap();
+        e.name().unwrap().syntax().should_eq("test");
+    }
+
+    #[test]
+    fn soft_keywords() {
+        let e = parse::<ImportModule>("import a/b as c");
+        e.as_name().unwrap().syntax().should_eq("c");
+        let e = parse::<TodoExpr>("fn main() { todo as \"later\" }");
+        e.message().unwrap().syntax().should_eq("\"later\"");
+
+        let parse = crate::parse_file("fn as(erlang) { let as = erlang\n as }");
+        assert!(parse.errors().is_empty());
+        let e = parse
+            .root()
+            .syntax()
+            .descendants()
+            .find_map(Function::cast)
+            .unwrap();
+        e.name().unwrap().syntax().should_eq("as");
+        assert!(!parse
+            .syntax_node()
+            .descendants_with_tokens()
+            .any(|it| it.kind() == T!["as"]));
+    }
+
+    #[test]
+    fn echo() {
+        let e = parse::<EchoExpr>("fn main() { echo f(1) + 2 }");
+        e.expression().unwrap().syntax().should_eq("f(1) + 2");
+        let e = parse::<EchoExpr>("fn main() { x |> echo }");
+        assert!(e.expression().is_none());
+
+        let parse =
+            crate::parse_file_with_version("fn main() { echo 1 }", LanguageVersion::new(1, 8));
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [ErrorKind::UnsupportedSyntax(LanguageVersion::ECHO)]
+        );
+        assert!(parse
+            .syntax_node()
+            .descendants()
+            .any(|it| it.kind() == ECHO_EXPR));
+    }
+
+    #[test]
+    fn guard() {
+        let e = parse::<CaseClause>("fn f() { case x { y if Ok(y.0) -> 1 } }");
+        e.guard().unwrap().syntax().should_eq("Ok(y.0)");
+
+        let errors = crate::parse_file("fn f() { case x { y if g(y) -> 1 y if { y } -> 2 } }")
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                ErrorKind::InvalidGuardExpression,
+                ErrorKind::InvalidGuardExpression
+            ]
+        );
+    }
+
+    #[test]
+    fn alternative_pattern() {
+        let e = parse::<CaseClause>("fn f() { case x { 1 | 2 | _ -> 1 } }");
+        let mut iter = e.patterns();
+        let Some(Pattern::AlternativePattern(alt)) = iter.next() else {
+            panic!()
+        };
+        assert!(iter.next().is_none());
+        let pats = alt
+            .patterns()
+            .map(|it| it.syntax().to_string().trim().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(pats, ["1", "2", "_"]);
+
+        let e = parse::<AlternativePattern>("fn f() { case x, y { 1, a | a, 1 -> a } }");
+        let alts = e
+            .alternatives()
+            .into_iter()
+            .map(|pats| pats.len())
+            .collect::<Vec<_>>();
+        assert_eq!(alts, [2, 2]);
+    }
+
+    #[test]
+    fn binary_precedence() {
+        #[track_caller]
+        fn check(src: &str, expect: &str) {
+            fn show(e: &Expr) -> String {
+                match e {
+                    Expr::BinaryExpr(e) => format!(
+                        "({} {} {})",
+                        show(&e.lhs().unwrap()),
+                        e.op_token().unwrap(),
+                        show(&e.rhs().unwrap()),
+                    ),
+                    e => e.syntax().to_string().trim().to_owned(),
+                }
+            }
+            let e = parse::<Block>(&format!("fn main() {{ {src} }}"));
+            assert_eq!(show(&e.tail_expr().unwrap()), expect);
+        }
+
+        check("a || b && c == d", "(a || (b && (c == d)))");
+        check("a < b <> c", "(a < (b <> c))");
+        check("a <> b |> f", "(a <> (b |> f))");
+        check("a |> f(1) + 2 * 3", "(a |> (f(1) + (2 * 3)))");
+        check("1 - 2 - 3", "((1 - 2) - 3)");
+        check("1. /. 2. % 3", "((1. /. 2.) % 3)");
+        check("x >=. 1.0 != y <=. 2.0", "((x >=. 1.0) != (y <=. 2.0))");
+    }
+
+    #[test]
+    fn binary_op_kind() {
+        let e = parse::<BinaryExpr>("fn main() { \"a\" <> \"b\" }");
+        assert_eq!(e.op_kind(), Some(BinaryOpKind::Concat));
+        e.lhs().unwrap().syntax().should_eq("\"a\"");
+        e.rhs().unwrap().syntax().should_eq("\"b\"");
+        let e = parse::<BinaryExpr>("fn main() { 1.0 *. 2.0 }");
+        assert_eq!(e.op_kind(), Some(BinaryOpKind::MulFloat));
+    }
+
+    #[test]
+    fn attributes() {
+        let e = parse::<Function>(
+            "@external(erlang, \"lists\", \"reverse\")\n@deprecated(\"Use other\")\npub fn reverse(x: List(a)) -> List(a)",
+        );
+        assert!(e.is_public());
+        assert!(e.body().is_none());
+        let mut iter = e.attributes();
+        let external = iter.next().unwrap();
+        external.name().unwrap().syntax().should_eq("external");
+        let args = external
+            .arguments()
+            .map(|it| it.syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(args, ["erlang", "\"lists\"", "\"reverse\""]);
+        iter.next()
+            .unwrap()
+            .name()
+            .unwrap()
+            .syntax()
+            .should_eq("deprecated");
+        assert!(iter.next().is_none());
+
+        let e = parse::<CustomType>("@internal\ntype A { A }");
+        let attr = e.attributes().next().unwrap();
+        attr.name().unwrap().syntax().should_eq("internal");
+        assert!(attr.arguments().next().is_none());
+
+        let e = parse::<TargetGroup>("if erlang { @target(erlang) const a = 1 }");
+        let Some(Statement::ModuleConstant(c)) = e.statements().next() else {
+            panic!()
+        };
+        c.attributes()
+            .next()
+            .unwrap()
+            .syntax()
+            .should_eq("@target(erlang)");
+    }
+
+    #[test]
+    fn source_file_items() {
+        let src = "import a\nif erlang { fn f() {} }\nconst c = 1\ntype T { T }\ntype A = T\npub fn g() {}";
+        let root = crate::parse_file(src).root();
+        assert_eq!(root.items().count(), 6);
+        assert_eq!(root.imports().count(), 1);
+        let functions = root
+            .functions()
+            .map(|it| it.name().unwrap().syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(functions, ["f", "g"]);
+        assert_eq!(root.constants().count(), 1);
+        let types = root
+            .type_definitions()
+            .map(|it| it.name().unwrap().syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(types, ["T", "A"]);
+        let items = root
+            .module_items()
+            .map(|it| it.name().map(|name| name.syntax().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(items, [None, Some("T".into()), Some("A".into())]);
+
+        let offset = TextSize::from(src.find("c =").unwrap() as u32);
+        let item = root.item_at_offset(offset).unwrap();
+        assert!(matches!(item, Statement::ModuleConstant(_)));
+        assert!(root
+            .item_at_offset(TextSize::from(src.len() as u32))
+            .is_some());
+    }
+
+    #[test]
+    fn item_traits() {
+        let src = "@internal\nimport a/b as c\npub fn f() {}\n@deprecated(\"no\")\nconst x = 1\npub opaque type T { T }\ntype A = Int";
+        let items = crate::parse_file(src)
+            .root()
+            .statements()
+            .flat_map(|group| group.statements())
+            .map(|it| {
+                (
+                    it.name().map(|name| name.syntax().to_string()),
+                    it.is_public(),
+                    it.attributes().count(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [
+                (Some("c".into()), false, 1),
+                (Some("f".into()), true, 0),
+                (Some("x".into()), false, 1),
+                (Some("T".into()), true, 0),
+                (Some("A".into()), false, 0),
+            ]
+        );
+
+        let e = parse::<Import>("import a/b");
+        assert!(e.name().is_none());
+    }
+
+    #[test]
+    fn doc_comments() {
+        let src = "//// Module.\n//// Docs.\n\nimport a\n/// The answer.\n///To everything.\n@deprecated(\"no\")\npub const answer = 42\n// Not a doc.\nfn f() {}\n/// Foo.\ntype Foo { Foo }";
+        let e = parse::<SourceFile>(src);
+        assert_eq!(e.module_doc().unwrap(), "Module.\nDocs.");
+        assert_eq!(e.doc_comment_text(), e.module_doc());
+
+        let e = parse::<ModuleConstant>(src);
+        assert_eq!(e.doc_comment_text().unwrap(), "The answer.\nTo everything.");
+        let e = parse::<Function>(src);
+        assert_eq!(e.doc_comment_text(), None);
+        let e = parse::<CustomType>(src);
+        assert_eq!(e.doc_comment_text().unwrap(), "Foo.");
+    }
+
+    #[test]
+    fn external() {
+        let e = parse::<ExternalFunction>(
+            "pub external fn now(unit: Atom, Int) -> Int = \"os\" \"system_time\"",
+        );
+        assert!(e.is_public());
+        e.name().unwrap().syntax().should_eq("now");
+        let mut iter = e.param_list().unwrap().params();
+        let unit = iter.next().unwrap();
+        unit.label().unwrap().syntax().should_eq("unit");
+        assert!(unit.name().is_none());
+        unit.ty().unwrap().syntax().should_eq("Atom");
+        iter.next().unwrap().ty().unwrap().syntax().should_eq("Int");
+        assert!(iter.next().is_none());
+        e.return_annotation().unwrap().syntax().should_eq("Int");
+        e.module().unwrap().should_eq("\"os\"");
+        e.function().unwrap().should_eq("\"system_time\"");
+
+        let e = parse::<ExternalType>("external type Dict(k, v)");
+        assert!(!e.is_public());
+        e.name().unwrap().syntax().should_eq("Dict");
+        assert_eq!(e.type_params().count(), 2);
+    }
+
+    #[test]
+    fn integer_radix() {
+        let radix = |src: &str| parse::<Literal>(src).radix();
+        assert_eq!(radix("const a = 0xFF"), Some(16));
+        assert_eq!(radix("const a = 0o77"), Some(8));
+        assert_eq!(radix("const a = 0b1010"), Some(2));
+        assert_eq!(radix("const a = 1_000_000"), Some(10));
+        assert_eq!(radix("const a = 1.0"), None);
+
+        let errors = crate::parse_file("const a = 0b102\nconst b = 0x\nconst c = 0o7_7")
+            .errors()
+            .to_vec();
+        assert_eq!(
+            errors.iter().map(|err| err.kind).collect::<Vec<_>>(),
+            [ErrorKind::InvalidDigit, ErrorKind::InvalidDigit]
+        );
+        assert_eq!(errors[0].range, TextRange::new(10.into(), 15.into()));
+    }
+
+    #[test]
+    fn string_value() {
+        let value = |src: &str| parse::<Literal>(src).string_value();
+        assert_eq!(value(r#"const a = "a\tb""#).as_deref(), Some("a\tb"));
+        assert_eq!(value(r#"const a = "\u{1F600}""#).as_deref(), Some("😀"));
+        assert_eq!(value("const a = 1"), None);
+
+        let parse = crate::parse_file(r#"const a = "a\qb\u{FFFFFF}""#);
+        assert_eq!(
+            parse
+                .errors()
+                .iter()
+                .map(|err| (err.kind, err.range))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    ErrorKind::InvalidEscape,
+                    TextRange::new(12.into(), 14.into())
+                ),
+                (
+                    ErrorKind::InvalidEscape,
+                    TextRange::new(15.into(), 25.into())
+                ),
+            ]
+        );
+        let lit = parse
+            .syntax_node()
+            .descendants()
+            .find_map(Literal::cast)
+            .unwrap();
+        assert_eq!(lit.string_value(), None);
+    }
+
+    #[test]
+    fn float_value() {
+        let value = |src: &str| parse::<Literal>(src).float_value();
+        assert_eq!(value("const a = 1.5e3"), Some(1500.0));
+        assert_eq!(value("const a = 1.0e-3"), Some(0.001));
+        assert_eq!(value("const a = 1_000.5"), Some(1000.5));
+        assert_eq!(value("const a = 2."), Some(2.0));
+        assert_eq!(value("const a = 2"), None);
+
+        let parse = crate::parse_file("const a = 1.0e999");
+        assert_eq!(
+            parse
+                .errors()
+                .iter()
+                .map(|err| err.kind)
+                .collect::<Vec<_>>(),
+            [ErrorKind::FloatOverflow]
+        );
+        let lit = parse
+            .syntax_node()
+            .descendants()
+            .find_map(Literal::cast)
+            .unwrap();
+        assert_eq!(lit.float_value(), None);
+    }
+
+    #[test]
+    fn type_params_in_signature() {
+        let e = parse::<Function>(
+            "pub fn map(list: List(a), f: fn(a) -> b) -> List(b) { let x: c = todo }",
+        );
+        let params = e
+            .type_params_in_signature()
+            .into_iter()
+            .map(|var| var.syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(params, ["a", "b"]);
+    }
+
+    #[test]
+    fn operator_table() {
+        for op in [
+            BinaryOpKind::Or,
+            BinaryOpKind::LessEqualFloat,
+            BinaryOpKind::Pipe,
+            BinaryOpKind::Rem,
+        ] {
+            assert_eq!(BinaryOpKind::from_token(op.token()), Some(op));
+        }
+        assert_eq!(BinaryOpKind::from_token(T!["!"]), None);
+        assert_eq!(UnaryOpKind::from_token(T!["-"]), Some(UnaryOpKind::Negate));
+
+        assert!(BinaryOpKind::Mul.precedence() > BinaryOpKind::Add.precedence());
+        assert!(UnaryOpKind::Not.precedence() > BinaryOpKind::DivFloat.precedence());
+        // (a + b) * c
+        assert!(BinaryOpKind::Mul.needs_parens(BinaryOpKind::Add, false));
+        // a * b + c
+        assert!(!BinaryOpKind::Add.needs_parens(BinaryOpKind::Mul, false));
+        // (a - b) - c and a - (b - c)
+        assert!(!BinaryOpKind::Sub.needs_parens(BinaryOpKind::Add, false));
+        assert!(BinaryOpKind::Sub.needs_parens(BinaryOpKind::Add, true));
+    }
+
+    #[test]
+    fn unary() {
+        let e = parse::<UnaryExpr>("const a = -1");
+        assert_eq!(e.op_kind(), Some(UnaryOpKind::Negate));
+        e.arg().unwrap().syntax().should_eq("1");
+        let e = parse::<ModuleConstant>("const a = [-1.5, 2]");
+        let Some(ConstantValue::List(list)) = e.value() else {
+            panic!()
+        };
+        assert!(matches!(list.elements().next(), Some(Expr::UnaryExpr(_))));
+
+        let e = parse::<BinaryExpr>("fn main() { !a || -f(x).0 * 2 }");
+        assert_eq!(e.op_kind(), Some(BinaryOpKind::Or));
+        let Some(Expr::UnaryExpr(not)) = e.lhs() else {
+            panic!()
+        };
+        assert_eq!(not.op_kind(), Some(UnaryOpKind::Not));
+        let Some(Expr::BinaryExpr(mul)) = e.rhs() else {
+            panic!()
+        };
+        let Some(Expr::UnaryExpr(neg)) = mul.lhs() else {
+            panic!()
+        };
+        neg.arg().unwrap().syntax().should_eq("f(x).0");
+    }
+
+    #[test]
+    fn case() {
+        let e = parse::<CaseExpr>("fn main() { case x, f(1) { a, _ if a -> a 1, \"b\" -> 2 } }");
+        let mut iter = e.subjects();
+        iter.next().unwrap().syntax().should_eq("x");
+        iter.next().unwrap().syntax().should_eq("f(1)");
+        assert!(iter.next().is_none());
+
+        let mut clauses = e.clauses();
+        let first = clauses.next().unwrap();
+        let mut pats = first.patterns();
+        assert!(matches!(pats.next(), Some(Pattern::VariablePattern(_))));
+        assert!(matches!(pats.next(), Some(Pattern::DiscardPattern(_))));
+        assert!(pats.next().is_none());
+        first.guard().unwrap().syntax().should_eq("a");
+        first.body().unwrap().syntax().should_eq("a");
+
+        let second = clauses.next().unwrap();
+        let kinds = second
+            .patterns()
+            .map(|pat| match pat {
+                Pattern::LiteralPattern(lit) => lit.literal().unwrap().kind(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, [Some(LiteralKind::Int), Some(LiteralKind::String)]);
+        assert!(second.guard().is_none());
+        second.body().unwrap().syntax().should_eq("2");
+        assert!(clauses.next().is_none());
+    }
+
+    #[test]
+    fn patterns() {
+        let e =
+            parse::<TuplePattern>("fn f() { case x { #(a, [1, _, ..rest], \"a\" <> b) -> 1 } }");
+        let mut iter = e.elements();
+        assert!(matches!(iter.next(), Some(Pattern::VariablePattern(_))));
+        let Some(Pattern::ListPattern(list)) = iter.next() else {
+            panic!()
+        };
+        assert_eq!(list.elements().count(), 2);
+        list.rest()
+            .unwrap()
+            .pattern()
+            .unwrap()
+            .syntax()
+            .should_eq("rest");
+        let Some(Pattern::ConcatPattern(concat)) = iter.next() else {
+            panic!()
+        };
+        concat.prefix().unwrap().syntax().should_eq("\"a\"");
+        concat.rest_name().unwrap().syntax().should_eq("b");
+        assert!(concat.prefix_name().is_none());
+        assert!(iter.next().is_none());
+
+        let e = parse::<ConcatPattern>("fn f() { case x { \"/\" as slash <> _ -> 1 } }");
+        e.prefix().unwrap().syntax().should_eq("\"/\"");
+        e.prefix_name().unwrap().syntax().should_eq("slash");
+        e.rest().unwrap().syntax().should_eq("_");
+        assert!(e.rest_name().is_none());
+
+        let file = crate::parse_file("fn f() { case x { \"a\" <> \"b\" -> 1 } }");
+        assert_eq!(file.errors()[0].kind, ErrorKind::ExpectedIdentifier);
+
+        let e = parse::<ListPattern>("fn f() { case x { [..] -> 1 } }");
+        assert_eq!(e.elements().count(), 0);
+        assert!(e.rest().unwrap().pattern().is_none());
+    }
+
+    #[test]
+    fn constructor_pattern() {
+        let e =
+            parse::<ConstructorPattern>("fn f() { case x { Person(name: n, Ok(1), ..) -> n } }");
+        e.constructor().unwrap().syntax().should_eq("Person");
+        assert!(e.has_spread());
+        let mut iter = e.args();
+        let name = iter.next().unwrap();
+        name.label().unwrap().syntax().should_eq("name");
+        name.pattern().unwrap().syntax().should_eq("n");
+        let ok = iter.next().unwrap();
+        assert!(ok.label().is_none());
+        ok.pattern().unwrap().syntax().should_eq("Ok(1)");
+        assert!(iter.next().is_none());
+        assert_eq!(e.positional_args().count(), 1);
+        let mut iter = e.labelled_args();
+        let label = iter.next().unwrap().label().unwrap();
+        label.syntax().should_eq("name");
+        assert!(iter.next().is_none());
+
+        let e = parse::<ConstructorPattern>("fn f() { case x { Nil -> 1 } }");
+        assert_eq!(e.args().count(), 0);
+        assert!(!e.has_spread());
+    }
+
+    #[test]
+    fn bit_array() {
+        let e = parse::<BitArray>("fn f() { <<1:size(8), x:8-little, \"a\":utf8>> }");
+        let mut iter = e.segments();
+        let seg = iter.next().unwrap();
+        seg.value().unwrap().syntax().should_eq("1");
+        let opt = seg.options().next().unwrap();
+        assert_eq!(opt.kind(), Some(BitArrayOptionKind::Size));
+        opt.value().unwrap().syntax().should_eq("8");
+        let kinds = iter
+            .next()
+            .unwrap()
+            .options()
+            .map(|opt| opt.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                Some(BitArrayOptionKind::Size),
+                Some(BitArrayOptionKind::Little)
+            ]
+        );
+        let seg = iter.next().unwrap();
+        assert_eq!(
+            seg.options().next().unwrap().kind(),
+            Some(BitArrayOptionKind::Utf8)
+        );
+        assert!(iter.next().is_none());
+
+        let e = parse::<BitArray>("fn f() { case x { <<len:int, rest:bits>> -> 1 } }");
+        let mut iter = e.segments();
+        let seg = iter.next().unwrap();
+        assert!(matches!(seg.pattern(), Some(Pattern::VariablePattern(_))));
+        assert!(seg.value().is_none());
+        let seg = iter.next().unwrap();
+        assert_eq!(
+            seg.options().next().unwrap().kind(),
+            Some(BitArrayOptionKind::Bits)
+        );
+    }
+
+    #[test]
+    fn custom_type() {
+        let e = parse::<CustomType>("pub type Foo(a, b) { Bar(x: Int, a) Baz }");
+        assert!(e.is_public());
+        e.name().unwrap().syntax().should_eq("Foo");
+        let params = e
+            .type_params()
+            .map(|it| it.syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(params, ["a", "b"]);
+
+        let mut iter = e.constructors();
+        let bar = iter.next().unwrap();
+        bar.name().unwrap().syntax().should_eq("Bar");
+        let mut fields = bar.fields();
+        let x = fields.next().unwrap();
+        x.label().unwrap().syntax().should_eq("x");
+        x.ty().unwrap().syntax().should_eq("Int");
+        let a = fields.next().unwrap();
+        assert!(a.label().is_none());
+        a.ty().unwrap().syntax().should_eq("a");
+        assert!(fields.next().is_none());
+
+        let baz = iter.next().unwrap();
+        baz.name().unwrap().syntax().should_eq("Baz");
+        assert!(baz.fields().next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn opaque_type() {
+        let e = parse::<CustomType>("pub opaque type Counter { Counter(Int) }");
+        assert!(e.is_public());
+        assert!(e.is_opaque());
+        e.name().unwrap().syntax().should_eq("Counter");
+        assert_eq!(e.constructors().count(), 1);
+
+        let e = parse::<CustomType>("type Counter { Counter(Int) }");
+        assert!(!e.is_opaque());
+    }
+
+    #[test]
+    fn type_alias() {
+        let e = parse::<TypeAlias>("pub type Headers = List(#(String, String))");
+        assert!(e.is_public());
+        e.name().unwrap().syntax().should_eq("Headers");
+        assert!(e.type_params().next().is_none());
+        e.aliased_type()
+            .unwrap()
+            .syntax()
+            .should_eq("List(#(String, String))");
+
+        let e = parse::<TypeAlias>("type Pair(a) = #(a, a)");
+        assert!(!e.is_public());
+        assert_eq!(e.type_params().count(), 1);
+    }
+
+    #[test]
+    fn type_args() {
+        let e = parse::<ConstructorType>("const a: dict.Dict(String, List(Int)) = 1");
+        e.module().unwrap().syntax().should_eq("dict");
+        e.constructor().unwrap().syntax().should_eq("Dict");
+        let mut iter = e.arg_list().unwrap().args();
+        iter.next().unwrap().syntax().should_eq("String");
+        iter.next().unwrap().syntax().should_eq("List(Int)");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn const_list() {
+        let e = parse::<List>("const a = [1, 2]");
+        assert_eq!(e.elements().count(), 2);
+    }
+
     #[test]
     fn const_tuple() {
         let e = parse::<Tuple>("const a = #(#(2,3),2)");
@@ -331,6 +1898,37 @@ mod tests {
         assert!(iter.next().is_none())
     }
 
+    #[test]
+    fn const_expressions() {
+        let value = |src: &str| parse::<ModuleConstant>(src).value().unwrap();
+        let ConstantValue::CallExpr(record) = value("const a = Person(name: \"a\", age: b)") else {
+            panic!()
+        };
+        record.callee().unwrap().syntax().should_eq("Person");
+        assert_eq!(record.arg_list().unwrap().args().count(), 2);
+        assert!(matches!(
+            value("const a = option.None"),
+            ConstantValue::QualifiedRef(_)
+        ));
+        assert!(matches!(value("const a = main"), ConstantValue::NameRef(_)));
+        assert!(matches!(
+            value("const a = <<1:8>>"),
+            ConstantValue::BitArray(_)
+        ));
+        let ConstantValue::BinaryExpr(concat) = value("const a = \"a\" <> b <> \"c\"") else {
+            panic!()
+        };
+        concat.lhs().unwrap().syntax().should_eq("\"a\" <> b");
+
+        let errors =
+            crate::parse_file("const a = f(1)\nconst b = 1 + 2\nconst c = case x { _ -> 1 }")
+                .errors()
+                .iter()
+                .map(|err| err.kind)
+                .collect::<Vec<_>>();
+        assert_eq!(errors, [ErrorKind::NonConstantExpression; 3]);
+    }
+
     #[test]
     fn module() {
         let e =
@@ -401,6 +1999,7 @@ mod tests {
         let snd = iter.next().unwrap();
 
         fst.as_name().unwrap().syntax().should_eq("a");
+        fst.as_name().unwrap().token().unwrap().should_eq("a");
         fst.name().unwrap().syntax().should_eq("m");
         snd.as_name().unwrap().syntax().should_eq("A");
         snd.name().unwrap().syntax().should_eq("M");
@@ -412,6 +2011,7 @@ mod tests {
         let e = parse::<ImportModule>("import aa/a.{m as a, M as A} as e");
 
         e.as_name().unwrap().syntax().should_eq("e");
+        e.as_name().unwrap().token().unwrap().should_eq("e");
     }
     //     let mut iter = e.bindings();
     //     iter.next().unwrap().syntax().should_eq("a = let { };");
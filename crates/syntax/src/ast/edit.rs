@@ -0,0 +1,505 @@
+//! Editing of syntax trees for refactorings.
+//!
+//! Trees are immutable, so a `SyntaxEditor` records the changes to the original tree and
+//! turns them into `TextEdit`s, adding the whitespace and commas needed for the result to
+//! still be valid code.
+use std::fmt;
+use std::iter::successors;
+
+use crate::ast::{AstNode, ImportModule};
+use crate::{NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextEdit};
+use crate::{TextRange, TextSize};
+
+/// A position between two elements of a node.
+#[derive(Debug, Clone)]
+pub struct Position {
+    repr: PositionRepr,
+}
+
+#[derive(Debug, Clone)]
+enum PositionRepr {
+    FirstChild(SyntaxNode),
+    After(SyntaxElement),
+}
+
+impl Position {
+    pub fn before(elem: impl Into<SyntaxElement>) -> Self {
+        let elem = elem.into();
+        let repr = match elem.prev_sibling_or_token() {
+            Some(prev) => PositionRepr::After(prev),
+            None => PositionRepr::FirstChild(elem.parent().expect("the root has no siblings")),
+        };
+        Self { repr }
+    }
+
+    pub fn after(elem: impl Into<SyntaxElement>) -> Self {
+        Self {
+            repr: PositionRepr::After(elem.into()),
+        }
+    }
+
+    pub fn first_child_of(node: &SyntaxNode) -> Self {
+        Self {
+            repr: PositionRepr::FirstChild(node.clone()),
+        }
+    }
+
+    pub fn last_child_of(node: &SyntaxNode) -> Self {
+        match node.last_child_or_token() {
+            Some(last) => Self::after(last),
+            None => Self::first_child_of(node),
+        }
+    }
+
+    fn parent(&self) -> SyntaxNode {
+        match &self.repr {
+            PositionRepr::FirstChild(parent) => parent.clone(),
+            PositionRepr::After(elem) => elem.parent().expect("the root has no siblings"),
+        }
+    }
+
+    fn prev(&self) -> Option<SyntaxElement> {
+        match &self.repr {
+            PositionRepr::FirstChild(_) => None,
+            PositionRepr::After(elem) => Some(elem.clone()),
+        }
+    }
+
+    fn next(&self) -> Option<SyntaxElement> {
+        match &self.repr {
+            PositionRepr::FirstChild(parent) => parent.first_child_or_token(),
+            PositionRepr::After(elem) => elem.next_sibling_or_token(),
+        }
+    }
+
+    fn offset(&self) -> TextSize {
+        match &self.repr {
+            PositionRepr::FirstChild(parent) => parent.text_range().start(),
+            PositionRepr::After(elem) => elem.text_range().end(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Change {
+    Insert(TextSize, String),
+    Replace(SyntaxElement, String),
+    Delete(SyntaxElement),
+}
+
+/// Collects changes to a tree, all positions and elements refer to the original one.
+#[derive(Debug)]
+pub struct SyntaxEditor {
+    root: SyntaxNode,
+    changes: Vec<Change>,
+}
+
+impl SyntaxEditor {
+    pub fn new(root: SyntaxNode) -> Self {
+        Self {
+            root,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Insert `new` at `pos`, separated by commas from its neighbours in lists and by newlines
+    /// at the top level of modules.
+    pub fn insert(&mut self, pos: Position, new: impl fmt::Display) {
+        let offset = pos.offset();
+        let mut text = new.to_string();
+        let parent = pos.parent();
+        let prev = pos.prev();
+        let next = pos.next();
+        let prev_item = prev.clone().and_then(|it| {
+            if it.kind().is_whitespace() {
+                prev_non_trivia(&it)
+            } else {
+                Some(it)
+            }
+        });
+        let next_item = next.clone().and_then(|it| {
+            if it.kind().is_whitespace() {
+                next_non_trivia(&it)
+            } else {
+                Some(it)
+            }
+        });
+        // Only items between the delimiters of a list are separated by commas, not eg. the
+        // path of an import.
+        let in_list = is_list(parent.kind())
+            && (prev_item.as_ref().map_or(false, |it| is_opening(it.kind()))
+                || next_item.as_ref().map_or(false, |it| is_closing(it.kind())));
+        if in_list {
+            if prev_item.map_or(false, |it| !is_opening(it.kind())) {
+                text.insert_str(0, ", ");
+            } else if prev.map_or(false, |it| it.kind() == T![","]) {
+                text.insert(0, ' ');
+            }
+            if next_item.map_or(false, |it| !is_closing(it.kind())) {
+                text.push(',');
+                if next.map_or(true, |it| !it.kind().is_whitespace()) {
+                    text.push(' ');
+                }
+            }
+        } else if is_statement_list(parent.kind()) {
+            if self.char_before(offset).map_or(false, |c| c != '\n') {
+                text.insert(0, '\n');
+            }
+            if self.char_after(offset).map_or(false, |c| c != '\n') {
+                text.push('\n');
+            }
+        }
+        if self.char_before(offset).map_or(false, is_ident_char) && text.starts_with(is_ident_char)
+        {
+            text.insert(0, ' ');
+        }
+        if self.char_after(offset).map_or(false, is_ident_char) && text.ends_with(is_ident_char) {
+            text.push(' ');
+        }
+        self.changes.push(Change::Insert(offset, text));
+    }
+
+    /// Replace `old` with `new`, keeping the whitespace around `old`.
+    pub fn replace(&mut self, old: impl Into<SyntaxElement>, new: impl fmt::Display) {
+        self.changes
+            .push(Change::Replace(old.into(), new.to_string()));
+    }
+
+    /// Delete `elem`, along with its separating comma in lists and the whitespace around it.
+    pub fn delete(&mut self, elem: impl Into<SyntaxElement>) {
+        self.changes.push(Change::Delete(elem.into()));
+    }
+
+    /// The resulting edits, sorted by their range and not overlapping.
+    pub fn finish(self) -> Vec<TextEdit> {
+        let deleted = self
+            .changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Delete(elem) => Some(elem.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut edits = self
+            .changes
+            .into_iter()
+            .map(|change| match change {
+                Change::Insert(offset, text) => TextEdit {
+                    delete: TextRange::empty(offset),
+                    insert: text.into(),
+                },
+                Change::Replace(elem, text) => TextEdit {
+                    delete: trimmed_range(&elem),
+                    insert: text.into(),
+                },
+                Change::Delete(elem) => TextEdit {
+                    delete: removal_range(&elem, &deleted),
+                    insert: "".into(),
+                },
+            })
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|edit| (edit.delete.start(), edit.delete.end()));
+
+        let mut ret = Vec::<TextEdit>::with_capacity(edits.len());
+        for edit in edits {
+            match ret.last_mut() {
+                Some(last)
+                    if edit.delete.start() < last.delete.end()
+                        || (edit.delete.start() == last.delete.end()
+                            && (edit.delete.is_empty() || last.delete.is_empty())) =>
+                {
+                    last.delete = last.delete.cover(edit.delete);
+                    last.insert = format!("{}{}", last.insert, edit.insert).into();
+                }
+                _ => ret.push(edit),
+            }
+        }
+        ret
+    }
+
+    fn char_before(&self, offset: TextSize) -> Option<char> {
+        let tok = self.root.token_at_offset(offset).left_biased()?;
+        let end = usize::from(offset.checked_sub(tok.text_range().start())?);
+        tok.text()[..end].chars().next_back()
+    }
+
+    fn char_after(&self, offset: TextSize) -> Option<char> {
+        let tok = self.root.token_at_offset(offset).right_biased()?;
+        let start = usize::from(offset.checked_sub(tok.text_range().start())?);
+        tok.text()[start..].chars().next()
+    }
+}
+
+impl ImportModule {
+    /// Add `item` to the unqualified imports, eg. `import a.{x}` becomes `import a.{x, item}`.
+    pub fn add_unqualified(&self, editor: &mut SyntaxEditor, item: impl fmt::Display) {
+        let r_curly = self
+            .syntax()
+            .children_with_tokens()
+            .find(|it| it.kind() == T!["}"]);
+        match r_curly {
+            Some(r_curly) => editor.insert(Position::before(r_curly), item),
+            None => {
+                let pos = match self.module_path().last() {
+                    Some(path) => Position::after(path.syntax().clone()),
+                    None => Position::last_child_of(self.syntax()),
+                };
+                editor.insert(pos, format!(".{{{item}}}"));
+            }
+        }
+    }
+}
+
+/// Nodes whose children are separated by commas.
+fn is_list(kind: SyntaxKind) -> bool {
+    use SyntaxKind::*;
+    matches!(
+        kind,
+        ARG_LIST
+            | BIT_ARRAY
+            | CONSTRUCTOR
+            | CONSTRUCTOR_PATTERN
+            | IMPORT_MODULE
+            | LIST
+            | LIST_PATTERN
+            | PARAM_LIST
+            | TUPLE
+            | TUPLE_PATTERN
+            | TUPLE_TYPE
+            | TYPE_ARG_LIST
+    )
+}
+
+/// Nodes whose children are separated by newlines.
+fn is_statement_list(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::SOURCE_FILE | SyntaxKind::TARGET_GROUP)
+}
+
+fn is_opening(kind: SyntaxKind) -> bool {
+    matches!(kind, T!["("] | T!["["] | T!["{"] | T!["<<"] | T![","])
+}
+
+fn is_closing(kind: SyntaxKind) -> bool {
+    matches!(kind, T![")"] | T!["]"] | T!["}"] | T![">>"] | T![","])
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn prev_non_trivia(elem: &SyntaxElement) -> Option<SyntaxElement> {
+    successors(
+        elem.prev_sibling_or_token(),
+        SyntaxElement::prev_sibling_or_token,
+    )
+    .find(|it| !it.kind().is_whitespace())
+}
+
+fn next_non_trivia(elem: &SyntaxElement) -> Option<SyntaxElement> {
+    successors(
+        elem.next_sibling_or_token(),
+        SyntaxElement::next_sibling_or_token,
+    )
+    .find(|it| !it.kind().is_whitespace())
+}
+
+/// Whether an item before `elem` in its list is not deleted.
+fn kept_before(elem: &SyntaxElement, deleted: &[SyntaxElement]) -> bool {
+    let mut elem = elem.clone();
+    loop {
+        let Some(comma) = prev_non_trivia(&elem).filter(|it| it.kind() == T![","]) else {
+            return false;
+        };
+        let Some(item) = prev_non_trivia(&comma) else {
+            return false;
+        };
+        if !deleted.contains(&item) {
+            return true;
+        }
+        elem = item;
+    }
+}
+
+fn non_trivia_tokens(elem: &SyntaxElement) -> impl Iterator<Item = SyntaxToken> {
+    let tokens: Box<dyn Iterator<Item = SyntaxToken>> = match elem {
+        NodeOrToken::Node(node) => Box::new(
+            node.descendants_with_tokens()
+                .filter_map(NodeOrToken::into_token),
+        ),
+        NodeOrToken::Token(tok) => Box::new(std::iter::once(tok.clone())),
+    };
+    tokens.filter(|tok| !tok.kind().is_whitespace())
+}
+
+/// Nodes can start or end with whitespace and comments, which are left alone.
+fn trimmed_range(elem: &SyntaxElement) -> TextRange {
+    let mut tokens = non_trivia_tokens(elem);
+    let first = tokens
+        .next()
+        .map_or(elem.text_range(), |tok| tok.text_range());
+    let last = tokens.last().map_or(first, |tok| tok.text_range());
+    first.cover(last)
+}
+
+fn removal_range(elem: &SyntaxElement, deleted: &[SyntaxElement]) -> TextRange {
+    let range = trimmed_range(elem);
+    let parent = match elem.parent() {
+        Some(parent) => parent,
+        None => return range,
+    };
+    if is_list(parent.kind()) && elem.as_node().is_some() {
+        // Remove the comma before the element if an item before it is kept, so that deleting
+        // every item leaves no commas behind.
+        let prev = prev_non_trivia(elem);
+        return match prev {
+            Some(comma) if comma.kind() == T![","] && kept_before(elem, deleted) => {
+                range.cover(comma.text_range())
+            }
+            _ => match next_non_trivia(elem) {
+                Some(comma) if comma.kind() == T![","] => {
+                    let end = next_non_trivia(&comma)
+                        .filter(|it| !is_closing(it.kind()))
+                        .map_or(comma.text_range().end(), |it| it.text_range().start());
+                    TextRange::new(range.start(), end)
+                }
+                _ => range,
+            },
+        };
+    }
+    if is_statement_list(parent.kind()) {
+        // Also remove the whitespace separating it from its neighbours, so no empty line is
+        // left behind.
+        let ws_run = |tok: Option<SyntaxToken>, step: fn(&SyntaxToken) -> Option<SyntaxToken>| {
+            successors(tok, step)
+                .take_while(|tok| tok.kind() == SyntaxKind::WHITESPACE)
+                .map(|tok| tok.text_range())
+                .reduce(TextRange::cover)
+        };
+        let mut tokens = non_trivia_tokens(elem);
+        let first = tokens.next();
+        let last = tokens.last().or_else(|| first.clone());
+        let before = ws_run(
+            first.and_then(|tok| tok.prev_token()),
+            SyntaxToken::prev_token,
+        );
+        let after = ws_run(
+            last.and_then(|tok| tok.next_token()),
+            SyntaxToken::next_token,
+        );
+        return match before.or(after) {
+            Some(ws) => range.cover(ws),
+            None => range,
+        };
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, SyntaxEditor};
+    use crate::ast::{self, AstNode};
+    use crate::parse_file;
+
+    #[track_caller]
+    fn check(src: &str, f: impl FnOnce(&ast::SourceFile, &mut SyntaxEditor), expect: &str) {
+        let root = parse_file(src).root();
+        let mut editor = SyntaxEditor::new(root.syntax().clone());
+        f(&root, &mut editor);
+        let mut text = src.to_owned();
+        for edit in editor.finish().iter().rev() {
+            edit.apply(&mut text);
+        }
+        assert_eq!(text, expect);
+    }
+
+    fn find<N: AstNode<Language = crate::GleamLanguage>>(root: &ast::SourceFile, text: &str) -> N {
+        root.syntax()
+            .descendants()
+            .filter_map(N::cast)
+            .find(|it| it.syntax().to_string().trim() == text)
+            .unwrap()
+    }
+
+    #[test]
+    fn add_unqualified() {
+        let add = |item: &'static str| {
+            move |root: &ast::SourceFile, editor: &mut SyntaxEditor| {
+                let module = root
+                    .syntax()
+                    .descendants()
+                    .find_map(ast::ImportModule::cast);
+                module.unwrap().add_unqualified(editor, item);
+            }
+        };
+        check("import a.{x}", add("y"), "import a.{x, y}");
+        check("import a.{x,}", add("y"), "import a.{x, y}");
+        check("import a.{}", add("y"), "import a.{y}");
+        check("import a/b", add("y"), "import a/b.{y}");
+        check("import a/b as c\n", add("Y"), "import a/b.{Y} as c\n");
+    }
+
+    #[test]
+    fn insert() {
+        check(
+            "const a = #(1, 2)",
+            |root, editor| {
+                let one = find::<ast::Literal>(root, "1");
+                editor.insert(Position::before(one.syntax().clone()), "0");
+                let two = find::<ast::Literal>(root, "2");
+                editor.insert(Position::after(two.syntax().clone()), "3");
+            },
+            "const a = #(0, 1, 2, 3)",
+        );
+        check(
+            "import a\nfn f() { 1 }",
+            |root, editor| {
+                let import = find::<ast::Import>(root, "import a");
+                editor.insert(Position::after(import.syntax().clone()), "import b");
+                editor.insert(Position::last_child_of(root.syntax()), "const c = 1");
+            },
+            "import a\nimport b\nfn f() { 1 }\nconst c = 1",
+        );
+    }
+
+    #[test]
+    fn replace() {
+        check(
+            "import a\nconst b = 1\n",
+            |root, editor| {
+                let import = find::<ast::Import>(root, "import a");
+                editor.replace(import.syntax().clone(), "import c");
+            },
+            "import c\nconst b = 1\n",
+        );
+    }
+
+    #[test]
+    fn delete() {
+        let delete = |items: &'static [&'static str]| {
+            move |root: &ast::SourceFile, editor: &mut SyntaxEditor| {
+                for item in items {
+                    let item = find::<ast::UnqualifiedImport>(root, item);
+                    editor.delete(item.syntax().clone());
+                }
+            }
+        };
+        let src = "import a.{x, y, z}";
+        check(src, delete(&["x"]), "import a.{y, z}");
+        check(src, delete(&["z"]), "import a.{x, y}");
+        check(src, delete(&["x", "y"]), "import a.{z}");
+        check(src, delete(&["y", "z"]), "import a.{x}");
+        check(src, delete(&["x", "z"]), "import a.{y}");
+        check(src, delete(&["z", "x", "y"]), "import a.{}");
+        check("import a.{x, y,}", delete(&["y"]), "import a.{x,}");
+
+        check(
+            "import a\nimport b\nconst c = 1",
+            |root, editor| editor.delete(find::<ast::Import>(root, "import b").syntax().clone()),
+            "import a\nconst c = 1",
+        );
+    }
+}
@@ -0,0 +1,2379 @@
+//! Generated by `cargo xtask codegen` from `gleam.ungrammar`, do not edit by hand.
+
+use crate::ast::{AstChildren, AstNode, HasAttributes, HasName, HasVisibility, Stmt};
+use crate::SyntaxKind::{self, *};
+use crate::{GleamLanguage, SyntaxNode, SyntaxToken};
+use rowan::ast::support;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AlternativePattern(pub(crate) SyntaxNode);
+
+impl AlternativePattern {
+    pub fn patterns(&self) -> AstChildren<Pattern> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for AlternativePattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == ALTERNATIVE_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Arg(pub(crate) SyntaxNode);
+
+impl Arg {
+    pub fn label(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Arg {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == ARG
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArgList(pub(crate) SyntaxNode);
+
+impl ArgList {
+    pub fn record_spread(&self) -> Option<RecordSpread> {
+        support::child(&self.0)
+    }
+    pub fn args(&self) -> AstChildren<Arg> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for ArgList {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == ARG_LIST
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Attribute(pub(crate) SyntaxNode);
+
+impl Attribute {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn arg_list(&self) -> Option<ArgList> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Attribute {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == ATTRIBUTE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasName for Attribute {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BinaryExpr(pub(crate) SyntaxNode);
+
+impl BinaryExpr {
+    pub fn lhs(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+    pub fn rhs(&self) -> Option<Expr> {
+        support::children(&self.0).nth(1)
+    }
+}
+
+impl AstNode for BinaryExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == BINARY_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitArray(pub(crate) SyntaxNode);
+
+impl BitArray {
+    pub fn segments(&self) -> AstChildren<BitArraySegment> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for BitArray {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == BIT_ARRAY
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitArrayOption(pub(crate) SyntaxNode);
+
+impl BitArrayOption {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, IDENT)
+    }
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for BitArrayOption {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == BIT_ARRAY_OPTION
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitArraySegment(pub(crate) SyntaxNode);
+
+impl BitArraySegment {
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+    pub fn pattern(&self) -> Option<Pattern> {
+        support::child(&self.0)
+    }
+    pub fn options(&self) -> AstChildren<BitArrayOption> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for BitArraySegment {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == BIT_ARRAY_SEGMENT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Block(pub(crate) SyntaxNode);
+
+impl Block {
+    pub fn statements(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for Block {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == BLOCK
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CallExpr(pub(crate) SyntaxNode);
+
+impl CallExpr {
+    pub fn callee(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+    pub fn arg_list(&self) -> Option<ArgList> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for CallExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CALL_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CaptureHole(pub(crate) SyntaxNode);
+
+impl AstNode for CaptureHole {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CAPTURE_HOLE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CaseClause(pub(crate) SyntaxNode);
+
+impl CaseClause {
+    pub fn patterns(&self) -> AstChildren<Pattern> {
+        support::children(&self.0)
+    }
+    pub fn body(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for CaseClause {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CASE_CLAUSE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CaseExpr(pub(crate) SyntaxNode);
+
+impl CaseExpr {
+    pub fn subjects(&self) -> AstChildren<Expr> {
+        support::children(&self.0)
+    }
+    pub fn clauses(&self) -> AstChildren<CaseClause> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for CaseExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CASE_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CaseGuard(pub(crate) SyntaxNode);
+
+impl CaseGuard {
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for CaseGuard {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CASE_GUARD
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConcatPattern(pub(crate) SyntaxNode);
+
+impl ConcatPattern {
+    pub fn prefix_name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn rest(&self) -> Option<Pattern> {
+        support::children(&self.0).nth(1)
+    }
+}
+
+impl AstNode for ConcatPattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CONCAT_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstantValue {
+    Literal(Literal),
+    NameRef(NameRef),
+    QualifiedRef(QualifiedRef),
+    Tuple(Tuple),
+    List(List),
+    CallExpr(CallExpr),
+    BitArray(BitArray),
+    BinaryExpr(BinaryExpr),
+    UnaryExpr(UnaryExpr),
+}
+
+impl AstNode for ConstantValue {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            LITERAL
+                | NAME_REF
+                | QUALIFIED_REF
+                | TUPLE
+                | LIST
+                | CALL_EXPR
+                | BIT_ARRAY
+                | BINARY_EXPR
+                | UNARY_EXPR
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            LITERAL => Self::Literal(Literal(node)),
+            NAME_REF => Self::NameRef(NameRef(node)),
+            QUALIFIED_REF => Self::QualifiedRef(QualifiedRef(node)),
+            TUPLE => Self::Tuple(Tuple(node)),
+            LIST => Self::List(List(node)),
+            CALL_EXPR => Self::CallExpr(CallExpr(node)),
+            BIT_ARRAY => Self::BitArray(BitArray(node)),
+            BINARY_EXPR => Self::BinaryExpr(BinaryExpr(node)),
+            UNARY_EXPR => Self::UnaryExpr(UnaryExpr(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Literal(it) => &it.0,
+            Self::NameRef(it) => &it.0,
+            Self::QualifiedRef(it) => &it.0,
+            Self::Tuple(it) => &it.0,
+            Self::List(it) => &it.0,
+            Self::CallExpr(it) => &it.0,
+            Self::BitArray(it) => &it.0,
+            Self::BinaryExpr(it) => &it.0,
+            Self::UnaryExpr(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Constructor(pub(crate) SyntaxNode);
+
+impl Constructor {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn fields(&self) -> AstChildren<ConstructorField> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for Constructor {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CONSTRUCTOR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasName for Constructor {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstructorField(pub(crate) SyntaxNode);
+
+impl ConstructorField {
+    pub fn label(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn ty(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ConstructorField {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CONSTRUCTOR_FIELD
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstructorPattern(pub(crate) SyntaxNode);
+
+impl ConstructorPattern {
+    pub fn qualified_ref(&self) -> Option<QualifiedRef> {
+        support::child(&self.0)
+    }
+    pub fn args(&self) -> AstChildren<PatternArg> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for ConstructorPattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CONSTRUCTOR_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstructorType(pub(crate) SyntaxNode);
+
+impl ConstructorType {
+    pub fn module(&self) -> Option<ModuleName> {
+        support::child(&self.0)
+    }
+    pub fn constructor(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn arg_list(&self) -> Option<TypeArgList> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ConstructorType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CONSTRUCTOR_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomType(pub(crate) SyntaxNode);
+
+impl CustomType {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn type_params(&self) -> AstChildren<TypeParam> {
+        support::children(&self.0)
+    }
+    pub fn constructors(&self) -> AstChildren<Constructor> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for CustomType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == CUSTOM_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for CustomType {}
+
+impl HasName for CustomType {}
+
+impl HasVisibility for CustomType {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiscardPattern(pub(crate) SyntaxNode);
+
+impl AstNode for DiscardPattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == DISCARD_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EchoExpr(pub(crate) SyntaxNode);
+
+impl EchoExpr {
+    pub fn expression(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for EchoExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == ECHO_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Literal(Literal),
+    NameRef(NameRef),
+    QualifiedRef(QualifiedRef),
+    Tuple(Tuple),
+    List(List),
+    CallExpr(CallExpr),
+    CaseExpr(CaseExpr),
+    BitArray(BitArray),
+    BinaryExpr(BinaryExpr),
+    UnaryExpr(UnaryExpr),
+    CaptureHole(CaptureHole),
+    FieldAccess(FieldAccess),
+    TodoExpr(TodoExpr),
+    PanicExpr(PanicExpr),
+    EchoExpr(EchoExpr),
+    Block(Block),
+    Lambda(Lambda),
+}
+
+impl AstNode for Expr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            LITERAL
+                | NAME_REF
+                | QUALIFIED_REF
+                | TUPLE
+                | LIST
+                | CALL_EXPR
+                | CASE_EXPR
+                | BIT_ARRAY
+                | BINARY_EXPR
+                | UNARY_EXPR
+                | CAPTURE_HOLE
+                | FIELD_ACCESS
+                | TODO_EXPR
+                | PANIC_EXPR
+                | ECHO_EXPR
+                | BLOCK
+                | LAMBDA
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            LITERAL => Self::Literal(Literal(node)),
+            NAME_REF => Self::NameRef(NameRef(node)),
+            QUALIFIED_REF => Self::QualifiedRef(QualifiedRef(node)),
+            TUPLE => Self::Tuple(Tuple(node)),
+            LIST => Self::List(List(node)),
+            CALL_EXPR => Self::CallExpr(CallExpr(node)),
+            CASE_EXPR => Self::CaseExpr(CaseExpr(node)),
+            BIT_ARRAY => Self::BitArray(BitArray(node)),
+            BINARY_EXPR => Self::BinaryExpr(BinaryExpr(node)),
+            UNARY_EXPR => Self::UnaryExpr(UnaryExpr(node)),
+            CAPTURE_HOLE => Self::CaptureHole(CaptureHole(node)),
+            FIELD_ACCESS => Self::FieldAccess(FieldAccess(node)),
+            TODO_EXPR => Self::TodoExpr(TodoExpr(node)),
+            PANIC_EXPR => Self::PanicExpr(PanicExpr(node)),
+            ECHO_EXPR => Self::EchoExpr(EchoExpr(node)),
+            BLOCK => Self::Block(Block(node)),
+            LAMBDA => Self::Lambda(Lambda(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Literal(it) => &it.0,
+            Self::NameRef(it) => &it.0,
+            Self::QualifiedRef(it) => &it.0,
+            Self::Tuple(it) => &it.0,
+            Self::List(it) => &it.0,
+            Self::CallExpr(it) => &it.0,
+            Self::CaseExpr(it) => &it.0,
+            Self::BitArray(it) => &it.0,
+            Self::BinaryExpr(it) => &it.0,
+            Self::UnaryExpr(it) => &it.0,
+            Self::CaptureHole(it) => &it.0,
+            Self::FieldAccess(it) => &it.0,
+            Self::TodoExpr(it) => &it.0,
+            Self::PanicExpr(it) => &it.0,
+            Self::EchoExpr(it) => &it.0,
+            Self::Block(it) => &it.0,
+            Self::Lambda(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExternalFunction(pub(crate) SyntaxNode);
+
+impl ExternalFunction {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn param_list(&self) -> Option<ParamList> {
+        support::child(&self.0)
+    }
+    pub fn return_annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+    pub fn module(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, STRING)
+    }
+    pub fn function(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == STRING)
+            .nth(1)
+    }
+}
+
+impl AstNode for ExternalFunction {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == EXTERNAL_FUNCTION
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for ExternalFunction {}
+
+impl HasName for ExternalFunction {}
+
+impl HasVisibility for ExternalFunction {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExternalType(pub(crate) SyntaxNode);
+
+impl ExternalType {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn type_params(&self) -> AstChildren<TypeParam> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for ExternalType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == EXTERNAL_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for ExternalType {}
+
+impl HasName for ExternalType {}
+
+impl HasVisibility for ExternalType {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldAccess(pub(crate) SyntaxNode);
+
+impl FieldAccess {
+    pub fn container(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+    pub fn index(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, INTEGER)
+    }
+}
+
+impl AstNode for FieldAccess {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == FIELD_ACCESS
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FnType(pub(crate) SyntaxNode);
+
+impl FnType {
+    pub fn param_list(&self) -> Option<ParamList> {
+        support::child(&self.0)
+    }
+    pub fn return_(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for FnType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == FN_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Function(pub(crate) SyntaxNode);
+
+impl Function {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn param_list(&self) -> Option<ParamList> {
+        support::child(&self.0)
+    }
+    pub fn return_annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+    pub fn body(&self) -> Option<Block> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Function {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == FUNCTION
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for Function {}
+
+impl HasName for Function {}
+
+impl HasVisibility for Function {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Import(pub(crate) SyntaxNode);
+
+impl Import {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn module(&self) -> Option<ImportModule> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Import {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == IMPORT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for Import {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImportModule(pub(crate) SyntaxNode);
+
+impl ImportModule {
+    pub fn module_path(&self) -> AstChildren<Path> {
+        support::children(&self.0)
+    }
+    pub fn unqualified(&self) -> AstChildren<UnqualifiedImport> {
+        support::children(&self.0)
+    }
+    pub fn as_name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ImportModule {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == IMPORT_MODULE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lambda(pub(crate) SyntaxNode);
+
+impl Lambda {
+    pub fn param_list(&self) -> Option<ParamList> {
+        support::child(&self.0)
+    }
+    pub fn return_annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+    pub fn body(&self) -> Option<Block> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Lambda {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LAMBDA
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LetStatement(pub(crate) SyntaxNode);
+
+impl LetStatement {
+    pub fn pattern(&self) -> Option<Pattern> {
+        support::child(&self.0)
+    }
+    pub fn annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for LetStatement {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LET_STATEMENT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct List(pub(crate) SyntaxNode);
+
+impl List {
+    pub fn elements(&self) -> AstChildren<Expr> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for List {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LIST
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ListPattern(pub(crate) SyntaxNode);
+
+impl ListPattern {
+    pub fn elements(&self) -> AstChildren<Pattern> {
+        support::children(&self.0)
+    }
+    pub fn rest(&self) -> Option<ListRest> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ListPattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LIST_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ListRest(pub(crate) SyntaxNode);
+
+impl ListRest {
+    pub fn pattern(&self) -> Option<Pattern> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ListRest {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LIST_REST
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Literal(pub(crate) SyntaxNode);
+
+impl AstNode for Literal {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LITERAL
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LiteralPattern(pub(crate) SyntaxNode);
+
+impl LiteralPattern {
+    pub fn literal(&self) -> Option<Literal> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for LiteralPattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LITERAL_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleConstant(pub(crate) SyntaxNode);
+
+impl ModuleConstant {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+    pub fn value(&self) -> Option<ConstantValue> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for ModuleConstant {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == MODULE_CONSTANT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for ModuleConstant {}
+
+impl HasName for ModuleConstant {}
+
+impl HasVisibility for ModuleConstant {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModuleItem {
+    CustomType(CustomType),
+    TypeAlias(TypeAlias),
+    ExternalFunction(ExternalFunction),
+    ExternalType(ExternalType),
+    Import(Import),
+}
+
+impl AstNode for ModuleItem {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            CUSTOM_TYPE | TYPE_ALIAS | EXTERNAL_FUNCTION | EXTERNAL_TYPE | IMPORT
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            CUSTOM_TYPE => Self::CustomType(CustomType(node)),
+            TYPE_ALIAS => Self::TypeAlias(TypeAlias(node)),
+            EXTERNAL_FUNCTION => Self::ExternalFunction(ExternalFunction(node)),
+            EXTERNAL_TYPE => Self::ExternalType(ExternalType(node)),
+            IMPORT => Self::Import(Import(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::CustomType(it) => &it.0,
+            Self::TypeAlias(it) => &it.0,
+            Self::ExternalFunction(it) => &it.0,
+            Self::ExternalType(it) => &it.0,
+            Self::Import(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleName(pub(crate) SyntaxNode);
+
+impl AstNode for ModuleName {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == MODULE_NAME
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name(pub(crate) SyntaxNode);
+
+impl AstNode for Name {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == NAME
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NameRef(pub(crate) SyntaxNode);
+
+impl AstNode for NameRef {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == NAME_REF
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PanicExpr(pub(crate) SyntaxNode);
+
+impl PanicExpr {
+    pub fn message(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for PanicExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PANIC_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Param(pub(crate) SyntaxNode);
+
+impl Param {
+    pub fn ty(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for Param {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PARAM
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParamList(pub(crate) SyntaxNode);
+
+impl ParamList {
+    pub fn params(&self) -> AstChildren<Param> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for ParamList {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PARAM_LIST
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Path(pub(crate) SyntaxNode);
+
+impl AstNode for Path {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PATH
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    VariablePattern(VariablePattern),
+    DiscardPattern(DiscardPattern),
+    LiteralPattern(LiteralPattern),
+    TuplePattern(TuplePattern),
+    ListPattern(ListPattern),
+    ConstructorPattern(ConstructorPattern),
+    ConcatPattern(ConcatPattern),
+    AlternativePattern(AlternativePattern),
+    BitArray(BitArray),
+}
+
+impl AstNode for Pattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            VARIABLE_PATTERN
+                | DISCARD_PATTERN
+                | LITERAL_PATTERN
+                | TUPLE_PATTERN
+                | LIST_PATTERN
+                | CONSTRUCTOR_PATTERN
+                | CONCAT_PATTERN
+                | ALTERNATIVE_PATTERN
+                | BIT_ARRAY
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            VARIABLE_PATTERN => Self::VariablePattern(VariablePattern(node)),
+            DISCARD_PATTERN => Self::DiscardPattern(DiscardPattern(node)),
+            LITERAL_PATTERN => Self::LiteralPattern(LiteralPattern(node)),
+            TUPLE_PATTERN => Self::TuplePattern(TuplePattern(node)),
+            LIST_PATTERN => Self::ListPattern(ListPattern(node)),
+            CONSTRUCTOR_PATTERN => Self::ConstructorPattern(ConstructorPattern(node)),
+            CONCAT_PATTERN => Self::ConcatPattern(ConcatPattern(node)),
+            ALTERNATIVE_PATTERN => Self::AlternativePattern(AlternativePattern(node)),
+            BIT_ARRAY => Self::BitArray(BitArray(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::VariablePattern(it) => &it.0,
+            Self::DiscardPattern(it) => &it.0,
+            Self::LiteralPattern(it) => &it.0,
+            Self::TuplePattern(it) => &it.0,
+            Self::ListPattern(it) => &it.0,
+            Self::ConstructorPattern(it) => &it.0,
+            Self::ConcatPattern(it) => &it.0,
+            Self::AlternativePattern(it) => &it.0,
+            Self::BitArray(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PatternArg(pub(crate) SyntaxNode);
+
+impl PatternArg {
+    pub fn label(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn pattern(&self) -> Option<Pattern> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for PatternArg {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PATTERN_ARG
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QualifiedRef(pub(crate) SyntaxNode);
+
+impl QualifiedRef {
+    pub fn module(&self) -> Option<ModuleName> {
+        support::child(&self.0)
+    }
+    pub fn name(&self) -> Option<NameRef> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for QualifiedRef {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == QUALIFIED_REF
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecordSpread(pub(crate) SyntaxNode);
+
+impl RecordSpread {
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for RecordSpread {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == RECORD_SPREAD
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceFile(pub(crate) SyntaxNode);
+
+impl SourceFile {
+    pub fn statements(&self) -> AstChildren<TargetGroup> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for SourceFile {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SOURCE_FILE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Statement {
+    ModuleConstant(ModuleConstant),
+    Function(Function),
+    CustomType(CustomType),
+    TypeAlias(TypeAlias),
+    ExternalFunction(ExternalFunction),
+    ExternalType(ExternalType),
+    Import(Import),
+}
+
+impl AstNode for Statement {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            MODULE_CONSTANT
+                | FUNCTION
+                | CUSTOM_TYPE
+                | TYPE_ALIAS
+                | EXTERNAL_FUNCTION
+                | EXTERNAL_TYPE
+                | IMPORT
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            MODULE_CONSTANT => Self::ModuleConstant(ModuleConstant(node)),
+            FUNCTION => Self::Function(Function(node)),
+            CUSTOM_TYPE => Self::CustomType(CustomType(node)),
+            TYPE_ALIAS => Self::TypeAlias(TypeAlias(node)),
+            EXTERNAL_FUNCTION => Self::ExternalFunction(ExternalFunction(node)),
+            EXTERNAL_TYPE => Self::ExternalType(ExternalType(node)),
+            IMPORT => Self::Import(Import(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::ModuleConstant(it) => &it.0,
+            Self::Function(it) => &it.0,
+            Self::CustomType(it) => &it.0,
+            Self::TypeAlias(it) => &it.0,
+            Self::ExternalFunction(it) => &it.0,
+            Self::ExternalType(it) => &it.0,
+            Self::Import(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Target(pub(crate) SyntaxNode);
+
+impl Target {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, IDENT)
+    }
+}
+
+impl AstNode for Target {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TARGET
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TargetGroup(pub(crate) SyntaxNode);
+
+impl TargetGroup {
+    pub fn target(&self) -> Option<Target> {
+        support::child(&self.0)
+    }
+    pub fn statements(&self) -> AstChildren<Statement> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TargetGroup {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TARGET_GROUP
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TodoExpr(pub(crate) SyntaxNode);
+
+impl TodoExpr {
+    pub fn message(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for TodoExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TODO_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tuple(pub(crate) SyntaxNode);
+
+impl Tuple {
+    pub fn elements(&self) -> AstChildren<Expr> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for Tuple {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TUPLE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TuplePattern(pub(crate) SyntaxNode);
+
+impl TuplePattern {
+    pub fn elements(&self) -> AstChildren<Pattern> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TuplePattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TUPLE_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TupleType(pub(crate) SyntaxNode);
+
+impl TupleType {
+    pub fn field_types(&self) -> AstChildren<TypeAnnotation> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TupleType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TUPLE_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeAlias(pub(crate) SyntaxNode);
+
+impl TypeAlias {
+    pub fn attributes(&self) -> AstChildren<Attribute> {
+        support::children(&self.0)
+    }
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn type_params(&self) -> AstChildren<TypeParam> {
+        support::children(&self.0)
+    }
+    pub fn aliased_type(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for TypeAlias {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_ALIAS
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasAttributes for TypeAlias {}
+
+impl HasName for TypeAlias {}
+
+impl HasVisibility for TypeAlias {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypeAnnotation {
+    FnType(FnType),
+    VarType(VarType),
+    TupleType(TupleType),
+    ConstructorType(ConstructorType),
+}
+
+impl AstNode for TypeAnnotation {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, FN_TYPE | VAR_TYPE | TUPLE_TYPE | CONSTRUCTOR_TYPE)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            FN_TYPE => Self::FnType(FnType(node)),
+            VAR_TYPE => Self::VarType(VarType(node)),
+            TUPLE_TYPE => Self::TupleType(TupleType(node)),
+            CONSTRUCTOR_TYPE => Self::ConstructorType(ConstructorType(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::FnType(it) => &it.0,
+            Self::VarType(it) => &it.0,
+            Self::TupleType(it) => &it.0,
+            Self::ConstructorType(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeArgList(pub(crate) SyntaxNode);
+
+impl TypeArgList {
+    pub fn args(&self) -> AstChildren<TypeAnnotation> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TypeArgList {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_ARG_LIST
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypeDefinition {
+    CustomType(CustomType),
+    TypeAlias(TypeAlias),
+    ExternalType(ExternalType),
+}
+
+impl AstNode for TypeDefinition {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, CUSTOM_TYPE | TYPE_ALIAS | EXTERNAL_TYPE)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let ret = match node.kind() {
+            CUSTOM_TYPE => Self::CustomType(CustomType(node)),
+            TYPE_ALIAS => Self::TypeAlias(TypeAlias(node)),
+            EXTERNAL_TYPE => Self::ExternalType(ExternalType(node)),
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::CustomType(it) => &it.0,
+            Self::TypeAlias(it) => &it.0,
+            Self::ExternalType(it) => &it.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeParam(pub(crate) SyntaxNode);
+
+impl TypeParam {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for TypeParam {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_PARAM
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasName for TypeParam {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnaryExpr(pub(crate) SyntaxNode);
+
+impl UnaryExpr {
+    pub fn arg(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for UnaryExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == UNARY_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnqualifiedImport(pub(crate) SyntaxNode);
+
+impl UnqualifiedImport {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+    pub fn as_name(&self) -> Option<Name> {
+        support::children(&self.0).nth(1)
+    }
+}
+
+impl AstNode for UnqualifiedImport {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == UNQUALIFIED_IMPORT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasName for UnqualifiedImport {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UseAssignment(pub(crate) SyntaxNode);
+
+impl UseAssignment {
+    pub fn pattern(&self) -> Option<Pattern> {
+        support::child(&self.0)
+    }
+    pub fn annotation(&self) -> Option<TypeAnnotation> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for UseAssignment {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == USE_ASSIGNMENT
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UseExpr(pub(crate) SyntaxNode);
+
+impl UseExpr {
+    pub fn assignments(&self) -> AstChildren<UseAssignment> {
+        support::children(&self.0)
+    }
+    pub fn callee(&self) -> Option<Expr> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for UseExpr {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == USE_EXPR
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VarType(pub(crate) SyntaxNode);
+
+impl VarType {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, IDENT)
+    }
+}
+
+impl AstNode for VarType {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == VAR_TYPE
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VariablePattern(pub(crate) SyntaxNode);
+
+impl VariablePattern {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.0)
+    }
+}
+
+impl AstNode for VariablePattern {
+    type Language = GleamLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == VARIABLE_PATTERN
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl HasName for VariablePattern {}
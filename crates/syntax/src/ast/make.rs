@@ -0,0 +1,175 @@
+//! Constructors of syntax nodes, eg. for the code inserted by refactorings.
+//!
+//! Nodes are built by parsing a small module containing them, so they are always well-formed
+//! as long as their parts are. The returned nodes are roots of their own trees.
+use crate::ast::{self, AstNode};
+use crate::{parse_file, GleamLanguage};
+
+pub fn name(text: &str) -> ast::Name {
+    from_text("const ", text, "= 1")
+}
+
+pub fn name_ref(text: &str) -> ast::NameRef {
+    from_text("fn f() { ", text, "}")
+}
+
+/// `import gleam/list.{map}`, without the braces if there are no unqualified imports.
+pub fn import(path: &str, unqualified: &[&str]) -> ast::Import {
+    from_text("", &import_text(path, unqualified), "")
+}
+
+/// `import gleam/list.{map} as l`.
+pub fn import_as(path: &str, unqualified: &[&str], alias: &str) -> ast::Import {
+    let text = format!("{} as {alias}", import_text(path, unqualified));
+    from_text("", &text, "")
+}
+
+fn import_text(path: &str, unqualified: &[&str]) -> String {
+    if unqualified.is_empty() {
+        format!("import {path}")
+    } else {
+        format!("import {path}.{{{}}}", unqualified.join(", "))
+    }
+}
+
+pub fn expr_from_text(text: &str) -> ast::Expr {
+    from_text("fn f() { ", text, "}")
+}
+
+pub fn pattern_from_text(text: &str) -> ast::Pattern {
+    from_text("fn f() { let ", text, "= x }")
+}
+
+pub fn ty_from_text(text: &str) -> ast::TypeAnnotation {
+    from_text("type A = ", text, "")
+}
+
+/// A parameter of a function, eg. `x: Int`.
+pub fn param(name: &str, ty: Option<ast::TypeAnnotation>) -> ast::Param {
+    let text = match ty {
+        Some(ty) => format!("{name}: {}", ty.syntax()),
+        None => name.to_owned(),
+    };
+    from_text("fn f(", &text, ") {}")
+}
+
+/// An argument of a call, eg. `label: value`.
+pub fn arg(label: Option<&str>, value: ast::Expr) -> ast::Arg {
+    let text = match label {
+        Some(label) => format!("{label}: {}", value.syntax()),
+        None => value.syntax().to_string(),
+    };
+    from_text("fn f() { g(", &text, ") }")
+}
+
+pub fn let_statement(
+    pattern: ast::Pattern,
+    ty: Option<ast::TypeAnnotation>,
+    value: ast::Expr,
+) -> ast::LetStatement {
+    let text = match ty {
+        Some(ty) => format!(
+            "let {}: {} = {}",
+            pattern.syntax(),
+            ty.syntax(),
+            value.syntax()
+        ),
+        None => format!("let {} = {}", pattern.syntax(), value.syntax()),
+    };
+    from_text("fn f() { ", &text, "}")
+}
+
+/// A clause matching each subject of a case expression with one of `patterns`.
+pub fn case_clause(
+    patterns: impl IntoIterator<Item = ast::Pattern>,
+    guard: Option<ast::Expr>,
+    body: ast::Expr,
+) -> ast::CaseClause {
+    let patterns = patterns
+        .into_iter()
+        .map(|it| it.syntax().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let text = match guard {
+        Some(guard) => format!("{patterns} if {} -> {}", guard.syntax(), body.syntax()),
+        None => format!("{patterns} -> {}", body.syntax()),
+    };
+    from_text("fn f() { case x { ", &text, "} }")
+}
+
+/// The outermost node of type `N` spanning `text`, parsed between `prefix` and `suffix`.
+///
+/// Whitespace after `text` would end up in the node, so `suffix` should not start with any.
+#[track_caller]
+fn from_text<N: AstNode<Language = GleamLanguage>>(prefix: &str, text: &str, suffix: &str) -> N {
+    let parse = parse_file(&format!("{prefix}{text}{suffix}"));
+    let node = parse
+        .syntax_node()
+        .descendants()
+        .filter(|node| usize::from(node.text_range().start()) == prefix.len())
+        .filter(|node| node.text().to_string().trim_end() == text)
+        .find_map(N::cast)
+        .unwrap_or_else(|| {
+            panic!(
+                "failed to make a `{}` from {text:?}",
+                std::any::type_name::<N>()
+            )
+        });
+    N::cast(node.syntax().clone_subtree()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import() {
+        let it = super::import("gleam/list", &["map", "Map"]);
+        assert_eq!(it.syntax().to_string(), "import gleam/list.{map, Map}");
+        assert!(it.syntax().parent().is_none());
+        let unqualified = it
+            .module()
+            .unwrap()
+            .unqualified()
+            .map(|it| it.syntax().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(unqualified, ["map", "Map"]);
+
+        let it = import_as("a/b", &[], "c");
+        assert_eq!(it.syntax().to_string(), "import a/b as c");
+    }
+
+    #[test]
+    fn exprs() {
+        let e = expr_from_text("a + b");
+        assert!(matches!(e, ast::Expr::BinaryExpr(_)));
+        let e = expr_from_text("{ a }");
+        assert!(matches!(e, ast::Expr::Block(_)));
+
+        let param = param("x", Some(ty_from_text("List(Int)")));
+        assert_eq!(param.syntax().to_string(), "x: List(Int)");
+        assert_eq!(param.name().unwrap().syntax().to_string(), "x");
+
+        let clause = case_clause(
+            [pattern_from_text("[x, ..]"), pattern_from_text("_")],
+            Some(expr_from_text("x > 1")),
+            expr_from_text("x"),
+        );
+        assert_eq!(clause.syntax().to_string(), "[x, ..], _ if x > 1 -> x");
+        assert!(clause.guard().is_some());
+
+        let stmt = let_statement(pattern_from_text("#(a, b)"), None, arg_value());
+        assert_eq!(stmt.syntax().to_string(), "let #(a, b) = f(label: 1)");
+    }
+
+    fn arg_value() -> ast::Expr {
+        let arg = arg(Some("label"), expr_from_text("1"));
+        expr_from_text(&format!("f({})", arg.syntax()))
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid() {
+        name("1");
+    }
+}
@@ -0,0 +1,140 @@
+//! Typed traversals of syntax trees.
+use std::marker::PhantomData;
+
+pub use rowan::WalkEvent;
+
+use crate::ast::AstNode;
+use crate::{GleamLanguage, SyntaxNode};
+
+/// Dispatch a `SyntaxNode` to the first arm whose node type it can be cast to.
+///
+/// ```
+/// # use syntax::{ast, match_ast, SyntaxNode};
+/// fn describe(node: SyntaxNode) -> &'static str {
+///     match_ast! {
+///         match node {
+///             ast::Function(_it) => "function",
+///             ast::Import(_it) => "import",
+///             _ => "other",
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! match_ast {
+    (match $node:ident { $($tt:tt)* }) => { $crate::match_ast!(match ($node) { $($tt)* }) };
+
+    (match ($node:expr) {
+        $( $( $path:ident )::+ ($it:pat) => $res:expr, )*
+        _ => $catch_all:expr $(,)?
+    }) => {{
+        $( if let Some($it) = <$($path)::+ as $crate::ast::AstNode>::cast($node.clone()) { $res } else )*
+        { $catch_all }
+    }};
+}
+
+/// All descendants of `node` of type `N`, including itself, in preorder.
+pub fn descendants_of_type<N: AstNode<Language = GleamLanguage>>(
+    node: &SyntaxNode,
+) -> impl Iterator<Item = N> {
+    node.descendants().filter_map(N::cast)
+}
+
+/// A preorder traversal yielding only the nodes of type `N`.
+///
+/// Unlike `descendants`, it reports leaving a node too and can skip the subtree of the node
+/// it just entered, eg. to not look into nested functions.
+pub struct Preorder<N> {
+    inner: rowan::api::Preorder<GleamLanguage>,
+    _ty: PhantomData<fn() -> N>,
+}
+
+impl<N: AstNode<Language = GleamLanguage>> Preorder<N> {
+    pub fn new(node: &SyntaxNode) -> Self {
+        Self {
+            inner: node.preorder(),
+            _ty: PhantomData,
+        }
+    }
+
+    /// Skip the subtree of the node of the last `WalkEvent::Enter`.
+    pub fn skip_subtree(&mut self) {
+        self.inner.skip_subtree();
+    }
+}
+
+impl<N: AstNode<Language = GleamLanguage>> Iterator for Preorder<N> {
+    type Item = WalkEvent<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.find_map(|event| match event {
+            WalkEvent::Enter(node) => N::cast(node).map(WalkEvent::Enter),
+            WalkEvent::Leave(node) => N::cast(node).map(WalkEvent::Leave),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{descendants_of_type, Preorder, WalkEvent};
+    use crate::ast::{self, AstNode};
+    use crate::{parse_file, SyntaxNode};
+
+    #[test]
+    fn descendants() {
+        let root = parse_file("fn f(a) { g(1, h(2)) }\nconst c = 3").syntax_node();
+        let calls = descendants_of_type::<ast::CallExpr>(&root)
+            .map(|it| it.syntax().to_string().trim().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(calls, ["g(1, h(2))", "h(2)"]);
+    }
+
+    #[test]
+    fn preorder() {
+        let root = parse_file("fn f() { g(h(1)) }\nfn i() { j() }").syntax_node();
+        let mut preorder = Preorder::<ast::CallExpr>::new(&root);
+        let mut events = Vec::new();
+        while let Some(event) = preorder.next() {
+            match event {
+                WalkEvent::Enter(call) => {
+                    let text = call.syntax().to_string().trim().to_owned();
+                    // Skip the arguments of the outer call.
+                    if text.starts_with('g') {
+                        preorder.skip_subtree();
+                    }
+                    events.push(format!("enter {text}"));
+                }
+                WalkEvent::Leave(call) => {
+                    events.push(format!("leave {}", call.syntax().to_string().trim()))
+                }
+            }
+        }
+        assert_eq!(
+            events,
+            ["enter g(h(1))", "leave g(h(1))", "enter j()", "leave j()"]
+        );
+    }
+
+    #[test]
+    fn match_ast() {
+        fn describe(node: SyntaxNode) -> String {
+            match_ast! {
+                match node {
+                    ast::Function(it) => format!("fn {}", it.name().unwrap().syntax()),
+                    ast::Import(_it) => "import".into(),
+                    _ => "other".into(),
+                }
+            }
+        }
+        let root = parse_file("import a\nfn f() {}").syntax_node();
+        let kinds = root
+            .descendants()
+            .filter(|it| {
+                it.parent()
+                    .map_or(false, |p| p.kind() == crate::SyntaxKind::TARGET_GROUP)
+            })
+            .map(describe)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, ["import", "fn f"]);
+    }
+}
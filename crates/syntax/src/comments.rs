@@ -0,0 +1,159 @@
+//! Which node a comment belongs to, so that it travels with the node when it is moved or
+//! reformatted.
+//!
+//! A comment following code on the same line belongs to the outermost node ending with that
+//! code. Any other comment belongs to the outermost node starting with the next code, unless
+//! a blank line separates them. Module comments (`////`) never belong to a node.
+use std::iter::successors;
+
+use crate::SyntaxKind::{self, *};
+use crate::{SyntaxNode, SyntaxToken, TextRange};
+
+/// The comments belonging to `node` before and after its code, in source order.
+pub fn attached_comments(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let (Some(first), Some(last)) = (first_code_token(node), last_code_token(node)) else {
+        return Vec::new();
+    };
+    let mut leading = successors(first.prev_token(), SyntaxToken::prev_token)
+        .take_while(|tok| tok.kind().is_whitespace())
+        .filter(|tok| comment_owner(tok).as_ref() == Some(node))
+        .collect::<Vec<_>>();
+    leading.reverse();
+    let trailing = successors(last.next_token(), SyntaxToken::next_token)
+        .take_while(|tok| tok.kind().is_whitespace())
+        .filter(|tok| comment_owner(tok).as_ref() == Some(node));
+    leading.extend(trailing);
+    leading
+}
+
+/// The range of the code of `node`, without the whitespace and comments around it.
+pub fn code_range(node: &SyntaxNode) -> TextRange {
+    match (first_code_token(node), last_code_token(node)) {
+        (Some(first), Some(last)) => first.text_range().cover(last.text_range()),
+        _ => node.text_range(),
+    }
+}
+
+/// The range of the code of `node` extended to its attached comments, but not to any
+/// whitespace around them.
+pub fn range_with_comments(node: &SyntaxNode) -> TextRange {
+    let code = code_range(node);
+    attached_comments(node)
+        .iter()
+        .fold(code, |range, tok| range.cover(tok.text_range()))
+}
+
+/// The node `comment` belongs to, if any.
+pub fn comment_owner(comment: &SyntaxToken) -> Option<SyntaxNode> {
+    if !matches!(comment.kind(), COMMENT | COMMENT_STATEMENT) {
+        return None;
+    }
+    if let Some(code) = code_before_on_same_line(comment) {
+        return outermost_node(&code, last_code_token);
+    }
+    let mut tok = comment.next_token();
+    while let Some(t) = tok {
+        match t.kind() {
+            WHITESPACE if t.text().matches('\n').count() > 1 => return None,
+            kind if kind.is_whitespace() => {}
+            _ => return outermost_node(&t, first_code_token),
+        }
+        tok = t.next_token();
+    }
+    None
+}
+
+fn code_before_on_same_line(comment: &SyntaxToken) -> Option<SyntaxToken> {
+    successors(comment.prev_token(), SyntaxToken::prev_token)
+        .take_while(|tok| !tok.text().contains('\n'))
+        .find(|tok| !tok.kind().is_whitespace())
+}
+
+/// The outermost node whose first (or last) code is `tok`. Modules and target groups only
+/// group statements, so comments never belong to them.
+fn outermost_node(
+    tok: &SyntaxToken,
+    edge: fn(&SyntaxNode) -> Option<SyntaxToken>,
+) -> Option<SyntaxNode> {
+    tok.parent_ancestors()
+        .take_while(|node| !is_container(node.kind()) && edge(node).as_ref() == Some(tok))
+        .last()
+}
+
+fn is_container(kind: SyntaxKind) -> bool {
+    matches!(kind, SOURCE_FILE | TARGET_GROUP)
+}
+
+fn first_code_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    successors(node.first_token(), SyntaxToken::next_token)
+        .take_while(|tok| tok.text_range().end() <= node.text_range().end())
+        .find(|tok| !tok.kind().is_whitespace())
+}
+
+fn last_code_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    successors(node.last_token(), SyntaxToken::prev_token)
+        .take_while(|tok| tok.text_range().start() >= node.text_range().start())
+        .find(|tok| !tok.kind().is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attached_comments, code_range, comment_owner, range_with_comments};
+    use crate::ast::{self, AstNode};
+    use crate::{parse_file, NodeOrToken, SyntaxKind};
+
+    #[track_caller]
+    fn check(src: &str, expect: &[(&str, Option<SyntaxKind>)]) {
+        let root = parse_file(src).syntax_node();
+        let owners = root
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|tok| tok.kind().is_whitespace() && tok.kind() != SyntaxKind::WHITESPACE)
+            .map(|tok| (tok.to_string(), comment_owner(&tok).map(|it| it.kind())))
+            .collect::<Vec<_>>();
+        let expect = expect
+            .iter()
+            .map(|&(text, kind)| (text.to_owned(), kind))
+            .collect::<Vec<_>>();
+        assert_eq!(owners, expect);
+    }
+
+    #[test]
+    fn owners() {
+        check(
+            "//// module\n\n// a\n/// doc\n@internal\nfn f() {\n  // b\n  g(1) // c\n  // d\n}\n\n// e\n\nconst x = 1 // f\n",
+            &[
+                ("//// module", None),
+                ("// a", Some(SyntaxKind::FUNCTION)),
+                ("/// doc", Some(SyntaxKind::FUNCTION)),
+                ("// b", Some(SyntaxKind::CALL_EXPR)),
+                ("// c", Some(SyntaxKind::CALL_EXPR)),
+                ("// d", None),
+                ("// e", None),
+                ("// f", Some(SyntaxKind::MODULE_CONSTANT)),
+            ],
+        );
+    }
+
+    #[test]
+    fn attached() {
+        let src = "import a // a\n// b\nconst x = 1 // x\nconst y = 2";
+        let root = parse_file(src).root();
+        let consts = root
+            .syntax()
+            .descendants()
+            .filter_map(ast::ModuleConstant::cast)
+            .collect::<Vec<_>>();
+        let comments = attached_comments(consts[0].syntax())
+            .iter()
+            .map(|tok| tok.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(comments, ["// b", "// x"]);
+        assert_eq!(
+            &src[range_with_comments(consts[0].syntax())],
+            "// b\nconst x = 1 // x"
+        );
+        assert_eq!(&src[code_range(consts[0].syntax())], "const x = 1");
+        assert!(attached_comments(consts[1].syntax()).is_empty());
+    }
+}
@@ -1,5 +1,3 @@
-use crate::lexer::lex_string;
-pub use logos::{Lexer, Logos};
 use std::fmt;
 
 macro_rules! def {
@@ -10,7 +8,7 @@ macro_rules! def {
     )*
   ) => {
     #[allow(bad_style)]
-    #[derive(Logos, Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
     #[repr(u16)]
     pub enum SyntaxKind {
       $(
@@ -64,214 +62,132 @@ macro_rules! to_str {
 }
 
 def! {
-    #[regex(r"([ \t\n])+")]
     WHITESPACE @WHITESPACE_FIRST,
-
-    #[regex(r"//[^\n\r]*")]
     COMMENT,
-
-    #[regex(r"///[^\n\r]*")]
     COMMENT_STATEMENT,
-
-    #[regex(r"////[^\n\r]*")]
     COMMENT_MODULE @WHITESPACE_LAST,
-
-    #[regex("[a-z][_a-z0-9]*")]
     IDENT,
-
-    #[regex("_[_a-z0-9]+")]
     DISCARD_IDENT,
-
-    #[regex("[A-Z][0-9a-zA-Z]*")]
-    U_IDENT ,
-
-    #[regex("(0[xXbBoO])?[0-9_]+")]
+    U_IDENT,
     INTEGER,
-
-    #[regex(r"([0-9][_0-9]+(\.[0-9_]+)?([eE][+-]?[0-9_]+)?)", priority = 3)]
     FLOAT,
-
-    #[regex(r#"""#, lex_string)]
     STRING,
 
-    #[token("[")]
     L_SQUARE = ["["] @SYMBOL_FIRST,
-
-    #[token("]")]
     R_SQUARE = ["]"],
-
-    #[token("{")]
     L_BRACE = ["{"],
-
-    #[token("}")]
     R_BRACE = ["}"],
-
-    #[token("(")]
     L_PAREN = ["("],
-
-    #[token(")")]
     R_PAREN = [")"],
-
-    #[token("+")]
     PLUS = ["+"],
-
-    #[token("-")]
     MINUS = ["-"],
-
-    #[token("*")]
     STAR = ["*"],
-
-    #[token("/")]
     SLASH = ["/"],
-
-    #[token("<")]
+    PERCENT = ["%"],
     LESS = ["<"],
-
-    #[token(">")]
     GREATER = [">"],
-
-    #[token("<=")]
     LESS_EQ = ["<="],
-
-    #[token(">=")]
     GREATER_EQ = [">="],
-
-    #[token("+.")]
     PLUS_DOT = ["+."],
-
-    #[token("-.")]
     MINUS_DOT = ["-."],
-
-    #[token("*.")]
     STAR_DOT = ["*."],
-
-    #[token("<.")]
+    SLASH_DOT = ["/."],
     LESS_DOT = ["<."],
-
-    #[token(">.")]
     GREATER_DOT = [">."],
-
-    #[token("<=.")]
     LESS_EQ_DOT = ["<=."],
-
-    #[token(">=.")]
     GREATER_EQ_DOT = [">=."],
-
-    #[token("<>")]
     LT_GT = ["<>"],
-
-    #[token(":")]
     COLON = [":"],
-
-    #[token(",")]
     COMMA = [","],
-
-    #[token("#")]
     HASH = ["#"],
-
-    #[token("!")]
+    AT = ["@"],
     BANG = ["!"],
-
-    #[token("=")]
     EQ = ["="],
-
-    #[token("==")]
     EQ_EQ = ["=="],
-
-    #[token("!=")]
     NOT_EQ = ["!="],
-
-    #[token("|")]
     VBAR = ["|"],
-
-    #[token("||")]
     VBAR_VBAR = ["||"],
-
-    #[token("&&")]
     AMPER_AMPER = ["&&"],
-
-    #[token("<<")]
     LT_LT = ["<<"],
-
-    #[token(">>")]
     GT_GT = [">>"],
-
-    #[token("|>")]
     PIPE = ["|>"],
-
-    #[token(".")]
     DOT = ["."],
-
-    #[token("->")]
     R_ARROW = ["->"],
-
-    #[token("<-")]
     L_ARROW = ["<-"],
-
-    #[token("..")]
     DOT_DOT = [".."] @SYMBOL_LAST,
 
-    #[token("as")]
+    // Soft keywords are lexed as identifiers and turned into keywords by the parser, so that
+    // they remain usable as names.
     AS_KW = ["as"] @KEYWORD_FIRST,
-
-    #[token("assert")]
     ASSERT_KW = ["assert"],
-
-    #[token("case")]
     CASE_KW = ["case"],
-
-    #[token("const")]
     CONST_KW = ["const"],
-
-    #[token("external")]
+    ECHO_KW = ["echo"],
     EXTERNAL_KW = ["external"],
-
-    #[token("fn")]
     FN_KW = ["fn"],
-
-    #[token("if")]
     IF_KW = ["if"],
-
-    #[token("import")]
     IMPORT_KW = ["import"],
-
-    #[token("let")]
     LET_KW = ["let"],
-
-    #[token("opaque")]
     OPAQUE_KW = ["opaque"],
-
-    #[token("panic")]
     PANIC_KW = ["panic"],
-
-    #[token("pub")]
     PUB_KW = ["pub"],
-
-    #[token("todo")]
     TODO_KW = ["todo"],
-
-    #[token("try")]
     TRY_KW = ["try"],
-
-    #[token("type")]
     TYPE_KW = ["type"],
-
-    #[token("use")]
     USE_KW = ["use"] @KEYWORD_LAST,
 
-    #[error]
     ERROR,
 
     // Nodes
     ANNOTATION,
+    ATTRIBUTE,
     LITERAL,
     IMPORT,
     SOURCE_FILE,
     MODULE_NAME,
     MODULE_CONSTANT,
+    FUNCTION,
+    EXTERNAL_FUNCTION,
+    EXTERNAL_TYPE,
+    CUSTOM_TYPE,
+    TYPE_ALIAS,
+    TYPE_PARAM,
+    TYPE_ARG_LIST,
+    CONSTRUCTOR,
+    CONSTRUCTOR_FIELD,
+    BLOCK,
+    LET_STATEMENT,
+    USE_EXPR,
+    USE_ASSIGNMENT,
+    CALL_EXPR,
+    BINARY_EXPR,
+    UNARY_EXPR,
+    CAPTURE_HOLE,
+    FIELD_ACCESS,
+    TODO_EXPR,
+    PANIC_EXPR,
+    CASE_EXPR,
+    CASE_CLAUSE,
+    CASE_GUARD,
+    ECHO_EXPR,
+    LAMBDA,
+    VARIABLE_PATTERN,
+    DISCARD_PATTERN,
+    LITERAL_PATTERN,
+    TUPLE_PATTERN,
+    LIST_PATTERN,
+    LIST_REST,
+    CONSTRUCTOR_PATTERN,
+    PATTERN_ARG,
+    CONCAT_PATTERN,
+    ALTERNATIVE_PATTERN,
+    ARG_LIST,
+    ARG,
+    RECORD_SPREAD,
     NAME,
     PATH,
     NAME_REF,
+    QUALIFIED_REF,
     PARAM,
     PARAM_LIST,
     TARGET,
@@ -287,7 +203,9 @@ def! {
     TUPLE,
     LIST,
     RECORD,
-    BITSTRING,
+    BIT_ARRAY,
+    BIT_ARRAY_SEGMENT,
+    BIT_ARRAY_OPTION,
 
     __LAST,
 }
@@ -303,6 +221,21 @@ impl SyntaxKind {
         (Self::KEYWORD_FIRST as u16..=Self::KEYWORD_LAST as u16).contains(&(self as u16))
     }
 
+    /// Keywords which are only keywords in some places and lexed as identifiers, see
+    /// `from_soft_keyword`. Target names like `erlang` stay identifiers everywhere.
+    #[inline(always)]
+    pub fn is_soft_keyword(self) -> bool {
+        matches!(self, Self::AS_KW)
+    }
+
+    /// The soft keyword spelled `text`.
+    pub fn from_soft_keyword(text: &str) -> Option<Self> {
+        match text {
+            "as" => Some(Self::AS_KW),
+            _ => None,
+        }
+    }
+
     #[inline(always)]
     pub fn is_symbol(self) -> bool {
         (Self::SYMBOL_FIRST as u16..=Self::SYMBOL_LAST as u16).contains(&(self as u16))
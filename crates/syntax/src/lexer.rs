@@ -1,41 +1,94 @@
 use crate::kind::SyntaxKind;
-pub use logos::{Lexer, Logos};
 use rowan::{TextRange, TextSize};
+use std::borrow::Cow;
 use std::ops::Range as StdRange;
 
-pub fn lex_string(lex: &mut Lexer<SyntaxKind>) -> bool {
-    let remainder: &str = lex.remainder();
-    let mut escaped = false;
-
-    let mut total_len = 0;
-
-    for c in remainder.chars() {
-        total_len += c.len_utf8();
-
-        if c == '\\' {
-            escaped = !escaped;
+/// Decode the escapes in the contents of a string literal, without its quotes. `on_char` is
+/// called with the byte range of each char or escape sequence and its value, which is `None`
+/// for invalid escapes.
+pub fn unescape_string(contents: &str, mut on_char: impl FnMut(StdRange<usize>, Option<char>)) {
+    let mut chars = contents.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            on_char(start..start + c.len_utf8(), Some(c));
             continue;
         }
+        let value = match chars.next() {
+            Some((_, 'n')) => Some('\n'),
+            Some((_, 'r')) => Some('\r'),
+            Some((_, 't')) => Some('\t'),
+            Some((_, 'f')) => Some('\u{c}'),
+            Some((_, '"')) => Some('"'),
+            Some((_, '\\')) => Some('\\'),
+            Some((_, 'u')) if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                chars.next();
+                let mut digits = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !c.is_ascii_hexdigit() {
+                        break;
+                    }
+                    digits.push(c);
+                    chars.next();
+                }
+                let closed = chars.next_if(|&(_, c)| c == '}').is_some();
+                (closed && (1..=6).contains(&digits.len()))
+                    .then(|| u32::from_str_radix(&digits, 16).ok())
+                    .flatten()
+                    .and_then(char::from_u32)
+            }
+            _ => None,
+        };
+        let end = chars.peek().map_or(contents.len(), |&(i, _)| i);
+        on_char(start..end, value);
+    }
+}
 
-        if c == '"' && !escaped {
-            lex.bump(remainder[0..total_len].as_bytes().len());
-            return true;
-        }
+/// The value of a string literal including its quotes, or `None` if it contains invalid
+/// escapes. Only strings with escapes need to be copied.
+pub fn unescape_string_literal(text: &str) -> Option<Cow<'_, str>> {
+    let contents = text.strip_prefix('"')?.strip_suffix('"')?;
+    if !contents.contains('\\') {
+        return Some(Cow::Borrowed(contents));
+    }
+    let mut ret = String::with_capacity(contents.len());
+    let mut valid = true;
+    unescape_string(contents, |_, c| match c {
+        Some(c) => ret.push(c),
+        None => valid = false,
+    });
+    valid.then_some(Cow::Owned(ret))
+}
 
-        escaped = false;
+/// Split an integer literal into its radix and the digits after the prefix.
+pub fn split_radix(text: &str) -> (u32, &str) {
+    match text.get(..2) {
+        Some("0x" | "0X") => (16, &text[2..]),
+        Some("0o" | "0O") => (8, &text[2..]),
+        Some("0b" | "0B") => (2, &text[2..]),
+        _ => (10, text),
     }
-    false
 }
 
+/// Parse a float literal like Gleam does, returning `None` if it overflows.
+pub fn parse_float(text: &str) -> Option<f64> {
+    let value = text.replace('_', "").parse::<f64>().ok()?;
+    value.is_finite().then_some(value)
+}
+
+/// A single pass lexer over the bytes of the source, yielding slices of it.
+///
+/// All tokens are ASCII except for comments, strings and unknown chars, so these are the only
+/// places where the source has to be treated as UTF-8.
 pub struct GleamLexer<'a> {
-    inner: logos::Lexer<'a, SyntaxKind>,
+    src: &'a str,
+    pos: usize,
 }
 
 impl<'a> GleamLexer<'a> {
     pub fn new(input: &'a str) -> Self {
-        Self {
-            inner: SyntaxKind::lexer(input),
-        }
+        // Offsets are converted without checks below, since the source fits in a `TextSize`.
+        assert!(u32::try_from(input.len()).is_ok(), "source is too large");
+        Self { src: input, pos: 0 }
     }
 }
 
@@ -43,18 +96,212 @@ impl<'a> Iterator for GleamLexer<'a> {
     type Item = LexToken<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let kind = self.inner.next()?;
-        let text = self.inner.slice();
+        let bytes = self
+            .src
+            .as_bytes()
+            .get(self.pos..)
+            .filter(|b| !b.is_empty())?;
+        let (kind, len) = lex_token(bytes);
+        let start = self.pos;
+        self.pos += len;
+        debug_assert!(self.src.is_char_boundary(self.pos));
+        Some(Self::Item {
+            kind,
+            // SAFETY: Only ASCII bytes and whole chars are consumed, so tokens start and end
+            // on char boundaries.
+            text: unsafe { self.src.get_unchecked(start..self.pos) },
+            range: TextRange::at((start as u32).into(), (len as u32).into()),
+        })
+    }
+}
 
-        let range = {
-            let StdRange { start, end } = self.inner.span();
-            let start = TextSize::try_from(start).unwrap();
-            let end = TextSize::try_from(end).unwrap();
+/// The kind and length of the token at the start of `bytes`, which is not empty.
+fn lex_token(bytes: &[u8]) -> (SyntaxKind, usize) {
+    use SyntaxKind::*;
 
-            TextRange::new(start, end)
-        };
+    let at = |i: usize| bytes.get(i).copied();
+    match bytes[0] {
+        b' ' | b'\t' | b'\n' => (
+            WHITESPACE,
+            take_while(bytes, 1, |b| matches!(b, b' ' | b'\t' | b'\n')),
+        ),
+        b'a'..=b'z' => {
+            let len = take_while(bytes, 1, is_ident_byte);
+            (keyword(&bytes[..len]).unwrap_or(IDENT), len)
+        }
+        b'_' => (DISCARD_IDENT, take_while(bytes, 1, is_ident_byte)),
+        b'A'..=b'Z' => (U_IDENT, take_while(bytes, 1, |b| b.is_ascii_alphanumeric())),
+        b'0'..=b'9' => lex_number(bytes),
+        // Strings may span lines, so an unterminated one is only known at the end of input.
+        // It then becomes an error token up to the end of its first line, instead of
+        // swallowing everything after it.
+        b'"' => match string_end(&bytes[1..]) {
+            Some(end) => (STRING, end + 1),
+            None => (ERROR, take_while(bytes, 1, |b| b != b'\n')),
+        },
+        b'/' => match (at(1), at(2), at(3)) {
+            (Some(b'/'), Some(b'/'), Some(b'/')) => (COMMENT_MODULE, comment_len(bytes)),
+            (Some(b'/'), Some(b'/'), _) => (COMMENT_STATEMENT, comment_len(bytes)),
+            (Some(b'/'), ..) => (COMMENT, comment_len(bytes)),
+            (Some(b'.'), ..) => (SLASH_DOT, 2),
+            _ => (SLASH, 1),
+        },
+        b'<' => match (at(1), at(2)) {
+            (Some(b'='), Some(b'.')) => (LESS_EQ_DOT, 3),
+            (Some(b'='), _) => (LESS_EQ, 2),
+            (Some(b'.'), _) => (LESS_DOT, 2),
+            (Some(b'>'), _) => (LT_GT, 2),
+            (Some(b'<'), _) => (LT_LT, 2),
+            (Some(b'-'), _) => (L_ARROW, 2),
+            _ => (LESS, 1),
+        },
+        b'>' => match (at(1), at(2)) {
+            (Some(b'='), Some(b'.')) => (GREATER_EQ_DOT, 3),
+            (Some(b'='), _) => (GREATER_EQ, 2),
+            (Some(b'.'), _) => (GREATER_DOT, 2),
+            (Some(b'>'), _) => (GT_GT, 2),
+            _ => (GREATER, 1),
+        },
+        b'-' => match at(1) {
+            Some(b'.') => (MINUS_DOT, 2),
+            Some(b'>') => (R_ARROW, 2),
+            _ => (MINUS, 1),
+        },
+        b'+' => match at(1) {
+            Some(b'.') => (PLUS_DOT, 2),
+            _ => (PLUS, 1),
+        },
+        b'*' => match at(1) {
+            Some(b'.') => (STAR_DOT, 2),
+            _ => (STAR, 1),
+        },
+        b'=' => match at(1) {
+            Some(b'=') => (EQ_EQ, 2),
+            _ => (EQ, 1),
+        },
+        b'!' => match at(1) {
+            Some(b'=') => (NOT_EQ, 2),
+            _ => (BANG, 1),
+        },
+        b'|' => match at(1) {
+            Some(b'|') => (VBAR_VBAR, 2),
+            Some(b'>') => (PIPE, 2),
+            _ => (VBAR, 1),
+        },
+        b'&' if at(1) == Some(b'&') => (AMPER_AMPER, 2),
+        b'.' => match at(1) {
+            Some(b'.') => (DOT_DOT, 2),
+            _ => (DOT, 1),
+        },
+        b'[' => (L_SQUARE, 1),
+        b']' => (R_SQUARE, 1),
+        b'{' => (L_BRACE, 1),
+        b'}' => (R_BRACE, 1),
+        b'(' => (L_PAREN, 1),
+        b')' => (R_PAREN, 1),
+        b'%' => (PERCENT, 1),
+        b':' => (COLON, 1),
+        b',' => (COMMA, 1),
+        b'#' => (HASH, 1),
+        b'@' => (AT, 1),
+        // A whole char, so that tokens stay on char boundaries.
+        b => (ERROR, utf8_len(b)),
+    }
+}
+
+/// Keywords are looked up once the whole identifier is known, instead of being matched while
+/// lexing it. Soft keywords like `as` are left to the parser, so that they remain usable as
+/// names.
+fn keyword(ident: &[u8]) -> Option<SyntaxKind> {
+    use SyntaxKind::*;
+
+    let kind = match ident {
+        b"assert" => ASSERT_KW,
+        b"case" => CASE_KW,
+        b"const" => CONST_KW,
+        b"echo" => ECHO_KW,
+        b"external" => EXTERNAL_KW,
+        b"fn" => FN_KW,
+        b"if" => IF_KW,
+        b"import" => IMPORT_KW,
+        b"let" => LET_KW,
+        b"opaque" => OPAQUE_KW,
+        b"panic" => PANIC_KW,
+        b"pub" => PUB_KW,
+        b"todo" => TODO_KW,
+        b"try" => TRY_KW,
+        b"type" => TYPE_KW,
+        b"use" => USE_KW,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Integers and floats like `1_000`, `1.5e-3` or `2.`.
+///
+/// Prefixed integers take all alphanumerics, so invalid digits are reported by the parser
+/// instead of silently starting another token.
+fn lex_number(bytes: &[u8]) -> (SyntaxKind, usize) {
+    let is_digit = |b: u8| b.is_ascii_digit() || b == b'_';
+    if bytes[0] == b'0' && matches!(bytes.get(1), Some(b'x' | b'X' | b'o' | b'O' | b'b' | b'B')) {
+        let len = take_while(bytes, 2, |b| b.is_ascii_alphanumeric() || b == b'_');
+        return (SyntaxKind::INTEGER, len);
+    }
+
+    let int_end = take_while(bytes, 1, is_digit);
+    if bytes.get(int_end) != Some(&b'.') {
+        return (SyntaxKind::INTEGER, int_end);
+    }
+    let mut end = take_while(bytes, int_end + 1, is_digit);
+    // The exponent is only part of the float if it has digits, as in `1.0e3`.
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let mut digits = end + 1;
+        if matches!(bytes.get(digits), Some(b'+' | b'-')) {
+            digits += 1;
+        }
+        let exp_end = take_while(bytes, digits, is_digit);
+        if exp_end > digits {
+            end = exp_end;
+        }
+    }
+    (SyntaxKind::FLOAT, end)
+}
+
+/// The length of a comment, which ends before the line break.
+fn comment_len(bytes: &[u8]) -> usize {
+    let is_break = |b: u8| matches!(b, b'\n' | b'\r');
+    let mut i = 2;
+    // Comments are mostly long, so whole chunks are checked first, without branching on
+    // every byte.
+    while let Some(chunk) = bytes.get(i..i + 8) {
+        if chunk.iter().fold(false, |any, &b| any | is_break(b)) {
+            break;
+        }
+        i += 8;
+    }
+    take_while(bytes, i, |b| !is_break(b))
+}
 
-        Some(Self::Item { kind, text, range })
+fn is_ident_byte(b: u8) -> bool {
+    matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_')
+}
+
+/// The end of the prefix of `bytes` from `start` whose bytes all satisfy `f`.
+fn take_while(bytes: &[u8], start: usize, f: impl Fn(u8) -> bool) -> usize {
+    let mut i = start;
+    while i < bytes.len() && f(bytes[i]) {
+        i += 1;
+    }
+    i
+}
+
+/// The length of the UTF-8 encoded char starting with `first`.
+fn utf8_len(first: u8) -> usize {
+    match first {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
     }
 }
 
@@ -65,6 +312,80 @@ pub struct LexToken<'a> {
     pub range: TextRange,
 }
 
+/// All tokens of `src` including whitespace and comments, without building a tree.
+///
+/// The tokens cover all of `src`, anything unknown becomes an `ERROR` token.
+pub fn tokenize(src: &str) -> impl Iterator<Item = (SyntaxKind, TextRange)> + '_ {
+    GleamLexer::new(src).map(|tok| (tok.kind, tok.range))
+}
+
+/// Where a line starts, which is inside a string literal if one spans the previous line break.
+/// No other token can span lines.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineState {
+    #[default]
+    Code,
+    InString,
+}
+
+/// Lex a single line given the state at its start, eg. to update the highlighting of a
+/// changed line without lexing the whole file. Returns the tokens with ranges relative to
+/// the line and the state at the start of the next line.
+///
+/// A string which is not closed on the line is assumed to continue on the next one, so its
+/// parts on each line are all `STRING` tokens.
+pub fn lex_line(line: &str, state: LineState) -> (Vec<(SyntaxKind, TextRange)>, LineState) {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    if state == LineState::InString {
+        match string_end(line.as_bytes()) {
+            Some(end) => {
+                tokens.push((SyntaxKind::STRING, range(0, end)));
+                start = end;
+            }
+            None => {
+                tokens.push((SyntaxKind::STRING, range(0, line.len())));
+                return (tokens, LineState::InString);
+            }
+        }
+    }
+
+    let mut next_state = LineState::Code;
+    for (kind, r) in tokenize(&line[start..]) {
+        let r = r + TextSize::try_from(start).unwrap();
+        if kind == SyntaxKind::ERROR && line[r].starts_with('"') {
+            tokens.push((SyntaxKind::STRING, range(r.start().into(), line.len())));
+            next_state = LineState::InString;
+            break;
+        }
+        tokens.push((kind, r));
+    }
+    (tokens, next_state)
+}
+
+/// The end of a string literal in `bytes`, just after its closing quote.
+///
+/// Works on raw bytes: both `"` and `\\` are ASCII and can never appear inside
+/// a multi-byte UTF-8 sequence, so there is no need to decode chars.
+fn string_end(bytes: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while let Some(&b) = bytes.get(i) {
+        match b {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn range(start: usize, end: usize) -> TextRange {
+    TextRange::new(
+        TextSize::try_from(start).unwrap(),
+        TextSize::try_from(end).unwrap(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,7 +405,7 @@ mod tests {
     fn it_works() {
         let _blu = 0b1;
         const INPUT: &str = "Lexers.";
-        let lex = SyntaxKind::lexer(INPUT);
+        let lex = GleamLexer::new(INPUT).map(|tok| tok.kind);
 
         println!(
             "{:?}",
@@ -102,16 +423,197 @@ mod tests {
     #[test]
     fn string() {
         check_lex(
-            "\"abc 1 A",
+            "\"abc 1\nA",
             expect![[r#"
-            ERROR "\""
-            IDENT "abc"
-            WHITESPACE " "
-            INTEGER "1"
+            ERROR "\"abc 1"
+            WHITESPACE "\n"
+            U_IDENT "A"
         "#]],
         )
     }
 
+    #[test]
+    fn string_escapes() {
+        check_lex(
+            r#""a\"b\\" "ß\ℝ" x"#,
+            expect![[r#"
+                STRING "\"a\\\"b\\\\\""
+                WHITESPACE " "
+                STRING "\"ß\\ℝ\""
+                WHITESPACE " "
+                IDENT "x"
+            "#]],
+        )
+    }
+
+    #[test]
+    fn numbers() {
+        check_lex(
+            "1_000 0x1F4 0o17 0b1010 1.5 2. 1.0e-3",
+            expect![[r#"
+                INTEGER "1_000"
+                WHITESPACE " "
+                INTEGER "0x1F4"
+                WHITESPACE " "
+                INTEGER "0o17"
+                WHITESPACE " "
+                INTEGER "0b1010"
+                WHITESPACE " "
+                FLOAT "1.5"
+                WHITESPACE " "
+                FLOAT "2."
+                WHITESPACE " "
+                FLOAT "1.0e-3"
+            "#]],
+        )
+    }
+
+    #[test]
+    fn operators() {
+        check_lex(
+            "a|>b<>c<-d..e<=.f>=.g+.h-><<1>>||!===",
+            expect![[r#"
+                IDENT "a"
+                PIPE "|>"
+                IDENT "b"
+                LT_GT "<>"
+                IDENT "c"
+                L_ARROW "<-"
+                IDENT "d"
+                DOT_DOT ".."
+                IDENT "e"
+                LESS_EQ_DOT "<=."
+                IDENT "f"
+                GREATER_EQ_DOT ">=."
+                IDENT "g"
+                PLUS_DOT "+."
+                IDENT "h"
+                R_ARROW "->"
+                LT_LT "<<"
+                INTEGER "1"
+                GT_GT ">>"
+                VBAR_VBAR "||"
+                NOT_EQ "!="
+                EQ_EQ "=="
+            "#]],
+        )
+    }
+
+    #[test]
+    fn keywords() {
+        check_lex(
+            "fn fnx as _if If & ß\r",
+            expect![[r#"
+                FN_KW "fn"
+                WHITESPACE " "
+                IDENT "fnx"
+                WHITESPACE " "
+                IDENT "as"
+                WHITESPACE " "
+                DISCARD_IDENT "_if"
+                WHITESPACE " "
+                U_IDENT "If"
+                WHITESPACE " "
+                ERROR "&"
+                WHITESPACE " "
+                ERROR "ß"
+                ERROR "\r"
+            "#]],
+        )
+    }
+
+    #[test]
+    fn invalid_digits() {
+        check_lex(
+            "0b102 0xG 0o",
+            expect![[r#"
+                INTEGER "0b102"
+                WHITESPACE " "
+                INTEGER "0xG"
+                WHITESPACE " "
+                INTEGER "0o"
+            "#]],
+        )
+    }
+
+    #[test]
+    fn unescape() {
+        let value = |text: &str| unescape_string_literal(text).map(|it| it.into_owned());
+        assert_eq!(value(r#""abc""#).as_deref(), Some("abc"));
+        assert_eq!(value(r#""a\n\t\"\\b""#).as_deref(), Some("a\n\t\"\\b"));
+        assert_eq!(value(r#""\u{1F600}\u{e9}""#).as_deref(), Some("😀é"));
+        assert_eq!(value(r#""\q""#), None);
+        assert!(matches!(
+            unescape_string_literal(r#""ß""#),
+            Some(Cow::Borrowed("ß"))
+        ));
+
+        let invalid = |contents: &str| {
+            let mut ranges = Vec::new();
+            unescape_string(contents, |range, c| {
+                if c.is_none() {
+                    ranges.push(range);
+                }
+            });
+            ranges
+        };
+        assert_eq!(
+            invalid(r"a\qb\u{}c\u{110000}\u{1234567}\u{12"),
+            [1..3, 4..8, 9..19, 19..30, 30..35]
+        );
+        assert_eq!(invalid(r"\q\"), [0..2, 2..3]);
+    }
+
+    #[test]
+    fn tokens_cover_input() {
+        let src = "fn f() { // hi\n  \"a\" <> ` }";
+        let mut end = TextSize::from(0);
+        for (_, range) in tokenize(src) {
+            assert_eq!(range.start(), end);
+            end = range.end();
+        }
+        assert_eq!(end, TextSize::of(src));
+    }
+
+    #[test]
+    fn lines() {
+        let check = |src: &str, expect: Expect| {
+            let mut state = LineState::Code;
+            let mut out = String::new();
+            for line in src.split_inclusive('\n') {
+                let (tokens, next) = lex_line(line, state);
+                for (kind, range) in tokens {
+                    out += &format!("{kind:?} {:?}\n", &line[range]);
+                }
+                out += &format!("-- {next:?}\n");
+                state = next;
+            }
+            expect.assert_eq(&out);
+        };
+        check(
+            "let a = \"x\n\\\"y\" <> b\nc",
+            expect![[r#"
+                LET_KW "let"
+                WHITESPACE " "
+                IDENT "a"
+                WHITESPACE " "
+                EQ "="
+                WHITESPACE " "
+                STRING "\"x\n"
+                -- InString
+                STRING "\\\"y\""
+                WHITESPACE " "
+                LT_GT "<>"
+                WHITESPACE " "
+                IDENT "b"
+                WHITESPACE "\n"
+                -- Code
+                IDENT "c"
+                -- Code
+            "#]],
+        );
+    }
+
     #[test]
     fn target_group() {
         check_lex(
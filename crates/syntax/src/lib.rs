@@ -1,16 +1,24 @@
 #[macro_use]
 mod kind;
 
+pub mod algo;
 pub mod ast;
+pub mod comments;
 pub mod lexer;
+mod line_index;
 pub mod parser;
+mod ptr;
+mod reparsing;
+mod token_set;
+mod validation;
 
 use core::fmt;
+use smol_str::SmolStr;
 
 #[cfg(test)]
 mod tests;
 
-pub use rowan::{self, NodeOrToken, TextRange, TextSize};
+pub use rowan::{self, NodeCache, NodeOrToken, TextRange, TextSize};
 
 pub type SyntaxNode = rowan::SyntaxNode<GleamLanguage>;
 pub type SyntaxToken = rowan::SyntaxToken<GleamLanguage>;
@@ -18,25 +26,32 @@ pub type SyntaxElement = rowan::SyntaxElement<GleamLanguage>;
 pub type SyntaxNodeChildren = rowan::SyntaxNodeChildren<GleamLanguage>;
 pub type SyntaxElementChildren = rowan::SyntaxElementChildren<GleamLanguage>;
 pub type PreorderWithTokens = rowan::api::PreorderWithTokens<GleamLanguage>;
-pub type SyntaxNodePtr = rowan::ast::SyntaxNodePtr<GleamLanguage>;
 
 pub use self::kind::SyntaxKind;
-pub use self::parser::{parse_file, Parse};
+pub use self::line_index::{LineCol, LineColUtf16, LineIndex};
+pub use self::parser::{
+    parse_file, parse_file_with_cache, parse_file_with_version, LanguageVersion, Parse,
+};
+pub use self::ptr::{AstPtr, SyntaxNodePtr};
+pub use self::token_set::TokenSet;
 
 pub fn whatever() {
     println!(
         "{}",
-        Error {
+        ParseError {
             range: TextRange::new(1.into(), 2.into()),
-            kind: ErrorKind::NestTooDeep
+            kind: ErrorKind::NestTooDeep,
+            expected: TokenSet::EMPTY,
         }
     )
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Error {
+pub struct ParseError {
     pub range: TextRange,
     pub kind: ErrorKind,
+    /// The tokens which would have been accepted at `range`, if known.
+    pub expected: TokenSet,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -45,31 +60,75 @@ pub enum ErrorKind {
     UnexpectedEof,
     ExpectToken(SyntaxKind),
     ExpectedTarget,
+    ExpectedBitArrayOption,
     ExpectedConstantExpression,
+    ExpectedConstructor,
+    ExpectedElement,
+    ExpectedExpression,
+    ExpectedPattern,
     ExpectedStatement,
     ExpectedType,
     ExpectedIdentifier,
     UnexpectedImport,
+    UnterminatedString,
+    UnsupportedSyntax(LanguageVersion),
+    InvalidGuardExpression,
+    NonConstantExpression,
+    ReservedWord,
+    InvalidDigit,
+    FloatOverflow,
+    InvalidEscape,
+    SpreadNotLast,
+    RecordSpreadNotFirst,
+    DuplicateAlias,
+    DuplicateBitArrayOption,
+    ConflictingEndianness,
+    SizedUtfSegment,
+    UnsizedSegmentNotLast,
 }
 
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::NestTooDeep => "Nest too deep",
+            Self::NestTooDeep => "Expression too deeply nested",
             Self::UnexpectedEof => "Did not expect end of file.",
             Self::ExpectToken(tok) => return write!(f, "Expecting {}", tok),
             Self::ExpectedTarget => "Expected target javascript or erlang",
             Self::ExpectedIdentifier => "Expected an identifier",
+            Self::ExpectedBitArrayOption => "Expected bit array option",
             Self::ExpectedConstantExpression => "Expected constant expression",
+            Self::ExpectedConstructor => "Expected constructor",
+            Self::ExpectedElement => "Expected element",
+            Self::ExpectedExpression => "Expected expression",
+            Self::ExpectedPattern => "Expected pattern",
             Self::ExpectedStatement => "Expected statement",
             Self::ExpectedType => "Expected type",
             Self::UnexpectedImport => "Did not expect an import here",
+            Self::UnterminatedString => "Unterminated string",
+            Self::UnsupportedSyntax(version) => {
+                return write!(f, "This syntax requires Gleam v{}", version)
+            }
+            Self::InvalidGuardExpression => "Expression not allowed in guard",
+            Self::NonConstantExpression => "Expression not allowed in constant",
+            Self::ReservedWord => "This is a reserved word",
+            Self::InvalidDigit => "Invalid digit for the base of this integer",
+            Self::FloatOverflow => "Float is too large",
+            Self::InvalidEscape => "Invalid escape sequence",
+            Self::SpreadNotLast => "The spread must be the last element",
+            Self::RecordSpreadNotFirst => "The record to update must come before the fields",
+            Self::DuplicateAlias => "Only one alias is allowed",
+            Self::DuplicateBitArrayOption => "Duplicate segment option",
+            Self::ConflictingEndianness => "Conflicting endianness options",
+            Self::SizedUtfSegment => "UTF segments cannot have a size",
+            Self::UnsizedSegmentNotLast => {
+                "A bytes or bits segment without a size must be the last one"
+            }
         }
         .fmt(f)
     }
 }
 
-impl fmt::Display for Error {
+impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
@@ -83,6 +142,19 @@ impl fmt::Display for Error {
 
 impl std::error::Error for ErrorKind {}
 
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TextEdit {
+    pub delete: TextRange,
+    pub insert: SmolStr,
+}
+
+impl TextEdit {
+    pub fn apply(&self, src: &mut String) {
+        let delete = usize::from(self.delete.start())..usize::from(self.delete.end());
+        src.replace_range(delete, &self.insert);
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GleamLanguage {}
 
@@ -0,0 +1,262 @@
+//! Conversions between byte offsets, as used by the syntax tree, and line/column pairs, as
+//! used by editors. The LSP counts columns in UTF-16 code units by default.
+use crate::{TextRange, TextSize};
+
+/// A zero based line and byte column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: u32,
+    pub col: u32,
+}
+
+/// A zero based line and column in UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColUtf16 {
+    pub line: u32,
+    pub col: u32,
+}
+
+/// The line starts and non-ASCII characters of a text. Only `\n` starts a new line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Invariant: Sorted, and the first is always 0.
+    line_starts: Vec<TextSize>,
+    /// Sorted by offset. ASCII characters are the same in all encodings, so they are skipped.
+    wide_chars: Vec<WideChar>,
+    len: TextSize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+    offset: TextSize,
+    len_utf8: u8,
+}
+
+impl WideChar {
+    /// How many more bytes it takes in UTF-8 than code units in UTF-16.
+    fn extra_len(self) -> u32 {
+        match self.len_utf8 {
+            4 => 2,
+            len => len as u32 - 1,
+        }
+    }
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut this = Self {
+            line_starts: vec![0.into()],
+            wide_chars: Vec::new(),
+            len: 0.into(),
+        };
+        this.edit(TextRange::empty(0.into()), text);
+        this
+    }
+
+    /// The length of the text.
+    pub fn len(&self) -> TextSize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0.into()
+    }
+
+    /// The number of lines, which is one more than the number of `\n`.
+    pub fn line_count(&self) -> u32 {
+        self.line_starts.len() as u32
+    }
+
+    /// The range of `line` without its trailing `\n`.
+    pub fn line_range(&self, line: u32) -> Option<TextRange> {
+        let start = *self.line_starts.get(line as usize)?;
+        let end = match self.line_starts.get(line as usize + 1) {
+            Some(&next) => next - TextSize::from(1),
+            None => self.len,
+        };
+        Some(TextRange::new(start, end))
+    }
+
+    /// The line and column of `offset`, which is clamped to the end of the text.
+    pub fn line_col(&self, offset: TextSize) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        LineCol {
+            line: line as u32,
+            col: (offset - self.line_starts[line]).into(),
+        }
+    }
+
+    /// The offset of `pos`, or `None` if its line is out of range. Columns past the end of the
+    /// line are clamped to it.
+    pub fn offset(&self, pos: LineCol) -> Option<TextSize> {
+        let range = self.line_range(pos.line)?;
+        Some((range.start() + TextSize::from(pos.col)).min(range.end()))
+    }
+
+    pub fn to_utf16(&self, pos: LineCol) -> LineColUtf16 {
+        let col = self
+            .wide_chars_in_line(pos.line)
+            .iter()
+            .take_while(|c| u32::from(c.offset) < self.line_start(pos.line) + pos.col)
+            .fold(pos.col, |col, &c| col - c.extra_len());
+        LineColUtf16 {
+            line: pos.line,
+            col,
+        }
+    }
+
+    pub fn to_utf8(&self, pos: LineColUtf16) -> LineCol {
+        let line_start = self.line_start(pos.line);
+        let mut col = pos.col;
+        for &c in self.wide_chars_in_line(pos.line) {
+            // `col` is in bytes up to here, as is the column of `c`.
+            if u32::from(c.offset) - line_start >= col {
+                break;
+            }
+            col += c.extra_len();
+        }
+        LineCol {
+            line: pos.line,
+            col,
+        }
+    }
+
+    /// Update the index for replacing `delete` with `insert`, as if it was rebuilt from the
+    /// new text. `delete` must be on character boundaries.
+    pub fn edit(&mut self, delete: TextRange, insert: &str) {
+        let (start, end) = (delete.start(), delete.end());
+        let shift = |offset: TextSize| offset - end + start + TextSize::of(insert);
+
+        // A line start after a deleted `\n` goes away with it.
+        let lo = self.line_starts.partition_point(|&s| s <= start);
+        let hi = self.line_starts.partition_point(|&s| s <= end);
+        let inserted = insert
+            .match_indices('\n')
+            .map(|(i, _)| start + TextSize::from(i as u32 + 1))
+            .collect::<Vec<_>>();
+        let tail = lo + inserted.len();
+        self.line_starts.splice(lo..hi, inserted);
+        for s in &mut self.line_starts[tail..] {
+            *s = shift(*s);
+        }
+
+        let lo = self.wide_chars.partition_point(|c| c.offset < start);
+        let hi = self.wide_chars.partition_point(|c| c.offset < end);
+        let inserted = insert
+            .char_indices()
+            .filter(|(_, c)| !c.is_ascii())
+            .map(|(i, c)| WideChar {
+                offset: start + TextSize::from(i as u32),
+                len_utf8: c.len_utf8() as u8,
+            })
+            .collect::<Vec<_>>();
+        let tail = lo + inserted.len();
+        self.wide_chars.splice(lo..hi, inserted);
+        for c in &mut self.wide_chars[tail..] {
+            c.offset = shift(c.offset);
+        }
+
+        self.len = shift(self.len);
+    }
+
+    fn line_start(&self, line: u32) -> u32 {
+        self.line_starts
+            .get(line as usize)
+            .map_or(self.len, |&s| s)
+            .into()
+    }
+
+    fn wide_chars_in_line(&self, line: u32) -> &[WideChar] {
+        let Some(range) = self.line_range(line) else {
+            return &[];
+        };
+        let lo = self
+            .wide_chars
+            .partition_point(|c| c.offset < range.start());
+        let hi = self.wide_chars.partition_point(|c| c.offset < range.end());
+        &self.wide_chars[lo..hi]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineCol, LineColUtf16, LineIndex};
+    use crate::{TextRange, TextSize};
+
+    #[test]
+    fn ascii() {
+        let index = LineIndex::new("hello\nworld\nend");
+        assert_eq!(index.line_count(), 3);
+        let mapping = [
+            (0, 0, 0),
+            (5, 0, 5),
+            (6, 1, 0),
+            (11, 1, 5),
+            (12, 2, 0),
+            (15, 2, 3),
+        ];
+        for (offset, line, col) in mapping {
+            let pos = LineCol { line, col };
+            assert_eq!(index.line_col(offset.into()), pos);
+            assert_eq!(index.offset(pos), Some(offset.into()));
+            assert_eq!(index.to_utf16(pos), LineColUtf16 { line, col });
+        }
+        assert_eq!(index.offset(LineCol { line: 0, col: 9 }), Some(5.into()));
+        assert_eq!(index.offset(LineCol { line: 3, col: 0 }), None);
+        assert_eq!(
+            index.line_range(1),
+            Some(TextRange::new(6.into(), 11.into()))
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        //    |         | UTF-8       | UTF-16
+        // A  | U+00041 | 41          | 0041
+        // ß  | U+000DF | C3 9F       | 00DF
+        // ℝ  | U+0211D | E2 84 9D    | 211D
+        // 💣 | U+1F4A3 | F0 9F 92 A3 | D83D DCA3
+        let index = LineIndex::new("x\n_A_ß_ℝ_💣_");
+        let mapping = [
+            (0, 0),
+            (3, 3),
+            (5, 4),
+            (6, 5),
+            (9, 6),
+            (10, 7),
+            (14, 9),
+            (15, 10),
+        ];
+        for (col, col16) in mapping {
+            let pos = LineCol { line: 1, col };
+            let pos16 = LineColUtf16 {
+                line: 1,
+                col: col16,
+            };
+            assert_eq!(index.to_utf16(pos), pos16);
+            assert_eq!(index.to_utf8(pos16), pos);
+        }
+    }
+
+    #[test]
+    fn edit() {
+        let edits = [
+            (0, 0, "ß\n"),
+            (3, 3, "héllo\nwörld💣\n"),
+            (4, 10, ""),
+            (0, 5, "a\n\nb"),
+            (4, 8, "ℝ"),
+            (2, 4, "\n"),
+        ];
+        let mut text = String::from("fn main() {\n  1\n}");
+        let mut index = LineIndex::new(&text);
+        for (start, end, insert) in edits {
+            let delete = TextRange::new(start.into(), end.into());
+            text.replace_range(start as usize..end as usize, insert);
+            index.edit(delete, insert);
+            assert_eq!(index, LineIndex::new(&text), "{text:?}");
+            assert_eq!(index.len(), TextSize::of(&*text));
+        }
+    }
+}
@@ -1,16 +1,19 @@
-use crate::ast::{AstNode, SourceFile};
-use crate::lexer::{GleamLexer, LexToken};
+use crate::ast::{Associativity, AstNode, BinaryOpKind, SourceFile, UnaryOpKind};
+use crate::lexer::{parse_float, split_radix, unescape_string, GleamLexer, LexToken};
 use crate::SyntaxKind::{self, *};
-use crate::{Error, ErrorKind, SyntaxNode};
-use rowan::{Checkpoint, GreenNode, GreenNodeBuilder, TextRange, TextSize};
+use crate::{validation, ErrorKind, ParseError, SyntaxNode, TokenSet};
+use rowan::{
+    Checkpoint, GreenNode, GreenNodeBuilder, NodeCache, NodeOrToken, TextRange, TextSize, WalkEvent,
+};
+use std::fmt;
 
 const MAX_STEPS: usize = 100_000_000;
-const MAX_DEPTHS: usize = 500;
+pub(crate) const MAX_DEPTHS: usize = 500;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Parse {
-    green: GreenNode,
-    errors: Vec<Error>,
+    pub(crate) green: GreenNode,
+    pub(crate) errors: Vec<ParseError>,
 }
 
 impl Parse {
@@ -26,47 +29,193 @@ impl Parse {
         SyntaxNode::new_root(self.green.clone())
     }
 
-    pub fn errors(&self) -> &[Error] {
+    pub fn errors(&self) -> &[ParseError] {
         &self.errors
     }
+
+    /// An indented dump of all nodes and tokens with their kinds and ranges, for snapshot
+    /// tests. Each error is shown before the first element starting at or after it.
+    pub fn debug_dump(&self) -> String {
+        use std::fmt::Write;
+
+        let mut errors = self.errors.clone();
+        errors.sort_by_key(|err| err.range.start());
+        let mut errors = errors.into_iter().peekable();
+        let mut out = String::new();
+        let mut depth = 0;
+        for event in self.syntax_node().preorder_with_tokens() {
+            let elem = match event {
+                WalkEvent::Enter(elem) => elem,
+                WalkEvent::Leave(_) => {
+                    depth -= 1;
+                    continue;
+                }
+            };
+            let indent = "  ".repeat(depth);
+            depth += 1;
+            while let Some(err) =
+                errors.next_if(|err| err.range.start() <= elem.text_range().start())
+            {
+                writeln!(out, "{indent}error@{:?}: {:?}", err.range, err.kind).unwrap();
+            }
+            match elem {
+                NodeOrToken::Node(node) => {
+                    writeln!(out, "{indent}{:?}@{:?}", node.kind(), node.text_range())
+                }
+                NodeOrToken::Token(tok) => writeln!(
+                    out,
+                    "{indent}{:?}@{:?} {:?}",
+                    tok.kind(),
+                    tok.text_range(),
+                    tok.text()
+                ),
+            }
+            .unwrap();
+        }
+        for err in errors {
+            writeln!(out, "error@{:?}: {:?}", err.range, err.kind).unwrap();
+        }
+        out
+    }
+}
+
+/// A Gleam version, deciding which syntax is accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LanguageVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl LanguageVersion {
+    /// `echo` expressions.
+    pub const ECHO: Self = Self::new(1, 9);
+    pub const LATEST: Self = Self::ECHO;
+
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl Default for LanguageVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
 }
 
 pub fn parse_file(src: &str) -> Parse {
+    parse_file_with_version(src, LanguageVersion::LATEST)
+}
+
+/// Same as `parse_file`, but reports syntax newer than `version`.
+pub fn parse_file_with_version(src: &str, version: LanguageVersion) -> Parse {
+    parse_file_with_builder(src, GreenNodeBuilder::new(), version)
+}
+
+/// Same as `parse_file_with_version`, but deduplicates green nodes and tokens through `cache`.
+///
+/// Sharing one cache across many files (eg. when indexing dependencies) lets identical
+/// subtrees like common import lines be allocated only once.
+pub fn parse_file_with_cache(src: &str, version: LanguageVersion, cache: &mut NodeCache) -> Parse {
+    parse_file_with_builder(src, GreenNodeBuilder::with_cache(cache), version)
+}
+
+fn parse_file_with_builder(
+    src: &str,
+    builder: GreenNodeBuilder<'_>,
+    version: LanguageVersion,
+) -> Parse {
     assert!(src.len() < u32::MAX as usize);
     let mut tokens: Vec<_> = GleamLexer::new(src).collect();
     tokens.reverse();
     let mut p = Parser {
         tokens,
-        builder: GreenNodeBuilder::default(),
+        builder,
         errors: Vec::new(),
         src,
         steps: 0,
         depth: 0,
+        in_guard: false,
+        version,
     };
     parse_module(&mut p);
-    Parse {
-        green: p.builder.finish(),
-        errors: p.errors,
+    let green = p.builder.finish();
+    let mut errors = p.errors;
+    validation::validate(&SyntaxNode::new_root(green.clone()), &mut errors);
+    Parse { green, errors }
+}
+
+/// Parse `src` as a single block, or `None` if it does not start with one or anything is left
+/// after it.
+pub(crate) fn parse_block_fragment(
+    src: &str,
+    version: LanguageVersion,
+) -> Option<(GreenNode, Vec<ParseError>)> {
+    let mut tokens: Vec<_> = GleamLexer::new(src).collect();
+    tokens.reverse();
+    if tokens.last()?.kind != T!["{"] {
+        return None;
     }
+    let mut p = Parser {
+        tokens,
+        builder: GreenNodeBuilder::new(),
+        errors: Vec::new(),
+        src,
+        steps: 0,
+        depth: 0,
+        in_guard: false,
+        version,
+    };
+    parse_block(&mut p);
+    if !p.tokens.is_empty() {
+        return None;
+    }
+    Some((p.builder.finish(), p.errors))
 }
 
-struct Parser<'i> {
+struct Parser<'i, 'c> {
     tokens: Vec<LexToken<'i>>,
-    builder: GreenNodeBuilder<'static>,
-    errors: Vec<Error>,
+    builder: GreenNodeBuilder<'c>,
+    errors: Vec<ParseError>,
     src: &'i str,
     steps: usize,
     depth: usize,
+    /// Whether we are inside a case guard, which only allows a subset of expressions.
+    in_guard: bool,
+    version: LanguageVersion,
 }
 
-impl<'i> Parser<'i> {
+impl<'i, 'c> Parser<'i, 'c> {
     fn error(&mut self, kind: ErrorKind) {
-        let range = self
-            .tokens
-            .last()
-            .map(|&LexToken { range, .. }| range)
-            .unwrap_or_else(|| TextRange::empty(TextSize::from(self.src.len() as u32)));
-        self.errors.push(Error { range, kind });
+        let expected = match kind {
+            ErrorKind::ExpectToken(tok) => TokenSet::new(&[tok]),
+            _ => TokenSet::EMPTY,
+        };
+        self.error_expected(kind, expected);
+    }
+
+    fn error_expected(&mut self, kind: ErrorKind, expected: TokenSet) {
+        let (range, kind) = match self.tokens.last() {
+            // Whatever was expected, the actual problem is the missing quote.
+            Some(tok) if tok.kind == ERROR && tok.text.starts_with('"') => {
+                (tok.range, ErrorKind::UnterminatedString)
+            }
+            Some(tok) => (tok.range, kind),
+            None => (
+                TextRange::empty(TextSize::from(self.src.len() as u32)),
+                kind,
+            ),
+        };
+        self.errors.push(ParseError {
+            range,
+            kind,
+            expected,
+        });
     }
 
     fn checkpoint(&mut self) -> Checkpoint {
@@ -87,10 +236,51 @@ impl<'i> Parser<'i> {
 
     /// Consume the next token, including whitespaces. Panic if there is no more token.
     fn bump(&mut self) {
+        match self.peek() {
+            Some(INTEGER) => self.validate_integer(),
+            Some(FLOAT) if parse_float(self.tokens.last().unwrap().text).is_none() => {
+                self.error(ErrorKind::FloatOverflow);
+            }
+            Some(STRING) => self.validate_escapes(),
+            _ => {}
+        }
         let LexToken { kind, range, .. } = self.tokens.pop().unwrap();
         self.builder.token(kind.into(), &self.src[range]);
     }
 
+    /// Report digits which are invalid for the radix of the next integer token.
+    fn validate_integer(&mut self) {
+        let text = self.tokens.last().unwrap().text;
+        let (radix, digits) = split_radix(text);
+        let valid = digits.chars().any(|c| c != '_')
+            && digits.chars().all(|c| c == '_' || c.is_digit(radix));
+        if !valid {
+            self.error(ErrorKind::InvalidDigit);
+        }
+    }
+
+    /// Report each invalid escape sequence of the next string token.
+    fn validate_escapes(&mut self) {
+        let LexToken { text, range, .. } = *self.tokens.last().unwrap();
+        // Skip the opening quote.
+        let start = range.start() + TextSize::from(1);
+        let mut invalid = Vec::new();
+        unescape_string(&text[1..text.len() - 1], |escape, c| {
+            if c.is_none() {
+                invalid.push(TextRange::new(
+                    start + TextSize::from(escape.start as u32),
+                    start + TextSize::from(escape.end as u32),
+                ));
+            }
+        });
+        self.errors
+            .extend(invalid.into_iter().map(|range| ParseError {
+                range,
+                kind: ErrorKind::InvalidEscape,
+                expected: TokenSet::EMPTY,
+            }));
+    }
+
     /// Same with `bump`, but override the kind.
     fn bump_with_kind(&mut self, kind: SyntaxKind) {
         let LexToken { range, .. } = self.tokens.pop().unwrap();
@@ -137,13 +327,26 @@ impl<'i> Parser<'i> {
 
     /// Get an iterator of following non-whitespace tokens.
     fn peek_iter_non_ws(&mut self) -> impl Iterator<Item = SyntaxKind> + '_ {
+        self.peek_iter_full_non_ws()
+            .map(|LexToken { kind, .. }| kind)
+    }
+
+    /// Like `peek_iter_non_ws`, but returns the whole tokens.
+    fn peek_iter_full_non_ws(&mut self) -> impl Iterator<Item = LexToken<'_>> + '_ {
         self.steps += 1;
         assert!(self.steps < MAX_STEPS);
         self.tokens
             .iter()
             .rev()
-            .map(|&LexToken { kind, .. }| kind)
-            .filter(|k| !k.is_whitespace())
+            .copied()
+            .filter(|tok| !tok.kind.is_whitespace())
+    }
+
+    /// Whether the next non-whitespace token is the soft keyword `kw`, which is lexed as an
+    /// identifier. It should be consumed with `bump_with_kind(kw)`.
+    fn at_soft_kw_non_ws(&mut self, kw: SyntaxKind) -> bool {
+        self.peek_full_non_ws()
+            .map_or(false, |tok| is_soft_kw(tok, kw))
     }
 
     /// Consumes all following whitespaces if any.
@@ -153,6 +356,49 @@ impl<'i> Parser<'i> {
         }
     }
 
+    /// Enter one more level of nested syntax. Return whether it should be parsed, in which case
+    /// `leave` must be called after it.
+    ///
+    /// Recursive descent would overflow the stack on input like `{{{{...}}}}`, so past
+    /// `MAX_DEPTHS` the nested syntax is reported and skipped instead.
+    fn enter(&mut self) -> bool {
+        if self.depth < MAX_DEPTHS {
+            self.depth += 1;
+            return true;
+        }
+        self.ws();
+        self.error(ErrorKind::NestTooDeep);
+        self.skip_nested();
+        false
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Wrap tokens in an ERROR node up to the end of the current element, skipping over
+    /// balanced delimiters without recursing into them.
+    fn skip_nested(&mut self) {
+        self.start_node(ERROR);
+        let mut level = 0usize;
+        while let Some(k) = self.peek() {
+            match k {
+                T!["("] | T!["["] | T!["{"] | T!["<<"] => level += 1,
+                T![")"] | T!["]"] | T!["}"] | T![">>"] => {
+                    if level == 0 {
+                        break;
+                    }
+                    level -= 1;
+                }
+                T![","] if level == 0 => break,
+                k if level == 0 && k.can_start_statement() => break,
+                _ => {}
+            }
+            self.bump();
+        }
+        self.finish_node();
+    }
+
     /// Consumes a token if the next token matches the expected one, or does nothing if not.
     /// Return whether the expected token is consumed.
     fn want(&mut self, expect: SyntaxKind) -> bool {
@@ -174,6 +420,10 @@ fn parse_module(p: &mut Parser) {
     p.finish_node();
 }
 
+fn is_soft_kw(tok: LexToken, kw: SyntaxKind) -> bool {
+    tok.kind == IDENT && SyntaxKind::from_soft_keyword(tok.text) == Some(kw)
+}
+
 const VALID_TARGETS: [&str; 2] = ["javascript", "erlang"];
 
 fn parse_target_group(p: &mut Parser) {
@@ -225,25 +475,126 @@ fn parse_statements(p: &mut Parser) {
 
 fn parse_statement(p: &mut Parser) {
     let cp = p.checkpoint();
+    let mut is_external = false;
+    while p.at_non_ws(T!["@"]) {
+        is_external |= parse_attribute(p);
+    }
     let is_pub = visibility_opt(p);
     match p.peek_non_ws() {
         Some(T!["const"]) => parse_module_const(p, cp),
+        Some(T!["fn"]) => parse_function(p, cp, is_external),
+        Some(T!["type"]) => parse_type_definition(p, cp),
+        Some(T!["opaque"]) => {
+            p.bump();
+            if p.at_non_ws(T!["type"]) {
+                parse_type_definition(p, cp);
+            } else {
+                p.error(ErrorKind::ExpectToken(T!["type"]));
+            }
+        }
+        Some(T!["external"]) => parse_external(p, cp),
         Some(T!["import"]) => {
             if is_pub {
                 p.error(ErrorKind::UnexpectedImport);
                 p.bump_error();
             } else {
-                parse_import(p);
+                parse_import(p, cp);
             }
         }
-        Some(_) => p.bump(),
+        // Only reachable after a visibility or an attribute, which need an item to apply to.
+        Some(_) => {
+            p.error(ErrorKind::ExpectedStatement);
+            p.bump_error();
+        }
         None => p.error(ErrorKind::UnexpectedEof),
     }
 }
 
-fn parse_import(p: &mut Parser) {
+/// The deprecated `external fn` and `external type` syntax.
+fn parse_external(p: &mut Parser, cp: Checkpoint) {
+    assert!(p.at(T!["external"]));
+    p.bump();
+    match p.peek_non_ws() {
+        Some(T!["fn"]) => {
+            p.start_node_at(cp, EXTERNAL_FUNCTION);
+            p.bump();
+            parse_name(p);
+            if p.peek_non_ws() == Some(T!["("]) {
+                parse_external_param_list(p);
+            } else {
+                p.error(ErrorKind::ExpectToken(T!["("]));
+            }
+            if p.want(T!["->"]) {
+                parse_type(p);
+            }
+            if p.want(T!["="]) {
+                p.want(STRING);
+                p.want(STRING);
+            }
+            p.finish_node();
+        }
+        Some(T!["type"]) => {
+            p.start_node_at(cp, EXTERNAL_TYPE);
+            p.bump();
+            parse_type_name(p);
+            if p.peek_non_ws() == Some(T!["("]) {
+                p.bump();
+                parse_comma_list(p, T![")"], |k| k == IDENT, parse_type_param);
+            }
+            p.finish_node();
+        }
+        _ => p.error(ErrorKind::ExpectToken(T!["fn"])),
+    }
+}
+
+/// Parameters of external functions are types with optional labels, as in `(label: Int)`.
+fn parse_external_param_list(p: &mut Parser) {
+    assert!(p.at(T!["("]));
+    p.start_node(PARAM_LIST);
+    p.bump();
+    parse_comma_list(p, T![")"], SyntaxKind::can_start_type, parse_external_param);
+    p.finish_node();
+}
+
+fn parse_external_param(p: &mut Parser) {
+    p.ws();
+    p.start_node(PARAM);
+    let labelled = {
+        let mut iter = p.peek_iter_non_ws();
+        iter.next() == Some(IDENT) && iter.next() == Some(T![":"])
+    };
+    if labelled {
+        parse_name(p);
+        p.want(T![":"]);
+    }
+    parse_type(p);
+    p.finish_node();
+}
+
+/// Parse an attribute like `@external(erlang, "mod", "fn")`, returning whether it is `@external`.
+fn parse_attribute(p: &mut Parser) -> bool {
+    assert!(p.at(T!["@"]));
+    p.start_node(ATTRIBUTE);
+    p.bump();
+    let is_external = p.peek_non_ws() == Some(T!["external"]);
+    // `external` is also a keyword.
+    p.start_node(NAME);
+    if is_external || p.at(IDENT) {
+        p.bump();
+    } else {
+        p.error(ErrorKind::ExpectToken(IDENT));
+    }
+    p.finish_node();
+    if p.peek_non_ws() == Some(T!["("]) {
+        parse_arg_list(p, parse_arg);
+    }
+    p.finish_node();
+    is_external
+}
+
+fn parse_import(p: &mut Parser, cp: Checkpoint) {
     assert!(p.at(T!["import"]));
-    p.start_node(IMPORT);
+    p.start_node_at(cp, IMPORT);
     p.bump();
     p.start_node(IMPORT_MODULE);
     loop {
@@ -270,11 +621,9 @@ fn parse_import(p: &mut Parser) {
         parse_unqualified_imports(p);
     }
 
-    if p.at_non_ws(T!["as"]) {
-        p.bump();
-        p.start_node(NAME);
-        p.want(IDENT);
-        p.finish_node();
+    if p.at_soft_kw_non_ws(T!["as"]) {
+        p.bump_with_kind(T!["as"]);
+        parse_name(p);
     }
 
     p.finish_node();
@@ -284,37 +633,36 @@ fn parse_import(p: &mut Parser) {
 fn parse_unqualified_imports(p: &mut Parser) {
     assert!(p.at(T!["."]));
     p.bump();
-    p.want(T!["{"]);
-    loop {
-        match p.peek_non_ws() {
-            Some(T!["}"]) => {
-                p.bump();
-                break;
-            }
-            Some(k @ (U_IDENT | IDENT)) => {
-                p.start_node(UNQUALIFIED_IMPORT);
-                p.start_node(NAME);
-                p.bump();
-                p.finish_node();
-                if p.at_non_ws(T!["as"]) {
-                    p.bump();
-                    p.start_node(NAME);
-                    p.want(k);
-                    p.finish_node();
-                }
-                p.finish_node();
-                continue;
-            }
-            Some(T![","]) => {
-                p.bump();
-                continue;
-            }
-            _ => {
-                p.error(ErrorKind::ExpectToken(T!["}"]));
-                break;
-            }
-        }
+    if p.want(T!["{"]) {
+        parse_comma_list(
+            p,
+            T!["}"],
+            |k| matches!(k, U_IDENT | IDENT),
+            parse_unqualified_import,
+        );
+    }
+}
+
+fn parse_unqualified_import(p: &mut Parser) {
+    p.ws();
+    let Some(k) = p.peek() else {
+        return;
+    };
+    p.start_node(UNQUALIFIED_IMPORT);
+    p.start_node(NAME);
+    p.bump();
+    p.finish_node();
+    // Values and constructors can only be renamed to a name of the same case. Extra aliases
+    // are reported by validation.
+    while p.at_soft_kw_non_ws(T!["as"]) {
+        p.bump_with_kind(T!["as"]);
+        p.ws();
+        p.start_node(NAME);
+        check_reserved_word(p);
+        p.want(k);
+        p.finish_node();
     }
+    p.finish_node();
 }
 
 fn parse_module_const(p: &mut Parser, cp: Checkpoint) {
@@ -329,86 +677,967 @@ fn parse_module_const(p: &mut Parser, cp: Checkpoint) {
 }
 
 fn parse_constant_value(p: &mut Parser) {
+    if !p.enter() {
+        return;
+    }
+    p.ws();
+    let cp = p.checkpoint();
+    parse_constant_atom(p);
+    // Strings are the only values which can be combined in constants. Trivia is only
+    // consumed before an operator, or it would end up in the constant.
+    loop {
+        let Some(k) = p.peek_iter_non_ws().next() else {
+            break;
+        };
+        if infix_binding_power(k).is_none() {
+            break;
+        }
+        p.ws();
+        if k != T!["<>"] {
+            p.error(ErrorKind::NonConstantExpression);
+        }
+        p.start_node_at(cp, BINARY_EXPR);
+        p.bump();
+        parse_constant_atom(p);
+        p.finish_node();
+    }
+    p.leave();
+}
+
+fn parse_constant_atom(p: &mut Parser) {
     match p.peek_non_ws() {
         Some(INTEGER | STRING | FLOAT) => {
             p.start_node(LITERAL);
             p.bump();
             p.finish_node()
         }
-        Some(HASH) => parse_tuple(p),
-        Some(IDENT) => {
-            p.start_node(NAME_REF);
+        Some(T!["#"]) => parse_tuple(
+            p,
+            TUPLE,
+            SyntaxKind::can_start_constant_expr,
+            parse_constant_value,
+        ),
+        Some(T!["["]) => parse_list(
+            p,
+            LIST,
+            SyntaxKind::can_start_constant_expr,
+            parse_constant_value,
+        ),
+        Some(IDENT | U_IDENT) => {
+            let cp = p.checkpoint();
+            let is_record = p.at(U_IDENT) || at_qualified_ref(p, |k| k == U_IDENT);
+            if at_qualified_ref(p, |k| matches!(k, IDENT | U_IDENT)) {
+                parse_qualified_ref(p);
+            } else {
+                p.start_node(NAME_REF);
+                p.bump();
+                p.finish_node();
+            }
+            // Records can be constructed, but functions can only be referenced.
+            if p.peek_non_ws() == Some(T!["("]) {
+                if !is_record {
+                    p.error(ErrorKind::NonConstantExpression);
+                }
+                p.start_node_at(cp, CALL_EXPR);
+                parse_arg_list(p, parse_constant_arg);
+                p.finish_node();
+            }
+        }
+        Some(T!["<<"]) => parse_bit_array(p, SyntaxKind::can_start_constant_expr, |p| {
+            parse_bit_array_segment(p, parse_constant_value)
+        }),
+        // Only numbers can be negated in constants.
+        Some(T!["-"]) => {
+            p.start_node(UNARY_EXPR);
             p.bump();
+            p.start_node(LITERAL);
+            if matches!(p.peek(), Some(INTEGER | FLOAT)) {
+                p.bump();
+            } else {
+                p.error(ErrorKind::ExpectedConstantExpression);
+            }
             p.finish_node();
+            p.finish_node();
+        }
+        // Still parse other expressions, to report them as a whole.
+        Some(k) if k.can_start_expr() => {
+            p.error(ErrorKind::NonConstantExpression);
+            parse_expr(p);
         }
-        _ => {
+        Some(k) if !k.is_separator() && !k.can_start_statement() => {
             p.error(ErrorKind::ExpectedConstantExpression);
             p.bump_error();
         }
+        _ => p.error(ErrorKind::ExpectedConstantExpression),
     }
 }
 
-fn parse_tuple(p: &mut Parser) {
+fn parse_tuple(
+    p: &mut Parser,
+    kind: SyntaxKind,
+    can_start: fn(SyntaxKind) -> bool,
+    elem: fn(&mut Parser),
+) {
     assert!(p.at(T!["#"]));
-    p.start_node(TUPLE);
+    p.start_node(kind);
+    p.bump();
+    if p.want(T!["("]) {
+        parse_comma_list(p, T![")"], can_start, elem);
+    }
+    p.finish_node()
+}
+
+fn parse_list(
+    p: &mut Parser,
+    kind: SyntaxKind,
+    can_start: fn(SyntaxKind) -> bool,
+    elem: fn(&mut Parser),
+) {
+    assert!(p.at(T!["["]));
+    p.start_node(kind);
     p.bump();
-    p.want(T!["("]);
+    parse_comma_list(p, T!["]"], can_start, elem);
+    p.finish_node()
+}
+
+/// Parse elements separated by commas up to and including the `close` token.
+///
+/// Unexpected tokens are skipped, so the following elements still end up in the list. Only
+/// other delimiters and statement starts end the list early, since they likely belong to the
+/// enclosing syntax.
+fn parse_comma_list(
+    p: &mut Parser,
+    close: SyntaxKind,
+    can_start: fn(SyntaxKind) -> bool,
+    elem: fn(&mut Parser),
+) {
+    let expected = TokenSet::new(&[close, T![","]]);
+    let mut after_elem = false;
     loop {
         match p.peek_non_ws() {
-            Some(T![")"]) => {
+            Some(k) if k == close => {
                 p.bump();
                 break;
             }
-            Some(k) if k.can_start_constant_expr() => {
-                parse_constant_value(p);
-                continue;
+            Some(k) if can_start(k) && !at_function_item(p) => {
+                if after_elem {
+                    p.error(ErrorKind::ExpectToken(T![","]));
+                }
+                elem(p);
+                after_elem = true;
             }
             Some(T![","]) => {
+                if !after_elem {
+                    p.error(ErrorKind::ExpectedElement);
+                }
                 p.bump();
-                continue;
+                after_elem = false;
+            }
+            Some(k) if !k.is_separator() && !k.can_start_statement() => {
+                p.error_expected(ErrorKind::ExpectToken(close), expected);
+                p.bump_error();
+                after_elem = false;
             }
             _ => {
-                p.error(ErrorKind::ExpectToken(T![")"]));
+                p.error_expected(ErrorKind::ExpectToken(close), expected);
                 break;
             }
         }
     }
-    p.finish_node()
 }
 
-fn visibility_opt(p: &mut Parser) -> bool {
-    if p.at_non_ws(T!["pub"]) {
+/// A custom type or a type alias, which share their heads.
+fn parse_type_definition(p: &mut Parser, cp: Checkpoint) {
+    assert!(p.at(T!["type"]));
+    p.bump();
+    parse_type_name(p);
+    if p.peek_non_ws() == Some(T!["("]) {
         p.bump();
-        return true;
+        parse_comma_list(p, T![")"], |k| k == IDENT, parse_type_param);
     }
-    false
+    if p.at_non_ws(T!["="]) {
+        p.start_node_at(cp, TYPE_ALIAS);
+        p.bump();
+        parse_type(p);
+        p.finish_node();
+        return;
+    }
+
+    p.start_node_at(cp, CUSTOM_TYPE);
+    if p.want(T!["{"]) {
+        loop {
+            match p.peek_non_ws() {
+                Some(T!["}"]) => {
+                    p.bump();
+                    break;
+                }
+                Some(U_IDENT | IDENT) => parse_constructor(p),
+                // Probably a missing `}`, leave the item to the module level.
+                Some(k) if k.can_start_statement() => {
+                    p.error(ErrorKind::ExpectToken(T!["}"]));
+                    break;
+                }
+                Some(_) => {
+                    p.error(ErrorKind::ExpectedConstructor);
+                    p.bump_error();
+                }
+                None => {
+                    p.error(ErrorKind::ExpectToken(T!["}"]));
+                    break;
+                }
+            }
+        }
+    }
+    p.finish_node();
 }
 
-fn parse_name(p: &mut Parser) {
+fn parse_type_name(p: &mut Parser) {
     p.ws();
     p.start_node(NAME);
-    p.want(IDENT);
+    // Wrongly cased names are reported by the naming lint, like in `parse_name`.
+    if p.at(IDENT) {
+        check_reserved_word(p);
+        p.bump();
+    } else {
+        p.want(U_IDENT);
+    }
     p.finish_node();
 }
 
-fn parse_type_annotation_opt(p: &mut Parser) {
-    match p.peek_non_ws() {
-        Some(T![":"]) => {
-            p.bump();
-            parse_type(p);
-        }
-        _ => {}
+fn parse_type_param(p: &mut Parser) {
+    p.ws();
+    p.start_node(TYPE_PARAM);
+    parse_name(p);
+    p.finish_node();
+}
+
+fn parse_constructor(p: &mut Parser) {
+    p.ws();
+    p.start_node(CONSTRUCTOR);
+    parse_type_name(p);
+    if p.peek_non_ws() == Some(T!["("]) {
+        p.bump();
+        parse_comma_list(
+            p,
+            T![")"],
+            SyntaxKind::can_start_type,
+            parse_constructor_field,
+        );
     }
+    p.finish_node();
 }
 
-fn parse_type(p: &mut Parser) {
-    match p.peek_non_ws() {
-        // function
-        Some(T!["fn"]) => parse_fn_type(p),
-        // type variable or constructor module
-        Some(IDENT) => {
-            let cp = p.checkpoint();
-            p.bump();
+fn parse_constructor_field(p: &mut Parser) {
+    p.ws();
+    p.start_node(CONSTRUCTOR_FIELD);
+    let labelled = {
+        let mut iter = p.peek_iter_non_ws();
+        iter.next() == Some(IDENT) && iter.next() == Some(T![":"])
+    };
+    if labelled {
+        parse_name(p);
+        p.want(T![":"]);
+    }
+    parse_type(p);
+    p.finish_node();
+}
+
+/// External functions may have no body.
+fn parse_function(p: &mut Parser, cp: Checkpoint, is_external: bool) {
+    assert!(p.at(T!["fn"]));
+    p.start_node_at(cp, FUNCTION);
+    p.bump();
+    parse_name(p);
+    if p.peek_non_ws() == Some(T!["("]) {
+        parse_param_list(p);
+    } else {
+        p.error(ErrorKind::ExpectToken(T!["("]));
+    }
+    if p.at_non_ws(T!["->"]) {
+        p.bump();
+        parse_type(p);
+    }
+    if p.peek_non_ws() == Some(T!["{"]) {
+        parse_block(p);
+    } else if !is_external {
+        p.error(ErrorKind::ExpectToken(T!["{"]));
+    }
+    p.finish_node();
+}
+
+fn parse_param_list(p: &mut Parser) {
+    assert!(p.at(T!["("]));
+    p.start_node(PARAM_LIST);
+    p.bump();
+    parse_comma_list(
+        p,
+        T![")"],
+        |k| matches!(k, IDENT | U_IDENT | DISCARD_IDENT),
+        parse_param,
+    );
+    p.finish_node();
+}
+
+fn parse_param(p: &mut Parser) {
+    p.ws();
+    p.start_node(PARAM);
+    let labelled = {
+        let mut iter = p.peek_iter_non_ws();
+        matches!(iter.next(), Some(IDENT | U_IDENT))
+            && matches!(iter.next(), Some(IDENT | U_IDENT | DISCARD_IDENT))
+    };
+    if labelled {
+        parse_name(p);
+        p.ws();
+    }
+    p.start_node(NAME);
+    check_reserved_word(p);
+    p.bump();
+    p.finish_node();
+    parse_type_annotation_opt(p);
+    p.finish_node();
+}
+
+fn parse_block(p: &mut Parser) {
+    assert!(p.at(T!["{"]));
+    p.start_node(BLOCK);
+    p.bump();
+    loop {
+        match p.peek_non_ws() {
+            Some(T!["}"]) => {
+                p.bump();
+                break;
+            }
+            Some(T!["let"]) => parse_let(p),
+            Some(T!["use"]) => parse_use(p),
+            Some(k) if k.can_start_expr() && !at_function_item(p) => parse_expr(p),
+            // Probably a missing `}`, leave the item to the module level.
+            Some(k) if k.can_start_statement() => {
+                p.error(ErrorKind::ExpectToken(T!["}"]));
+                break;
+            }
+            Some(_) => {
+                p.error(ErrorKind::ExpectedExpression);
+                p.bump_error();
+            }
+            None => {
+                p.error(ErrorKind::ExpectToken(T!["}"]));
+                break;
+            }
+        }
+    }
+    p.finish_node();
+}
+
+fn parse_let(p: &mut Parser) {
+    assert!(p.at(T!["let"]));
+    p.start_node(LET_STATEMENT);
+    p.bump();
+    if p.at_non_ws(T!["assert"]) {
+        p.bump();
+    }
+    parse_pattern(p);
+    if p.at_non_ws(T![":"]) {
+        p.bump();
+        parse_type(p);
+    }
+    if p.want(T!["="]) {
+        parse_expr(p);
+    }
+    p.finish_node();
+}
+
+fn parse_use(p: &mut Parser) {
+    assert!(p.at(T!["use"]));
+    p.start_node(USE_EXPR);
+    p.bump();
+    if !p.at_non_ws(T!["<-"]) {
+        parse_use_assignment(p);
+        while p.at_non_ws(T![","]) {
+            p.bump();
+            parse_use_assignment(p);
+        }
+    }
+    if p.want(T!["<-"]) {
+        parse_expr(p);
+    }
+    p.finish_node();
+}
+
+fn parse_use_assignment(p: &mut Parser) {
+    p.ws();
+    p.start_node(USE_ASSIGNMENT);
+    parse_pattern(p);
+    if p.at_non_ws(T![":"]) {
+        p.bump();
+        parse_type(p);
+    }
+    p.finish_node();
+}
+
+fn parse_expr(p: &mut Parser) {
+    parse_expr_bp(p, 0);
+}
+
+/// Pratt parsing of binary operators binding at least as tight as `min_bp`.
+fn parse_expr_bp(p: &mut Parser, min_bp: u8) {
+    if !p.enter() {
+        return;
+    }
+    p.ws();
+    let cp = p.checkpoint();
+    // Records may be constructed in guards, but functions may not be called.
+    let mut is_constructor = p.at(U_IDENT) || at_qualified_ref(p, |k| k == U_IDENT);
+    if matches!(p.peek(), Some(T!["-"] | T!["!"])) {
+        p.start_node(UNARY_EXPR);
+        p.bump();
+        parse_expr_bp(p, PREFIX_BINDING_POWER);
+        p.finish_node();
+    } else {
+        parse_atom(p);
+    }
+    loop {
+        match p.peek_non_ws() {
+            Some(T!["("]) => {
+                if p.in_guard && !is_constructor {
+                    p.error(ErrorKind::InvalidGuardExpression);
+                }
+                is_constructor = false;
+                p.start_node_at(cp, CALL_EXPR);
+                parse_arg_list(p, parse_arg);
+                p.finish_node();
+            }
+            Some(T!["."]) => {
+                is_constructor = false;
+                p.start_node_at(cp, FIELD_ACCESS);
+                p.bump();
+                match p.peek_non_ws() {
+                    Some(IDENT) => {
+                        p.start_node(NAME_REF);
+                        p.bump();
+                        p.finish_node();
+                    }
+                    // Tuple index.
+                    Some(INTEGER) => p.bump(),
+                    _ => p.error(ErrorKind::ExpectedIdentifier),
+                }
+                p.finish_node();
+            }
+            _ => break,
+        }
+    }
+    while let Some((l_bp, r_bp)) = p.peek_non_ws().and_then(infix_binding_power) {
+        if l_bp < min_bp {
+            break;
+        }
+        p.start_node_at(cp, BINARY_EXPR);
+        p.bump();
+        parse_expr_bp(p, r_bp);
+        p.finish_node();
+    }
+    p.leave();
+}
+
+/// Binding powers are derived from the precedences, so that the operand of a prefix operator
+/// stops before any infix operator.
+const PREFIX_BINDING_POWER: u8 = UnaryOpKind::Negate.precedence() * 2 - 1;
+
+/// The left and right binding power of an infix operator.
+/// A right one higher than the left one makes the operator left associative.
+fn infix_binding_power(kind: SyntaxKind) -> Option<(u8, u8)> {
+    let op = BinaryOpKind::from_token(kind)?;
+    let bp = op.precedence() * 2 - 1;
+    Some(match op.associativity() {
+        Associativity::Left => (bp, bp + 1),
+        Associativity::Right => (bp + 1, bp),
+    })
+}
+
+fn parse_atom(p: &mut Parser) {
+    let allowed_in_guard = matches!(
+        p.peek_non_ws(),
+        Some(IDENT | U_IDENT | INTEGER | FLOAT | STRING | T!["#"] | T!["["] | T!["<<"])
+    );
+    if p.in_guard && !allowed_in_guard && p.peek().map_or(false, SyntaxKind::can_start_expr) {
+        p.error(ErrorKind::InvalidGuardExpression);
+    }
+    match p.peek_non_ws() {
+        Some(INTEGER | STRING | FLOAT) => {
+            p.start_node(LITERAL);
+            p.bump();
+            p.finish_node()
+        }
+        Some(IDENT) if at_qualified_ref(p, |k| matches!(k, IDENT | U_IDENT)) => {
+            parse_qualified_ref(p)
+        }
+        Some(IDENT | U_IDENT) => {
+            p.start_node(NAME_REF);
+            p.bump();
+            p.finish_node();
+        }
+        // The hole of a function capture, as in `add(1, _)`.
+        Some(DISCARD_IDENT) => {
+            p.start_node(CAPTURE_HOLE);
+            p.bump();
+            p.finish_node();
+        }
+        Some(T!["#"]) => parse_tuple(p, TUPLE, SyntaxKind::can_start_expr, parse_expr),
+        Some(T!["["]) => parse_list(p, LIST, SyntaxKind::can_start_expr, parse_expr),
+        Some(T!["{"]) => parse_block(p),
+        Some(T!["case"]) => parse_case(p),
+        // Probably a missing delimiter, leave the item to the module level.
+        Some(T!["fn"]) if at_function_item(p) => p.error(ErrorKind::ExpectedExpression),
+        Some(T!["fn"]) => parse_lambda(p),
+        Some(T!["<<"]) => parse_bit_array(p, SyntaxKind::can_start_expr, |p| {
+            parse_bit_array_segment(p, parse_expr)
+        }),
+        Some(T!["echo"]) => parse_echo(p),
+        Some(T!["todo"]) => parse_todo_or_panic(p, TODO_EXPR),
+        Some(T!["panic"]) => parse_todo_or_panic(p, PANIC_EXPR),
+        Some(k) if k.is_separator() => p.error(ErrorKind::ExpectedExpression),
+        Some(_) => {
+            p.error(ErrorKind::ExpectedExpression);
+            p.bump_error();
+        }
+        None => p.error(ErrorKind::ExpectedExpression),
+    }
+}
+
+/// Whether a `fn` starts a module level function rather than an anonymous function or a
+/// function type, both of which are followed by their parameters.
+fn at_function_item(p: &mut Parser) -> bool {
+    let mut iter = p.peek_iter_non_ws();
+    iter.next() == Some(T!["fn"]) && iter.next() != Some(T!["("])
+}
+
+fn parse_lambda(p: &mut Parser) {
+    assert!(p.at(T!["fn"]));
+    p.start_node(LAMBDA);
+    p.bump();
+    if p.peek_non_ws() == Some(T!["("]) {
+        parse_param_list(p);
+    } else {
+        p.error(ErrorKind::ExpectToken(T!["("]));
+    }
+    if p.at_non_ws(T!["->"]) {
+        p.bump();
+        parse_type(p);
+    }
+    if p.peek_non_ws() == Some(T!["{"]) {
+        parse_block(p);
+    } else {
+        p.error(ErrorKind::ExpectToken(T!["{"]));
+    }
+    p.finish_node();
+}
+
+fn parse_case(p: &mut Parser) {
+    assert!(p.at(T!["case"]));
+    p.start_node(CASE_EXPR);
+    p.bump();
+    parse_expr(p);
+    while p.at_non_ws(T![","]) {
+        p.bump();
+        parse_expr(p);
+    }
+    if p.want(T!["{"]) {
+        loop {
+            match p.peek_non_ws() {
+                Some(T!["}"]) => {
+                    p.bump();
+                    break;
+                }
+                Some(k) if k.can_start_pattern() => parse_case_clause(p),
+                // Probably a missing `}`, leave the item to the module level.
+                Some(k) if k.can_start_statement() => {
+                    p.error(ErrorKind::ExpectToken(T!["}"]));
+                    break;
+                }
+                Some(_) => {
+                    p.error(ErrorKind::ExpectedPattern);
+                    p.bump_error();
+                }
+                None => {
+                    p.error(ErrorKind::ExpectToken(T!["}"]));
+                    break;
+                }
+            }
+        }
+    }
+    p.finish_node();
+}
+
+fn parse_case_clause(p: &mut Parser) {
+    p.ws();
+    p.start_node(CASE_CLAUSE);
+    let cp = p.checkpoint();
+    parse_pattern(p);
+    while p.at_non_ws(T![","]) {
+        p.bump();
+        parse_pattern(p);
+    }
+    if p.at_non_ws(T!["|"]) {
+        p.start_node_at(cp, ALTERNATIVE_PATTERN);
+        while p.at_non_ws(T!["|"]) {
+            p.bump();
+            parse_pattern(p);
+            while p.at_non_ws(T![","]) {
+                p.bump();
+                parse_pattern(p);
+            }
+        }
+        p.finish_node();
+    }
+    if p.at_non_ws(T!["if"]) {
+        p.start_node(CASE_GUARD);
+        p.bump();
+        p.in_guard = true;
+        parse_expr(p);
+        p.in_guard = false;
+        p.finish_node();
+    }
+    if p.want(T!["->"]) {
+        parse_expr(p);
+    }
+    p.finish_node();
+}
+
+fn parse_pattern(p: &mut Parser) {
+    if !p.enter() {
+        return;
+    }
+    p.ws();
+    match p.peek() {
+        Some(IDENT) if at_qualified_ref(p, |k| k == U_IDENT) => parse_constructor_pattern(p),
+        Some(IDENT) => {
+            p.start_node(VARIABLE_PATTERN);
+            parse_name(p);
+            p.finish_node();
+        }
+        Some(DISCARD_IDENT) => {
+            p.start_node(DISCARD_PATTERN);
+            p.bump();
+            p.finish_node();
+        }
+        Some(STRING) => {
+            let cp = p.checkpoint();
+            parse_literal_pattern(p);
+            let named_prefix = {
+                let mut iter = p.peek_iter_full_non_ws();
+                iter.next().map_or(false, |tok| is_soft_kw(tok, T!["as"]))
+                    && iter.next().map(|tok| tok.kind) == Some(IDENT)
+                    && iter.next().map(|tok| tok.kind) == Some(T!["<>"])
+            };
+            if named_prefix || p.at_non_ws(T!["<>"]) {
+                p.start_node_at(cp, CONCAT_PATTERN);
+                if named_prefix {
+                    p.ws();
+                    p.bump_with_kind(T!["as"]);
+                    parse_name(p);
+                }
+                p.want(T!["<>"]);
+                // Only the remainder of the string can be bound here.
+                match p.peek_non_ws() {
+                    Some(IDENT | DISCARD_IDENT) => parse_pattern(p),
+                    _ => p.error(ErrorKind::ExpectedIdentifier),
+                }
+                p.finish_node();
+            }
+        }
+        Some(INTEGER | FLOAT) => parse_literal_pattern(p),
+        Some(U_IDENT) => parse_constructor_pattern(p),
+        Some(T!["#"]) => parse_tuple(
+            p,
+            TUPLE_PATTERN,
+            SyntaxKind::can_start_pattern,
+            parse_pattern,
+        ),
+        Some(T!["["]) => parse_list(
+            p,
+            LIST_PATTERN,
+            |k| k.can_start_pattern() || k == T![".."],
+            parse_list_pattern_element,
+        ),
+        Some(T!["<<"]) => parse_bit_array(p, SyntaxKind::can_start_pattern, |p| {
+            parse_bit_array_segment(p, parse_pattern)
+        }),
+        Some(k) if k.is_separator() => p.error(ErrorKind::ExpectedPattern),
+        Some(_) => {
+            p.error(ErrorKind::ExpectedPattern);
+            p.bump_error();
+        }
+        None => p.error(ErrorKind::ExpectedPattern),
+    }
+    p.leave();
+}
+
+fn parse_literal_pattern(p: &mut Parser) {
+    p.start_node(LITERAL_PATTERN);
+    p.start_node(LITERAL);
+    p.bump();
+    p.finish_node();
+    p.finish_node();
+}
+
+fn parse_list_pattern_element(p: &mut Parser) {
+    if !p.at_non_ws(T![".."]) {
+        parse_pattern(p);
+        return;
+    }
+    p.start_node(LIST_REST);
+    p.bump();
+    // `..rest` binds the tail, a bare `..` discards it.
+    if matches!(p.peek_non_ws(), Some(IDENT | DISCARD_IDENT)) {
+        parse_pattern(p);
+    }
+    p.finish_node();
+}
+
+/// Whether a module name followed by `.` and a name accepted by `name` is next.
+fn at_qualified_ref(p: &mut Parser, name: fn(SyntaxKind) -> bool) -> bool {
+    let mut iter = p.peek_iter_non_ws();
+    iter.next() == Some(IDENT) && iter.next() == Some(T!["."]) && iter.next().map_or(false, name)
+}
+
+/// A reference to a value or constructor of another module, as in `list.map`.
+/// These can also be a field access on a local variable, which only name resolution can tell.
+fn parse_qualified_ref(p: &mut Parser) {
+    p.ws();
+    p.start_node(QUALIFIED_REF);
+    p.start_node(MODULE_NAME);
+    p.want(IDENT);
+    p.finish_node();
+    p.want(T!["."]);
+    p.ws();
+    p.start_node(NAME_REF);
+    p.bump();
+    p.finish_node();
+    p.finish_node();
+}
+
+fn parse_constructor_pattern(p: &mut Parser) {
+    p.start_node(CONSTRUCTOR_PATTERN);
+    if p.at(U_IDENT) {
+        p.start_node(NAME_REF);
+        p.bump();
+        p.finish_node();
+    } else {
+        parse_qualified_ref(p);
+    }
+    if p.peek_non_ws() == Some(T!["("]) {
+        p.bump();
+        parse_comma_list(
+            p,
+            T![")"],
+            |k| k.can_start_pattern() || k == T![".."],
+            parse_pattern_arg,
+        );
+    }
+    p.finish_node();
+}
+
+fn parse_pattern_arg(p: &mut Parser) {
+    // The spread of the remaining fields is not an argument itself.
+    if p.at_non_ws(T![".."]) {
+        p.bump();
+        return;
+    }
+    p.ws();
+    p.start_node(PATTERN_ARG);
+    let labelled = {
+        let mut iter = p.peek_iter_non_ws();
+        iter.next() == Some(IDENT) && iter.next() == Some(T![":"])
+    };
+    if labelled {
+        parse_name(p);
+        p.want(T![":"]);
+    }
+    parse_pattern(p);
+    p.finish_node();
+}
+
+/// A bit array in both expression and pattern position, with `segment` parsing the segments
+/// and `can_start` telling where their values start.
+fn parse_bit_array(p: &mut Parser, can_start: fn(SyntaxKind) -> bool, segment: fn(&mut Parser)) {
+    assert!(p.at(T!["<<"]));
+    p.start_node(BIT_ARRAY);
+    p.bump();
+    parse_comma_list(p, T![">>"], can_start, segment);
+    p.finish_node();
+}
+
+fn parse_bit_array_segment(p: &mut Parser, value: fn(&mut Parser)) {
+    p.ws();
+    p.start_node(BIT_ARRAY_SEGMENT);
+    value(p);
+    if p.at_non_ws(T![":"]) {
+        p.bump();
+        parse_bit_array_option(p);
+        while p.at_non_ws(T!["-"]) {
+            p.bump();
+            parse_bit_array_option(p);
+        }
+    }
+    p.finish_node();
+}
+
+fn parse_bit_array_option(p: &mut Parser) {
+    p.ws();
+    match p.peek() {
+        Some(IDENT) => {
+            p.start_node(BIT_ARRAY_OPTION);
+            p.bump();
+            // `size(n)` and `unit(n)`.
+            if p.at(T!["("]) {
+                p.bump();
+                parse_expr(p);
+                p.want(T![")"]);
+            }
+            p.finish_node();
+        }
+        // A bare size, as in `<<x:8>>`.
+        Some(INTEGER) => {
+            p.start_node(BIT_ARRAY_OPTION);
+            p.start_node(LITERAL);
+            p.bump();
+            p.finish_node();
+            p.finish_node();
+        }
+        _ => p.error(ErrorKind::ExpectedBitArrayOption),
+    }
+}
+
+fn parse_todo_or_panic(p: &mut Parser, kind: SyntaxKind) {
+    p.start_node(kind);
+    p.bump();
+    if p.at_soft_kw_non_ws(T!["as"]) {
+        p.bump_with_kind(T!["as"]);
+        parse_expr(p);
+    }
+    p.finish_node();
+}
+
+fn parse_echo(p: &mut Parser) {
+    assert!(p.at(T!["echo"]));
+    if p.version < LanguageVersion::ECHO {
+        p.error(ErrorKind::UnsupportedSyntax(LanguageVersion::ECHO));
+    }
+    p.start_node(ECHO_EXPR);
+    p.bump();
+    // The argument is omitted at the end of a pipeline, as in `x |> echo`.
+    if p.peek_non_ws().map_or(false, SyntaxKind::can_start_expr) && !at_function_item(p) {
+        parse_expr(p);
+    }
+    p.finish_node();
+}
+
+fn parse_arg_list(p: &mut Parser, arg: fn(&mut Parser)) {
+    assert!(p.at(T!["("]));
+    p.start_node(ARG_LIST);
+    p.bump();
+    parse_comma_list(p, T![")"], |k| k.can_start_expr() || k == T![".."], arg);
+    p.finish_node();
+}
+
+fn parse_arg(p: &mut Parser) {
+    p.ws();
+    // The record being updated, as in `Person(..old, name: "x")`.
+    if p.at(T![".."]) {
+        p.start_node(RECORD_SPREAD);
+        p.bump();
+        parse_expr(p);
+        p.finish_node();
+        return;
+    }
+    p.start_node(ARG);
+    parse_arg_label_opt(p);
+    parse_expr(p);
+    p.finish_node();
+}
+
+/// A record field of a constant. Records cannot be updated in constants.
+fn parse_constant_arg(p: &mut Parser) {
+    p.ws();
+    p.start_node(ARG);
+    parse_arg_label_opt(p);
+    parse_constant_value(p);
+    p.finish_node();
+}
+
+fn parse_arg_label_opt(p: &mut Parser) {
+    let labelled = {
+        let mut iter = p.peek_iter_non_ws();
+        iter.next() == Some(IDENT) && iter.next() == Some(T![":"])
+    };
+    if labelled {
+        parse_name(p);
+        p.want(T![":"]);
+    }
+}
+
+fn visibility_opt(p: &mut Parser) -> bool {
+    if p.at_non_ws(T!["pub"]) {
+        p.bump();
+        return true;
+    }
+    false
+}
+
+fn parse_name(p: &mut Parser) {
+    p.ws();
+    p.start_node(NAME);
+    // Wrongly cased names are reported by the naming lint instead, which can also fix them.
+    if p.at(U_IDENT) {
+        p.bump();
+    } else {
+        check_reserved_word(p);
+        p.want(IDENT);
+    }
+    p.finish_node();
+}
+
+/// Words reserved by Gleam for future use, which are lexed as identifiers.
+const RESERVED_WORDS: [&str; 7] = [
+    "auto",
+    "delegate",
+    "derive",
+    "else",
+    "implement",
+    "macro",
+    "test",
+];
+
+/// Report a reserved word used as a name. It is still parsed as one, so the IDE keeps working.
+fn check_reserved_word(p: &mut Parser) {
+    let reserved = p.peek_full().map_or(false, |tok| {
+        tok.kind == IDENT && RESERVED_WORDS.contains(&tok.text)
+    });
+    if reserved {
+        p.error(ErrorKind::ReservedWord);
+    }
+}
+
+fn parse_type_annotation_opt(p: &mut Parser) {
+    match p.peek_non_ws() {
+        Some(T![":"]) => {
+            p.bump();
+            parse_type(p);
+        }
+        _ => {}
+    }
+}
+
+fn parse_type(p: &mut Parser) {
+    if !p.enter() {
+        return;
+    }
+    match p.peek_non_ws() {
+        // function
+        Some(T!["fn"]) => parse_fn_type(p),
+        // type variable or constructor module
+        Some(IDENT) => {
+            let cp = p.checkpoint();
+            p.bump();
 
             match p.peek_non_ws() {
                 Some(T!["."]) => {
@@ -416,9 +1645,11 @@ fn parse_type(p: &mut Parser) {
                     p.start_node_at(cp, MODULE_NAME);
                     p.finish_node();
                     p.bump();
+                    p.ws();
                     p.start_node(NAME);
                     p.want(U_IDENT);
                     p.finish_node();
+                    parse_type_arg_list_opt(p);
                     p.finish_node();
                 }
                 _ => {
@@ -435,17 +1666,31 @@ fn parse_type(p: &mut Parser) {
             p.start_node(NAME);
             p.bump();
             p.finish_node();
+            parse_type_arg_list_opt(p);
             p.finish_node()
         }
         // tuple
         Some(T!("#")) => {
             parse_tuple_type(p);
         }
+        Some(k) if k.is_separator() || k.can_start_statement() => p.error(ErrorKind::ExpectedType),
         Some(_) => {
             p.error(ErrorKind::ExpectedType);
             p.bump_error()
         }
-        None => {}
+        None => p.error(ErrorKind::ExpectedType),
+    }
+    p.leave();
+}
+
+fn parse_type_arg_list_opt(p: &mut Parser) {
+    // Trivia is only consumed if there is an argument list, or it would end up in the type.
+    if p.peek_iter_non_ws().next() == Some(T!["("]) {
+        p.ws();
+        p.start_node(TYPE_ARG_LIST);
+        p.bump();
+        parse_comma_list(p, T![")"], SyntaxKind::can_start_type, parse_type);
+        p.finish_node();
     }
 }
 
@@ -453,26 +1698,8 @@ fn parse_tuple_type(p: &mut Parser) {
     assert!(p.at(T!["#"]));
     p.start_node(TUPLE_TYPE);
     p.bump();
-    p.want(T!["("]);
-    loop {
-        match p.peek_non_ws() {
-            Some(T![")"]) => {
-                p.bump();
-                break;
-            }
-            Some(k) if k.can_start_type() => {
-                parse_type(p);
-                continue;
-            }
-            Some(T![","]) => {
-                p.bump();
-                continue;
-            }
-            _ => {
-                p.error(ErrorKind::ExpectToken(T![")"]));
-                break;
-            }
-        }
+    if p.want(T!["("]) {
+        parse_comma_list(p, T![")"], SyntaxKind::can_start_type, parse_type);
     }
     p.finish_node()
 }
@@ -481,27 +1708,18 @@ fn parse_fn_type(p: &mut Parser) {
     assert!(p.at(T!("fn")));
     p.start_node(FN_TYPE);
     p.bump();
-    p.want(T!("("));
-    p.start_node(PARAM_LIST);
-    loop {
-        match p.peek_non_ws() {
-            Some(T![")"]) => {
-                p.bump();
-                break;
-            }
-            Some(T![","]) => {
-                p.bump();
-                continue;
-            }
-            _ => {
-                p.start_node(PARAM);
-                parse_type(p);
-                p.finish_node();
-                continue;
-            }
-        }
+    if p.at_non_ws(T!["("]) {
+        p.start_node(PARAM_LIST);
+        p.bump();
+        parse_comma_list(p, T![")"], SyntaxKind::can_start_type, |p| {
+            p.start_node(PARAM);
+            parse_type(p);
+            p.finish_node();
+        });
+        p.finish_node();
+    } else {
+        p.error(ErrorKind::ExpectToken(T!["("]));
     }
-    p.finish_node();
     p.want(T!["->"]);
     parse_type(p);
     p.finish_node();
@@ -509,23 +1727,28 @@ fn parse_fn_type(p: &mut Parser) {
 
 impl SyntaxKind {
     fn can_start_constant_expr(self) -> bool {
-        matches!(self, IDENT | INTEGER | FLOAT | STRING | T!["#"] | T!["["])
+        TokenSet::CONST_EXPR_START.contains(self)
+    }
+
+    fn can_start_expr(self) -> bool {
+        TokenSet::EXPR_START.contains(self)
+    }
+
+    fn can_start_pattern(self) -> bool {
+        TokenSet::PATTERN_START.contains(self)
     }
 
     fn can_start_type(self) -> bool {
-        matches!(self, T!["fn"] | T!["#"] | IDENT | U_IDENT)
+        TokenSet::TYPE_START.contains(self)
     }
 
     fn can_start_statement(self) -> bool {
-        matches!(
-            self,
-            T!["import"] | T!["pub"] | T!["const"] | T!["fn"] | T!["type"]
-        )
+        TokenSet::ITEM_START.contains(self)
     }
 
     /// Whether this token is a separator in some syntax.
     /// We should stop at these tokens during error recovery.
     fn is_separator(self) -> bool {
-        matches!(self, T![")"] | T!["]"] | T!["}"] | T!["="] | T![","])
+        TokenSet::SEPARATORS.contains(self)
     }
 }
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::successors;
+use std::marker::PhantomData;
+
+use rowan::ast::AstNode;
+
+use crate::{GleamLanguage, SyntaxKind, SyntaxNode, TextRange};
+
+/// A pointer to a syntax node which does not keep its tree alive.
+///
+/// It only stores the kind and range of the node, so it can be resolved against a reparsed
+/// tree as long as the text before and at the node did not change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxNodePtr {
+    kind: SyntaxKind,
+    range: TextRange,
+}
+
+impl SyntaxNodePtr {
+    pub fn new(node: &SyntaxNode) -> Self {
+        Self {
+            kind: node.kind(),
+            range: node.text_range(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.range
+    }
+
+    /// Find the node in the tree of `root`, or `None` if there is no node of the same kind and
+    /// range anymore.
+    pub fn try_to_node(&self, root: &SyntaxNode) -> Option<SyntaxNode> {
+        if root.parent().is_some() {
+            return None;
+        }
+        successors(Some(root.clone()), |node| {
+            node.child_or_token_at_range(self.range)?.into_node()
+        })
+        .find(|node| node.text_range() == self.range && node.kind() == self.kind)
+    }
+
+    /// Same as `try_to_node`, but panics if the node is gone.
+    pub fn to_node(&self, root: &SyntaxNode) -> SyntaxNode {
+        self.try_to_node(root)
+            .unwrap_or_else(|| panic!("can't resolve {self:?} with {root:?}"))
+    }
+
+    pub fn cast<N: AstNode<Language = GleamLanguage>>(self) -> Option<AstPtr<N>> {
+        if !N::can_cast(self.kind) {
+            return None;
+        }
+        Some(AstPtr {
+            raw: self,
+            _ty: PhantomData,
+        })
+    }
+}
+
+/// A typed `SyntaxNodePtr`.
+pub struct AstPtr<N> {
+    raw: SyntaxNodePtr,
+    _ty: PhantomData<fn() -> N>,
+}
+
+impl<N: AstNode<Language = GleamLanguage>> AstPtr<N> {
+    pub fn new(node: &N) -> Self {
+        Self {
+            raw: SyntaxNodePtr::new(node.syntax()),
+            _ty: PhantomData,
+        }
+    }
+
+    pub fn try_to_node(&self, root: &SyntaxNode) -> Option<N> {
+        self.raw.try_to_node(root).and_then(N::cast)
+    }
+
+    pub fn to_node(&self, root: &SyntaxNode) -> N {
+        N::cast(self.raw.to_node(root)).unwrap()
+    }
+
+    pub fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.raw
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.raw.range
+    }
+
+    /// Convert into a pointer to another view of the same node, eg. from `Function` to `Statement`.
+    pub fn cast<U: AstNode<Language = GleamLanguage>>(self) -> Option<AstPtr<U>> {
+        self.raw.cast()
+    }
+}
+
+// Derives would require the bounds on `N`.
+impl<N> fmt::Debug for AstPtr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AstPtr").field(&self.raw).finish()
+    }
+}
+
+impl<N> Clone for AstPtr<N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for AstPtr<N> {}
+
+impl<N> PartialEq for AstPtr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<N> Eq for AstPtr<N> {}
+
+impl<N> Hash for AstPtr<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
+impl<N> From<AstPtr<N>> for SyntaxNodePtr {
+    fn from(ptr: AstPtr<N>) -> Self {
+        ptr.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AstPtr, SyntaxNodePtr};
+    use crate::ast::{self, AstNode};
+    use crate::parse_file;
+
+    #[test]
+    fn reparse() {
+        let src = "fn a() { 1 }\nfn b() { 2 }";
+        let root = parse_file(src).syntax_node();
+        let func = root.descendants().find_map(ast::Function::cast).unwrap();
+        let ptr = AstPtr::new(&func);
+        assert_eq!(ptr.to_node(&root).syntax(), func.syntax());
+
+        let edited = parse_file(&format!("{src}\nfn c() {{ 3 }}")).syntax_node();
+        let func = ptr.to_node(&edited);
+        assert_eq!(func.name().unwrap().syntax().to_string(), "a");
+
+        let stmt = ptr.cast::<ast::Statement>().unwrap();
+        assert!(matches!(stmt.to_node(&edited), ast::Statement::Function(_)));
+        assert!(ptr.cast::<ast::Import>().is_none());
+        assert_eq!(SyntaxNodePtr::from(stmt), ptr.syntax_node_ptr());
+
+        let edited = parse_file(&format!("import x\n{src}")).syntax_node();
+        assert!(ptr.try_to_node(&edited).is_none());
+    }
+}
@@ -0,0 +1,267 @@
+//! Incremental reparsing after an edit.
+//!
+//! Edits inside whitespace and comments only relex that token, and other edits reparse the
+//! smallest enclosing block or module item. Whenever the result could depend on the text
+//! around it, eg. when a string is left unterminated, the whole file is parsed again.
+use rowan::{GreenNode, GreenToken, NodeOrToken};
+
+use crate::ast::{self, AstNode};
+use crate::lexer::GleamLexer;
+use crate::parser::{parse_block_fragment, MAX_DEPTHS};
+use crate::SyntaxKind::*;
+use crate::{
+    parse_file_with_version, validation, LanguageVersion, Parse, ParseError, SyntaxNode, TextEdit,
+    TextRange, TextSize,
+};
+
+impl Parse {
+    /// The parse of the text after `edit`, reusing the unchanged parts of this one. The result
+    /// is the same as parsing the new text from scratch.
+    pub fn reparse(&self, edit: &TextEdit, version: LanguageVersion) -> Parse {
+        reparse_token(self, edit)
+            .or_else(|| reparse_block(self, edit, version))
+            .unwrap_or_else(|| {
+                let mut text = self.syntax_node().to_string();
+                edit.apply(&mut text);
+                parse_file_with_version(&text, version)
+            })
+    }
+}
+
+fn reparse_token(parse: &Parse, edit: &TextEdit) -> Option<Parse> {
+    let root = parse.syntax_node();
+    let tok = root.covering_element(edit.delete).into_token()?;
+    if !tok.kind().is_whitespace() {
+        return None;
+    }
+    let range = tok.text_range();
+    let mut text = tok.text().to_owned();
+    relative(edit, range.start()).apply(&mut text);
+
+    // The new text must lex as a single token of the same kind, without merging with its
+    // neighbors.
+    let prev = tok.prev_token();
+    let next = tok.next_token();
+    let prev_text = prev.as_ref().map_or("", |it| it.text());
+    let next_text = next.as_ref().map_or("", |it| it.text());
+    let lexed = GleamLexer::new(&format!("{prev_text}{text}{next_text}"))
+        .map(|it| it.text.len())
+        .collect::<Vec<_>>();
+    let expect = [prev_text.len(), text.len(), next_text.len()]
+        .into_iter()
+        .filter(|&len| len != 0)
+        .collect::<Vec<_>>();
+    let new_kind = GleamLexer::new(&text).next()?.kind;
+    if lexed != expect || new_kind != tok.kind() {
+        return None;
+    }
+
+    let errors = merge_errors(&parse.errors, range, edit, Vec::new())?;
+    let green = tok.replace_with(GreenToken::new(tok.kind().into(), &text));
+    Some(Parse { green, errors })
+}
+
+fn reparse_block(parse: &Parse, edit: &TextEdit, version: LanguageVersion) -> Option<Parse> {
+    let root = parse.syntax_node();
+    let node = match root.covering_element(edit.delete) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(tok) => tok.parent()?,
+    };
+    node.ancestors()
+        .find_map(|node| reparse_node(parse, &node, edit, version))
+}
+
+fn reparse_node(
+    parse: &Parse,
+    node: &SyntaxNode,
+    edit: &TextEdit,
+    version: LanguageVersion,
+) -> Option<Parse> {
+    // The first and last token must stay, so that the tokens around the node are lexed the
+    // same.
+    let range = node.text_range();
+    let (first, last) = (node.first_token()?, node.last_token()?);
+    if edit.delete.start() < first.text_range().end()
+        || edit.delete.end() > last.text_range().start()
+    {
+        return None;
+    }
+    // The parser limits its nesting depth, which is not recorded in the tree. Leave deeply
+    // nested nodes to a full parse, so that the limit is hit in the same places.
+    if node.ancestors().count() > MAX_DEPTHS / 2 {
+        return None;
+    }
+
+    let mut text = node.text().to_string();
+    relative(edit, range.start()).apply(&mut text);
+    let (green, errors) = match node.kind() {
+        // Guards only allow some expressions, which the parser checks as it goes.
+        BLOCK if last.kind() == T!["}"] && !node.ancestors().any(|it| it.kind() == CASE_GUARD) => {
+            let (green, mut errors) = parse_block_fragment(&text, version)?;
+            validation::validate(&SyntaxNode::new_root(green.clone()), &mut errors);
+            (green, errors)
+        }
+        _ if node.parent()?.kind() == TARGET_GROUP && ast::Statement::can_cast(node.kind()) => {
+            reparse_item(&text, version)?
+        }
+        _ => return None,
+    };
+
+    let new_node = SyntaxNode::new_root(green.clone());
+    // An unterminated string might end anywhere after the node.
+    let unterminated_string = new_node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .any(|tok| tok.kind() == ERROR && tok.text().starts_with('"'));
+    if unterminated_string || new_node.ancestors().count() + height(&new_node) > MAX_DEPTHS / 2 {
+        return None;
+    }
+    // Errors at the first token could have been reported by the enclosing syntax too, and
+    // errors at the end of the text would have been reported at the next token instead.
+    let text_len = TextSize::of(&*text);
+    if errors
+        .iter()
+        .any(|err| err.range.start() == 0.into() || err.range.start() >= text_len)
+    {
+        return None;
+    }
+    let inner = errors
+        .into_iter()
+        .map(|err| ParseError {
+            range: err.range + range.start(),
+            ..err
+        })
+        .collect();
+
+    let errors = merge_errors(&parse.errors, range, edit, inner)?;
+    let green = node.replace_with(green);
+    Some(Parse { green, errors })
+}
+
+/// Parse `text` as a module containing only a single item.
+fn reparse_item(text: &str, version: LanguageVersion) -> Option<(GreenNode, Vec<ParseError>)> {
+    let parse = parse_file_with_version(text, version);
+    let root = parse.syntax_node();
+    let group = single_child(&root)?;
+    let item = single_child(&group)?;
+    if group.kind() != TARGET_GROUP || !ast::Statement::can_cast(item.kind()) {
+        return None;
+    }
+    Some((item.green().into_owned(), parse.errors))
+}
+
+fn single_child(node: &SyntaxNode) -> Option<SyntaxNode> {
+    let mut children = node.children_with_tokens();
+    match (children.next(), children.next()) {
+        (Some(NodeOrToken::Node(child)), None) => Some(child),
+        _ => None,
+    }
+}
+
+fn height(node: &SyntaxNode) -> usize {
+    node.children().map(|it| height(&it) + 1).max().unwrap_or(0)
+}
+
+/// `edit` with its range relative to `offset`.
+fn relative(edit: &TextEdit, offset: TextSize) -> TextEdit {
+    TextEdit {
+        delete: edit.delete - offset,
+        insert: edit.insert.clone(),
+    }
+}
+
+/// The errors of the old tree after replacing the errors reported inside `range` with `inner`,
+/// or `None` if an error only partly overlaps the edit.
+fn merge_errors(
+    old: &[ParseError],
+    range: TextRange,
+    edit: &TextEdit,
+    inner: Vec<ParseError>,
+) -> Option<Vec<ParseError>> {
+    let shift = |offset: TextSize| {
+        offset - edit.delete.end() + edit.delete.start() + TextSize::of(&*edit.insert)
+    };
+    let mut errors = Vec::with_capacity(old.len() + inner.len());
+    for err in old {
+        let (start, end) = (err.range.start(), err.range.end());
+        let range = if start >= range.end() {
+            TextRange::new(shift(start), shift(end))
+        } else if start > range.start() {
+            continue;
+        } else if end <= edit.delete.start() {
+            err.range
+        } else if end >= range.end() {
+            TextRange::new(start, shift(end))
+        } else {
+            return None;
+        };
+        errors.push(ParseError { range, ..*err });
+    }
+    errors.extend(inner);
+    errors.sort_by_key(|err| err.range.start());
+    Some(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reparse_block, reparse_token};
+    use crate::{parse_file, LanguageVersion, Parse, TextEdit, TextRange};
+
+    #[track_caller]
+    fn check(src: &str, delete: (u32, u32), insert: &str) -> (Option<Parse>, Option<Parse>) {
+        let parse = parse_file(src);
+        let edit = TextEdit {
+            delete: TextRange::new(delete.0.into(), delete.1.into()),
+            insert: insert.into(),
+        };
+        let mut text = src.to_owned();
+        edit.apply(&mut text);
+
+        let reparsed = parse.reparse(&edit, LanguageVersion::LATEST);
+        assert_eq!(reparsed.debug_dump(), parse_file(&text).debug_dump());
+        (
+            reparse_token(&parse, &edit),
+            reparse_block(&parse, &edit, LanguageVersion::LATEST),
+        )
+    }
+
+    #[test]
+    fn token() {
+        let (tok, _) = check("fn f() {\n  1\n}", (9, 11), "    ");
+        assert!(tok.is_some());
+        let (tok, _) = check("// a\nfn f() {}", (3, 4), "b c");
+        assert!(tok.is_some());
+        // The comment becomes a doc comment.
+        let (tok, _) = check("// a\nfn f() {}", (2, 2), "/");
+        assert!(tok.is_none());
+        // The whitespace is gone.
+        let (tok, _) = check("fn f() { a }", (8, 9), "");
+        assert!(tok.is_none());
+    }
+
+    #[test]
+    fn block() {
+        let src = "fn f() {\n  1\n}\n\nfn g() { [..x, 1] }";
+        let (_, block) = check(src, (11, 12), "let x = 1\n  x +");
+        assert!(block.is_some());
+        let (_, block) = check(src, (11, 12), "case x { [..r, a] -> 1 }");
+        assert!(block.is_some());
+    }
+
+    #[test]
+    fn item() {
+        check("const a = #(1, 2)\nconst b = 3", (12, 13), "[4]");
+        check("type A { A(x: Int) }\nconst b = 3", (11, 12), "y: Int, y");
+        check("import a.{b}\nconst b = 3", (10, 11), "c, d");
+    }
+
+    #[test]
+    fn fallback() {
+        let src = "fn f() { 1 }\nfn g() { 2 }";
+        let (_, block) = check(src, (9, 10), "\"a");
+        assert!(block.is_none());
+        let (_, block) = check(src, (11, 12), "");
+        assert!(block.is_none());
+        check(src, (0, src.len() as u32), "");
+    }
+}
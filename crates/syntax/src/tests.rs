@@ -1,7 +1,9 @@
-use crate::{parse_file, GleamLanguage};
+use crate::{
+    parse_file, parse_file_with_cache, ErrorKind, GleamLanguage, LanguageVersion, NodeCache,
+    SyntaxKind,
+};
 use expect_test::expect_file;
 use rowan::ast::AstNode;
-use std::fmt::Write;
 use std::fs;
 use std::path::Path;
 
@@ -33,11 +35,7 @@ fn run_test(dir: &Path, ok: bool) {
         println!("Parsing {}", path.display());
 
         let ast = parse_file(&src);
-        let mut got = String::new();
-        for err in ast.errors() {
-            writeln!(got, "{:?}: {:?}", err.range, err.kind).unwrap();
-        }
-        write!(got, "{:#?}", ast.syntax_node()).unwrap();
+        let got = ast.debug_dump();
 
         if ok != ast.errors().is_empty() {
             println!("--------\n{}\n--------", got);
@@ -55,3 +53,65 @@ fn parser() {
     run_test(&dir.join("ok"), true);
     run_test(&dir.join("err"), false);
 }
+
+#[test]
+fn node_cache_dedup() {
+    let mut cache = NodeCache::default();
+    let a = parse_file_with_cache("const a = 1", LanguageVersion::LATEST, &mut cache);
+    let b = parse_file_with_cache(
+        "import gleam/list\nconst b = 1",
+        LanguageVersion::LATEST,
+        &mut cache,
+    );
+    // Only nodes with up to 3 children are interned.
+    let literal = |parse: &crate::Parse| {
+        parse
+            .syntax_node()
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::LITERAL)
+            .unwrap()
+            .green()
+            .into_owned()
+    };
+    assert!(std::ptr::eq::<rowan::GreenNodeData>(
+        &*literal(&a),
+        &*literal(&b)
+    ));
+}
+
+#[test]
+fn deep_nesting() {
+    let depth = 10_000;
+    let sources = [
+        format!("fn a() {{ {}1{} }}", "{".repeat(depth), "}".repeat(depth)),
+        format!("fn a() {{ {}1{} }}", "[".repeat(depth), "]".repeat(depth)),
+        format!("fn a() {{ {}1 }}", "-".repeat(depth)),
+        format!("const a = {}1{}", "#(".repeat(depth), ")".repeat(depth)),
+        format!(
+            "fn a(x: {}Int{}) {{ 1 }}",
+            "List(".repeat(depth),
+            ")".repeat(depth)
+        ),
+    ];
+    for src in sources {
+        let parse = parse_file(&format!("{src}\nfn b() {{ 1 }}"));
+        let kinds = parse
+            .errors()
+            .iter()
+            .map(|err| err.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, [ErrorKind::NestTooDeep]);
+        // The rest of the file is still parsed.
+        let fns = parse
+            .syntax_node()
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::FUNCTION)
+            .count();
+        assert_eq!(fns, src.starts_with("fn") as usize + 1);
+    }
+
+    // Chained operators are parsed iteratively, and are never too deep. The tree still is, so
+    // keep it small enough to be dropped recursively.
+    let src = format!("fn a() {{ 1{} }}", " + 1".repeat(1_000));
+    assert!(parse_file(&src).errors().is_empty());
+}
@@ -0,0 +1,136 @@
+use crate::SyntaxKind::{self, *};
+
+/// A set of token kinds, eg. the tokens a parser error expected instead of the actual one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenSet(u128);
+
+impl TokenSet {
+    pub const EMPTY: Self = Self(0);
+
+    // The tokens starting each kind of syntax. The parser recovers from errors with these, and
+    // completion uses them to tell what can be typed at the cursor.
+
+    /// Module level items, including their attributes and modifiers.
+    pub const ITEM_START: Self = Self::new(&[
+        T!["import"],
+        T!["@"],
+        T!["pub"],
+        T!["opaque"],
+        T!["external"],
+        T!["const"],
+        T!["fn"],
+        T!["type"],
+    ]);
+
+    pub const EXPR_START: Self = Self::new(&[
+        IDENT,
+        T!["-"],
+        T!["!"],
+        DISCARD_IDENT,
+        U_IDENT,
+        INTEGER,
+        FLOAT,
+        STRING,
+        T!["#"],
+        T!["["],
+        T!["<<"],
+        T!["{"],
+        T!["case"],
+        T!["fn"],
+        T!["echo"],
+        T!["todo"],
+        T!["panic"],
+    ]);
+
+    /// Expressions allowed in constants, which are a subset of `EXPR_START`.
+    pub const CONST_EXPR_START: Self = Self::new(&[
+        IDENT,
+        U_IDENT,
+        INTEGER,
+        FLOAT,
+        STRING,
+        T!["#"],
+        T!["["],
+        T!["<<"],
+        T!["-"],
+    ]);
+
+    pub const PATTERN_START: Self = Self::new(&[
+        IDENT,
+        DISCARD_IDENT,
+        U_IDENT,
+        INTEGER,
+        FLOAT,
+        STRING,
+        T!["#"],
+        T!["["],
+        T!["<<"],
+    ]);
+
+    pub const TYPE_START: Self = Self::new(&[T!["fn"], T!["#"], IDENT, U_IDENT]);
+
+    /// Closing and separating tokens, which end whatever syntax is being recovered from.
+    pub const SEPARATORS: Self = Self::new(&[T![")"], T!["]"], T!["}"], T!["="], T![","]]);
+
+    pub const fn new(kinds: &[SyntaxKind]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < kinds.len() {
+            bits |= mask(kinds[i]);
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Whether `kind` is in the set. Nodes never are.
+    pub const fn contains(self, kind: SyntaxKind) -> bool {
+        (kind as u16) < 128 && self.0 & mask(kind) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The contained kinds, in the order of their definition.
+    pub fn iter(self) -> impl Iterator<Item = SyntaxKind> {
+        (0..SyntaxKind::ERROR as u16)
+            .map(|raw| SyntaxKind::from(rowan::SyntaxKind(raw)))
+            .filter(move |&kind| self.contains(kind))
+    }
+}
+
+const fn mask(kind: SyntaxKind) -> u128 {
+    // Only tokens are ever expected, and they are all defined before nodes.
+    assert!((kind as u16) < 128);
+    1 << kind as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokenSet;
+
+    #[test]
+    fn iter() {
+        let set = TokenSet::new(&[T![")"], T![","]]).union(TokenSet::new(&[T!["("]]));
+        assert!(set.contains(T![","]));
+        assert!(!set.contains(T!["["]));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [T!["("], T![")"], T![","]]);
+        assert!(TokenSet::EMPTY.is_empty());
+    }
+
+    #[test]
+    fn starts() {
+        for kind in TokenSet::CONST_EXPR_START.iter() {
+            assert!(TokenSet::EXPR_START.contains(kind), "{kind:?}");
+        }
+        assert!(TokenSet::ITEM_START.contains(T!["fn"]));
+        assert!(TokenSet::TYPE_START.contains(T!["fn"]));
+        assert!(TokenSet::EXPR_START.contains(T!["fn"]));
+        assert!(!TokenSet::CONST_EXPR_START.contains(T!["fn"]));
+        assert!(!TokenSet::PATTERN_START.contains(T!["{"]));
+    }
+}
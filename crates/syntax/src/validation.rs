@@ -0,0 +1,204 @@
+//! Checks of constraints the grammar cannot express, run after parsing. The parser accepts
+//! these mistakes and builds the tree as usual, so only errors are added here.
+use crate::ast::{self, AstNode, BitArrayOptionKind as Kind};
+use crate::SyntaxKind::*;
+use crate::{match_ast, ErrorKind, ParseError, SyntaxNode, TextRange, TokenSet};
+
+pub(crate) fn validate(root: &SyntaxNode, errors: &mut Vec<ParseError>) {
+    for node in root.descendants() {
+        match_ast! {
+            match node {
+                ast::ListPattern(it) => validate_list_pattern(&it, errors),
+                ast::ConstructorPattern(it) => validate_constructor_pattern(&it, errors),
+                ast::ArgList(it) => validate_arg_list(&it, errors),
+                ast::UnqualifiedImport(it) => validate_unqualified_import(&it, errors),
+                ast::BitArraySegment(it) => validate_bit_array_segment(&it, errors),
+                _ => {},
+            }
+        }
+    }
+}
+
+fn error(errors: &mut Vec<ParseError>, range: TextRange, kind: ErrorKind) {
+    errors.push(ParseError {
+        range,
+        kind,
+        expected: TokenSet::EMPTY,
+    });
+}
+
+fn validate_list_pattern(list: &ast::ListPattern, errors: &mut Vec<ParseError>) {
+    let mut rest = None;
+    for child in list.syntax().children() {
+        match child.kind() {
+            LIST_REST => rest = rest.or(Some(child)),
+            _ if ast::Pattern::can_cast(child.kind()) => {
+                if let Some(rest) = rest.take() {
+                    error(errors, rest.text_range(), ErrorKind::SpreadNotLast);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validate_constructor_pattern(pat: &ast::ConstructorPattern, errors: &mut Vec<ParseError>) {
+    let mut spread = None;
+    for child in pat.syntax().children_with_tokens() {
+        match child.kind() {
+            T![".."] => spread = spread.or(Some(child.text_range())),
+            PATTERN_ARG => {
+                if let Some(range) = spread.take() {
+                    error(errors, range, ErrorKind::SpreadNotLast);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validate_arg_list(args: &ast::ArgList, errors: &mut Vec<ParseError>) {
+    let mut after_arg = false;
+    for child in args.syntax().children() {
+        match child.kind() {
+            ARG => after_arg = true,
+            RECORD_SPREAD if after_arg => {
+                error(errors, child.text_range(), ErrorKind::RecordSpreadNotFirst);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validate_unqualified_import(import: &ast::UnqualifiedImport, errors: &mut Vec<ParseError>) {
+    let extra_alias = import
+        .syntax()
+        .children_with_tokens()
+        .filter(|it| it.kind() == T!["as"])
+        .nth(1);
+    if let Some(alias) = extra_alias {
+        let range = TextRange::new(
+            alias.text_range().start(),
+            import.syntax().text_range().end(),
+        );
+        error(errors, range, ErrorKind::DuplicateAlias);
+    }
+}
+
+fn validate_bit_array_segment(segment: &ast::BitArraySegment, errors: &mut Vec<ParseError>) {
+    let options = segment
+        .options()
+        .filter_map(|opt| Some((opt.kind()?, opt.syntax().text_range())))
+        .collect::<Vec<_>>();
+    let mut endianness = None;
+    for (i, &(kind, range)) in options.iter().enumerate() {
+        if options[..i].iter().any(|&(prev, _)| prev == kind) {
+            error(errors, range, ErrorKind::DuplicateBitArrayOption);
+        } else if matches!(kind, Kind::Big | Kind::Little | Kind::Native)
+            && endianness.replace(kind).is_some()
+        {
+            error(errors, range, ErrorKind::ConflictingEndianness);
+        }
+    }
+
+    let has = |kinds: &[Kind]| options.iter().any(|(kind, _)| kinds.contains(kind));
+    let sizes = options.iter().filter(|(kind, _)| *kind == Kind::Size);
+    if has(&[
+        Kind::Utf8,
+        Kind::Utf16,
+        Kind::Utf32,
+        Kind::Utf8Codepoint,
+        Kind::Utf16Codepoint,
+        Kind::Utf32Codepoint,
+    ]) {
+        for &(_, range) in sizes {
+            error(errors, range, ErrorKind::SizedUtfSegment);
+        }
+    } else if sizes.count() == 0 && has(&[Kind::Bytes, Kind::Bits]) {
+        // Only patterns need to know where an unsized segment ends.
+        let in_pattern = segment
+            .syntax()
+            .first_child()
+            .map_or(false, |it| !ast::Expr::can_cast(it.kind()));
+        let is_last = segment
+            .syntax()
+            .next_sibling()
+            .map_or(true, |it| it.kind() != BIT_ARRAY_SEGMENT);
+        if in_pattern && !is_last {
+            for &(kind, range) in &options {
+                if matches!(kind, Kind::Bytes | Kind::Bits) {
+                    error(errors, range, ErrorKind::UnsizedSegmentNotLast);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_file, ErrorKind};
+
+    #[track_caller]
+    fn check(src: &str, expect: &[(ErrorKind, &str)]) {
+        let parse = parse_file(src);
+        let errors = parse
+            .errors()
+            .iter()
+            .map(|err| (err.kind, &src[err.range]))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, expect);
+    }
+
+    #[test]
+    fn spread() {
+        check(
+            "fn f() { case x { [..rest, a] -> 1 A(.., b) -> 2 [a, ..] -> 3 A(a, ..) -> 4 } }",
+            &[
+                (ErrorKind::SpreadNotLast, "..rest"),
+                (ErrorKind::SpreadNotLast, ".."),
+            ],
+        );
+        check(
+            "fn f() { A(a: 1, ..b) A(..b, a: 1) }",
+            &[(ErrorKind::RecordSpreadNotFirst, "..b")],
+        );
+    }
+
+    #[test]
+    fn duplicate_alias() {
+        check(
+            "import a.{m as n, M as A as B}",
+            &[(ErrorKind::DuplicateAlias, "as B")],
+        );
+        assert!(parse_file("import a.{m as n}").errors().is_empty());
+    }
+
+    #[test]
+    fn bit_array_options() {
+        check(
+            "fn f() { <<x:int-big-int, y:big-little-native, z:size(8)-8>> }",
+            &[
+                (ErrorKind::DuplicateBitArrayOption, "int"),
+                (ErrorKind::ConflictingEndianness, "little"),
+                (ErrorKind::ConflictingEndianness, "native"),
+                (ErrorKind::DuplicateBitArrayOption, "8"),
+            ],
+        );
+        check(
+            "fn f() { <<\"a\":utf8-size(8), c:utf32_codepoint>> }",
+            &[(ErrorKind::SizedUtfSegment, "size(8)")],
+        );
+    }
+
+    #[test]
+    fn unsized_segment() {
+        check(
+            "fn f() { let <<a:bytes, b:bits-size(4), c:bytes>> = x <<a:bytes, b:bytes>> }",
+            &[(ErrorKind::UnsizedSegmentNotLast, "bytes")],
+        );
+        check(
+            "fn f() { case x { <<_:bytes, 1>> -> 1 } }",
+            &[(ErrorKind::UnsizedSegmentNotLast, "bytes")],
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! Generate the typed AST of the syntax crate from `gleam.ungrammar`.
+//!
+//! Every non-terminal becomes a node struct with one accessor per part, or an enum if it is
+//! an alternation of other non-terminals. Accessors that need more than picking a child are
+//! listed in `MANUAL_FIELDS` and written by hand in `ast.rs`.
+
+use crate::project_root;
+use crate::ungrammar::{self, Grammar, Rule};
+use std::fmt::Write;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::{fs, io};
+
+/// Nodes which are written by hand, since they do not map to one syntax kind each.
+const MANUAL_NODES: &[&str] = &["Stmt"];
+
+/// Accessors which are written by hand, as `(node, field)`.
+const MANUAL_FIELDS: &[(&str, &str)] = &[
+    // Bit arrays are patterns as well, so only look after the `=`.
+    ("LetStatement", "value"),
+    // The container can be a `NameRef` too, so only look after the `.`.
+    ("FieldAccess", "label"),
+    // Both are `Name`s, and the label is only there if both are.
+    ("Param", "label"),
+    ("Param", "name"),
+    // Skips the `CaseGuard` node.
+    ("CaseClause", "guard"),
+    // Skips the `LiteralPattern` node.
+    ("ConcatPattern", "prefix"),
+    // Either qualified or not.
+    ("ConstructorPattern", "constructor"),
+];
+
+pub fn run() -> Result<(), String> {
+    let (path, text) = generate()?;
+    fs::write(&path, text).map_err(|err| format!("Cannot write {}: {err}", path.display()))?;
+    println!("Generated {}", path.display());
+    Ok(())
+}
+
+/// The path of the generated file and its expected content.
+fn generate() -> Result<(PathBuf, String), String> {
+    let syntax_dir = project_root().join("crates/syntax");
+    let src = fs::read_to_string(syntax_dir.join("gleam.ungrammar"))
+        .map_err(|err| format!("Cannot read gleam.ungrammar: {err}"))?;
+    let grammar = ungrammar::parse(&src).map_err(|err| err.to_string())?;
+    let text =
+        reformat(&render(&lower(&grammar))).map_err(|err| format!("Cannot run rustfmt: {err}"))?;
+    Ok((syntax_dir.join("src/ast/generated.rs"), text))
+}
+
+enum AstItem {
+    Node {
+        name: String,
+        fields: Vec<Field>,
+        traits: Vec<&'static str>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<String>,
+    },
+}
+
+struct Field {
+    name: String,
+    ty: FieldTy,
+    many: bool,
+    /// The position among the preceding children of the same type.
+    nth: usize,
+    manual: bool,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum FieldTy {
+    Node(String),
+    Token(String),
+}
+
+fn lower(grammar: &Grammar) -> Vec<AstItem> {
+    let mut items = grammar
+        .nodes
+        .iter()
+        .filter(|node| !MANUAL_NODES.contains(&node.name.as_str()))
+        .map(|node| {
+            let name = node.name.clone();
+            if let Some(variants) = enum_variants(&node.rule) {
+                return AstItem::Enum { name, variants };
+            }
+            let mut fields = Vec::new();
+            collect_fields(&node.rule, None, false, &mut fields);
+            for field in &mut fields {
+                field.manual = MANUAL_FIELDS.contains(&(name.as_str(), field.name.as_str()));
+            }
+            let traits = node_traits(&node.rule, &fields);
+            AstItem::Node {
+                name,
+                fields,
+                traits,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let enums = items
+        .iter()
+        .filter_map(|item| match item {
+            AstItem::Enum { name, variants } => Some((name.clone(), variants.clone())),
+            AstItem::Node { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    // A child of a variant type is a child of its enum type as well.
+    let counts_as = |prev: &FieldTy, ty: &FieldTy| match (prev, ty) {
+        (FieldTy::Node(prev), FieldTy::Node(ty)) => {
+            prev == ty
+                || enums
+                    .iter()
+                    .any(|(name, variants)| name == ty && variants.contains(prev))
+        }
+        _ => prev == ty,
+    };
+    for item in &mut items {
+        let AstItem::Node { name, fields, .. } = item else {
+            continue;
+        };
+        for i in 0..fields.len() {
+            let (prev, rest) = fields.split_at_mut(i);
+            let field = &mut rest[0];
+            let same = prev.iter().filter(|prev| counts_as(&prev.ty, &field.ty));
+            assert!(
+                field.many || same.clone().all(|prev| !prev.many),
+                "{name}::{} follows a repeated child of the same type",
+                field.name,
+            );
+            field.nth = same.count();
+        }
+    }
+
+    items.sort_by(|a, b| item_name(a).cmp(item_name(b)));
+    items
+}
+
+fn item_name(item: &AstItem) -> &str {
+    match item {
+        AstItem::Node { name, .. } | AstItem::Enum { name, .. } => name,
+    }
+}
+
+/// The traits of `ast.rs` which a node implements, as decided by the shape of its rule.
+fn node_traits(rule: &Rule, fields: &[Field]) -> Vec<&'static str> {
+    let has_field = |name: &str, ty: &str, many: bool| {
+        fields.iter().any(|field| {
+            field.name == name
+                && field.ty == FieldTy::Node(ty.to_owned())
+                && field.many == many
+                && !field.manual
+        })
+    };
+    let mut traits = Vec::new();
+    if has_field("attributes", "Attribute", true) {
+        traits.push("HasAttributes");
+    }
+    if has_field("name", "Name", false) {
+        traits.push("HasName");
+    }
+    if has_token(rule, "pub") {
+        traits.push("HasVisibility");
+    }
+    traits
+}
+
+fn has_token(rule: &Rule, tok: &str) -> bool {
+    match rule {
+        Rule::Token(it) => it == tok,
+        Rule::Node(_) => false,
+        Rule::Labeled { rule, .. } | Rule::Opt(rule) | Rule::Rep(rule) => has_token(rule, tok),
+        Rule::Seq(rules) | Rule::Alt(rules) => rules.iter().any(|rule| has_token(rule, tok)),
+    }
+}
+
+fn enum_variants(rule: &Rule) -> Option<Vec<String>> {
+    let Rule::Alt(alts) = rule else {
+        return None;
+    };
+    alts.iter()
+        .map(|alt| match alt {
+            Rule::Node(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn collect_fields(rule: &Rule, label: Option<&str>, many: bool, fields: &mut Vec<Field>) {
+    let (name, ty) = match rule {
+        Rule::Labeled { label, rule } => return collect_fields(rule, Some(label), many, fields),
+        Rule::Seq(rules) | Rule::Alt(rules) => {
+            for rule in rules {
+                collect_fields(rule, label, many, fields);
+            }
+            return;
+        }
+        Rule::Opt(rule) => return collect_fields(rule, label, many, fields),
+        Rule::Rep(rule) => return collect_fields(rule, label, true, fields),
+        Rule::Node(ty) => {
+            let name = match label {
+                Some(label) => label.to_owned(),
+                None if many => format!("{}s", to_snake_case(ty)),
+                None => to_snake_case(ty),
+            };
+            (name, FieldTy::Node(ty.clone()))
+        }
+        // Only labelled tokens get an accessor.
+        Rule::Token(tok) => match label {
+            Some(label) => (label.to_owned(), FieldTy::Token(tok.clone())),
+            None => return,
+        },
+    };
+    if let Some(prev) = fields.iter_mut().find(|prev| prev.name == name) {
+        assert!(prev.ty == ty, "`{name}` is used for different types");
+        prev.many |= many;
+        return;
+    }
+    fields.push(Field {
+        name,
+        ty,
+        many,
+        nth: 0,
+        manual: false,
+    });
+}
+
+fn render(items: &[AstItem]) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "//! Generated by `cargo xtask codegen` from `gleam.ungrammar`, do not edit by hand.\n\n",
+    );
+    writeln!(
+        out,
+        "use crate::ast::{{AstChildren, AstNode, HasAttributes, HasName, HasVisibility, {}}};",
+        MANUAL_NODES.join(", ")
+    )
+    .unwrap();
+    out.push_str("use crate::SyntaxKind::{self, *};\n");
+    out.push_str("use crate::{GleamLanguage, SyntaxNode, SyntaxToken};\n");
+    out.push_str("use rowan::ast::support;\n");
+
+    for item in items {
+        out.push('\n');
+        match item {
+            AstItem::Node {
+                name,
+                fields,
+                traits,
+            } => render_node(&mut out, name, fields, traits),
+            AstItem::Enum { name, variants } => render_enum(&mut out, name, variants),
+        }
+    }
+    out
+}
+
+fn render_node(out: &mut String, name: &str, fields: &[Field], traits: &[&str]) {
+    let kind = to_upper_snake_case(name);
+    writeln!(out, "#[derive(Clone, Debug, PartialEq, Eq, Hash)]").unwrap();
+    writeln!(out, "pub struct {name}(pub(crate) SyntaxNode);\n").unwrap();
+
+    let fields = fields
+        .iter()
+        .filter(|field| !field.manual)
+        .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        writeln!(out, "impl {name} {{").unwrap();
+        for field in fields {
+            let fname = &field.name;
+            match (&field.ty, field.many) {
+                (FieldTy::Node(ty), true) => writeln!(
+                    out,
+                    "pub fn {fname}(&self) -> AstChildren<{ty}> {{ support::children(&self.0) }}"
+                ),
+                (FieldTy::Node(ty), false) if field.nth == 0 => writeln!(
+                    out,
+                    "pub fn {fname}(&self) -> Option<{ty}> {{ support::child(&self.0) }}"
+                ),
+                (FieldTy::Node(ty), false) => writeln!(
+                    out,
+                    "pub fn {fname}(&self) -> Option<{ty}> {{ support::children(&self.0).nth({}) }}",
+                    field.nth
+                ),
+                (FieldTy::Token(tok), false) if field.nth == 0 => writeln!(
+                    out,
+                    "pub fn {fname}(&self) -> Option<SyntaxToken> {{ support::token(&self.0, {}) }}",
+                    token_kind(tok)
+                ),
+                (FieldTy::Token(tok), false) => writeln!(
+                    out,
+                    "pub fn {fname}(&self) -> Option<SyntaxToken> {{
+                        self.0
+                            .children_with_tokens()
+                            .filter_map(|it| it.into_token())
+                            .filter(|it| it.kind() == {})
+                            .nth({})
+                    }}",
+                    token_kind(tok),
+                    field.nth
+                ),
+                (FieldTy::Token(_), true) => panic!("{name}::{fname} is a repeated token"),
+            }
+            .unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    writeln!(
+        out,
+        "impl AstNode for {name} {{
+            type Language = GleamLanguage;
+
+            fn can_cast(kind: SyntaxKind) -> bool {{
+                kind == {kind}
+            }}
+
+            fn cast(node: SyntaxNode) -> Option<Self> {{
+                if Self::can_cast(node.kind()) {{
+                    Some(Self(node))
+                }} else {{
+                    None
+                }}
+            }}
+
+            fn syntax(&self) -> &SyntaxNode {{
+                &self.0
+            }}
+        }}"
+    )
+    .unwrap();
+    for tr in traits {
+        writeln!(out, "\nimpl {tr} for {name} {{}}").unwrap();
+    }
+}
+
+fn render_enum(out: &mut String, name: &str, variants: &[String]) {
+    writeln!(out, "#[derive(Clone, Debug, PartialEq, Eq, Hash)]").unwrap();
+    writeln!(out, "pub enum {name} {{").unwrap();
+    for variant in variants {
+        writeln!(out, "{variant}({variant}),").unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    let kinds = variants
+        .iter()
+        .map(|variant| to_upper_snake_case(variant))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let casts = variants
+        .iter()
+        .map(|variant| {
+            format!(
+                "{} => Self::{variant}({variant}(node)),",
+                to_upper_snake_case(variant)
+            )
+        })
+        .collect::<String>();
+    let syntaxes = variants
+        .iter()
+        .map(|variant| format!("Self::{variant}(it) => &it.0,"))
+        .collect::<String>();
+    writeln!(
+        out,
+        "impl AstNode for {name} {{
+            type Language = GleamLanguage;
+
+            fn can_cast(kind: SyntaxKind) -> bool {{
+                matches!(kind, {kinds})
+            }}
+
+            fn cast(node: SyntaxNode) -> Option<Self> {{
+                let ret = match node.kind() {{
+                    {casts}
+                    _ => return None,
+                }};
+                Some(ret)
+            }}
+
+            fn syntax(&self) -> &SyntaxNode {{
+                match self {{
+                    {syntaxes}
+                }}
+            }}
+        }}"
+    )
+    .unwrap();
+}
+
+/// Tokens in all caps are token kinds, others are matched by their text.
+fn token_kind(tok: &str) -> String {
+    if tok.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+        tok.to_owned()
+    } else {
+        format!("T![{tok:?}]")
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut ret = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i != 0 {
+            ret.push('_');
+        }
+        ret.push(c.to_ascii_lowercase());
+    }
+    ret
+}
+
+fn to_upper_snake_case(name: &str) -> String {
+    to_snake_case(name).to_ascii_uppercase()
+}
+
+fn reformat(text: &str) -> io::Result<String> {
+    let mut rustfmt = Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    rustfmt.stdin.take().unwrap().write_all(text.as_bytes())?;
+    let output = rustfmt.wait_with_output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(io::ErrorKind::Other, "rustfmt failed"));
+    }
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+    use std::fs;
+
+    #[test]
+    fn generated_ast_is_fresh() {
+        let (path, expect) = generate().unwrap();
+        let actual = fs::read_to_string(&path).unwrap();
+        assert!(
+            actual == expect,
+            "{} is out of date, run `cargo xtask codegen`",
+            path.display()
+        );
+    }
+}
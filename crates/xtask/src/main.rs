@@ -0,0 +1,32 @@
+mod codegen;
+mod ungrammar;
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let task = env::args().nth(1);
+    let ret = match task.as_deref() {
+        Some("codegen") => codegen::run(),
+        _ => {
+            eprintln!("Usage: cargo xtask codegen");
+            return ExitCode::FAILURE;
+        }
+    };
+    match ret {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn project_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .unwrap()
+        .to_path_buf()
+}
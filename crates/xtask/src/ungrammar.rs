@@ -0,0 +1,273 @@
+//! A parser for the subset of the ungrammar format used by `gleam.ungrammar`.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub struct Grammar {
+    pub nodes: Vec<NodeDef>,
+}
+
+#[derive(Debug)]
+pub struct NodeDef {
+    pub name: String,
+    pub rule: Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    Labeled { label: String, rule: Box<Rule> },
+    Node(String),
+    Token(String),
+    Seq(Vec<Rule>),
+    Alt(Vec<Rule>),
+    Opt(Box<Rule>),
+    Rep(Box<Rule>),
+}
+
+#[derive(Debug)]
+pub struct Error {
+    line: usize,
+    msg: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gleam.ungrammar:{}: {}", self.line, self.msg)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Ident(String),
+    Token(String),
+    Eq,
+    Pipe,
+    Star,
+    QMark,
+    Colon,
+    LParen,
+    RParen,
+}
+
+pub fn parse(src: &str) -> Result<Grammar, Error> {
+    let mut p = Parser {
+        tokens: lex(src)?,
+        pos: 0,
+    };
+    let mut nodes = Vec::new();
+    while p.pos < p.tokens.len() {
+        let name = match p.bump() {
+            Some(Tok::Ident(name)) => name,
+            _ => return Err(p.error("expected a node definition")),
+        };
+        if p.bump() != Some(Tok::Eq) {
+            return Err(p.error("expected `=`"));
+        }
+        let rule = p.rule()?;
+        nodes.push(NodeDef { name, rule });
+    }
+    for node in &nodes {
+        check_refs(&nodes, &node.rule).map_err(|msg| Error { line: 0, msg })?;
+    }
+    Ok(Grammar { nodes })
+}
+
+fn check_refs(nodes: &[NodeDef], rule: &Rule) -> Result<(), String> {
+    match rule {
+        Rule::Node(name) if !nodes.iter().any(|node| node.name == *name) => {
+            Err(format!("undefined node `{name}`"))
+        }
+        Rule::Node(_) | Rule::Token(_) => Ok(()),
+        Rule::Labeled { rule, .. } | Rule::Opt(rule) | Rule::Rep(rule) => check_refs(nodes, rule),
+        Rule::Seq(rules) | Rule::Alt(rules) => {
+            rules.iter().try_for_each(|rule| check_refs(nodes, rule))
+        }
+    }
+}
+
+fn lex(src: &str) -> Result<Vec<(usize, Tok)>, Error> {
+    let mut ret = Vec::new();
+    for (i, line) in src.lines().enumerate() {
+        let line_no = i + 1;
+        let mut chars = line.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let tok = match c {
+                _ if c.is_whitespace() => continue,
+                '/' if line[start..].starts_with("//") => break,
+                '=' => Tok::Eq,
+                '|' => Tok::Pipe,
+                '*' => Tok::Star,
+                '?' => Tok::QMark,
+                ':' => Tok::Colon,
+                '(' => Tok::LParen,
+                ')' => Tok::RParen,
+                '\'' => {
+                    let mut text = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '\\')) => text.extend(chars.next().map(|(_, c)| c)),
+                            Some((_, '\'')) => break,
+                            Some((_, c)) => text.push(c),
+                            None => {
+                                return Err(Error {
+                                    line: line_no,
+                                    msg: "unterminated token".into(),
+                                })
+                            }
+                        }
+                    }
+                    Tok::Token(text)
+                }
+                _ if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut text = c.to_string();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if !c.is_ascii_alphanumeric() && c != '_' {
+                            break;
+                        }
+                        text.push(c);
+                        chars.next();
+                    }
+                    Tok::Ident(text)
+                }
+                _ => {
+                    return Err(Error {
+                        line: line_no,
+                        msg: format!("unexpected character `{c}`"),
+                    })
+                }
+            };
+            ret.push((line_no, tok));
+        }
+    }
+    Ok(ret)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Tok)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Tok> {
+        self.tokens.get(self.pos + n).map(|(_, tok)| tok)
+    }
+
+    fn bump(&mut self) -> Option<Tok> {
+        let tok = self.tokens.get(self.pos).map(|(_, tok)| tok.clone());
+        self.pos += 1;
+        tok
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        let line = self
+            .tokens
+            .get(self.pos.saturating_sub(1))
+            .map_or(0, |(line, _)| *line);
+        Error {
+            line,
+            msg: msg.to_owned(),
+        }
+    }
+
+    /// Whether the next tokens start the definition of another node.
+    fn at_node_def(&self) -> bool {
+        matches!(self.peek(), Some(Tok::Ident(_))) && self.peek_nth(1) == Some(&Tok::Eq)
+    }
+
+    fn rule(&mut self) -> Result<Rule, Error> {
+        if self.peek() == Some(&Tok::Pipe) {
+            self.bump();
+        }
+        let mut alts = vec![self.seq()?];
+        while self.peek() == Some(&Tok::Pipe) {
+            self.bump();
+            alts.push(self.seq()?);
+        }
+        Ok(if alts.len() == 1 {
+            alts.pop().unwrap()
+        } else {
+            Rule::Alt(alts)
+        })
+    }
+
+    fn seq(&mut self) -> Result<Rule, Error> {
+        let mut seq = Vec::new();
+        while !self.at_node_def() {
+            match self.peek() {
+                Some(Tok::Ident(_) | Tok::Token(_) | Tok::LParen) => seq.push(self.postfix()?),
+                _ => break,
+            }
+        }
+        match seq.len() {
+            0 => Err(self.error("expected a rule")),
+            1 => Ok(seq.pop().unwrap()),
+            _ => Ok(Rule::Seq(seq)),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Rule, Error> {
+        let mut rule = self.atom()?;
+        loop {
+            rule = match self.peek() {
+                Some(Tok::Star) => Rule::Rep(Box::new(rule)),
+                Some(Tok::QMark) => Rule::Opt(Box::new(rule)),
+                _ => return Ok(rule),
+            };
+            self.bump();
+        }
+    }
+
+    fn atom(&mut self) -> Result<Rule, Error> {
+        match self.bump() {
+            Some(Tok::Ident(label)) if self.peek() == Some(&Tok::Colon) => {
+                self.bump();
+                let rule = Box::new(self.atom()?);
+                Ok(Rule::Labeled { label, rule })
+            }
+            Some(Tok::Ident(name)) => Ok(Rule::Node(name)),
+            Some(Tok::Token(text)) => Ok(Rule::Token(text)),
+            Some(Tok::LParen) => {
+                let rule = self.rule()?;
+                if self.bump() != Some(Tok::RParen) {
+                    return Err(self.error("expected `)`"));
+                }
+                Ok(rule)
+            }
+            _ => Err(self.error("expected a rule")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Rule};
+
+    #[test]
+    fn rules() {
+        let grammar = parse("A = 'a' b:B* | (C)?\nB = 'x' // comment\nC = 'IDENT'").unwrap();
+        let names = grammar
+            .nodes
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["A", "B", "C"]);
+        assert_eq!(
+            grammar.nodes[0].rule,
+            Rule::Alt(vec![
+                Rule::Seq(vec![
+                    Rule::Token("a".into()),
+                    Rule::Rep(Box::new(Rule::Labeled {
+                        label: "b".into(),
+                        rule: Box::new(Rule::Node("B".into())),
+                    })),
+                ]),
+                Rule::Opt(Box::new(Rule::Node("C".into()))),
+            ])
+        );
+        assert!(parse("A = B").is_err());
+    }
+}